@@ -38,6 +38,7 @@ fn main() {
             "get_font",
             "scan_java_installations",
             "get_java_version",
+            "install_java_runtime",
             "front_launch_game",
             "front_stop_game",
             "front_get_launch_games",
@@ -46,6 +47,12 @@ fn main() {
             "front_get_launch_config",
             "front_update_launch_config",
             "front_get_game_log",
+            "front_get_game_resources",
+            "front_get_crash_details",
+            "front_get_launch_history",
+            "front_search_game_log",
+            "front_check_launch",
+            "front_preview_launch",
             "get_version_manifest",
             "get_version_detail",
             "get_version_download_manifest",
@@ -55,6 +62,7 @@ fn main() {
             "get_download_task",
             "cancel_download",
             "clear_completed_tasks",
+            "change_game_loader",
             "get_game_versions",
             "get_download_base_path",
             "set_download_base_path",
@@ -66,10 +74,11 @@ fn main() {
             "get_fabric_version_detail",
             "build_fabric_launch_config",
             "get_forge_versions",
-            "build_forge_launch_config",
             "get_installed_mod_loaders",
             "get_neoforge_versions",
             "get_optifine_versions",
+            "get_quilt_versions",
+            "get_legacy_fabric_versions",
             "get_disk_free_space",
             "scan_instances",
             "get_instance",
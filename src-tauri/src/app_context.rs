@@ -130,7 +130,7 @@ impl AppContext {
         game_dir.join(format!("{}.jar", version_id))
     }
 
-    /// 特定游戏的 natives 解压目录：{root}/natives
+    /// natives 解压根目录：{root}/natives（启动时按原生库集合哈希分子目录）
     pub fn natives_dir(&self) -> PathBuf {
         self.game_root().join("natives")
     }
@@ -186,11 +186,31 @@ impl AppContext {
         self.wecraft_data_dir().join(".wecraft.json")
     }
 
+    /// 用户崩溃诊断规则：{work_dir}/.wecraft/crash_rules.json
+    pub fn crash_rules_path(&self) -> PathBuf {
+        self.wecraft_data_dir().join("crash_rules.json")
+    }
+
+    /// 启动会话历史：{work_dir}/.wecraft/launch_history.jsonl
+    pub fn launch_history_path(&self) -> PathBuf {
+        self.wecraft_data_dir().join("launch_history.jsonl")
+    }
+
+    /// 启动器私有临时目录：{work_dir}/.wecraft/tmp（启动参数文件、安装器工作目录等）
+    pub fn wecraft_temp_dir(&self) -> PathBuf {
+        self.wecraft_data_dir().join("tmp")
+    }
+
     /// 日志目录：{work_dir}/logs
     pub fn launcher_logs_dir(&self) -> PathBuf {
         self.launcher_work_dir.join("logs")
     }
 
+    /// 游戏输出日志目录：{work_dir}/logs/games/{name}
+    pub fn game_logs_dir(&self, game_name: &str) -> PathBuf {
+        self.launcher_logs_dir().join("games").join(game_name)
+    }
+
     /// 确保基础目录存在（幂等）
     pub fn ensure_dirs(&self) -> Result<(), String> {
         for dir in [&self.game_root(), &self.wecraft_data_dir()] {
@@ -4,12 +4,13 @@ use crate::download::manager::DownloadManager;
 use crate::download::models::DownloadTask;
 use crate::download::{
     DownloadOptions, DownloadResult, VERSION_MANIFEST_URL, VersionManifest,
-    extract_jar, fetch_version_value, parse_version_json,
+    fetch_version_value, parse_version_json,
 };
-use crate::{GameManager, log_info};
+use crate::game::models::{Game, OptiFineInstall, OptiFineMode};
+use crate::modloader::ModLoaderType;
+use crate::{GameManager, log_info, log_warn};
 use std::fs;
 use std::sync::Arc;
-use std::time::Instant;
 use tauri::{Emitter, State};
 
 /// 获取所有下载任务列表
@@ -116,9 +117,25 @@ pub async fn download(
     let game_name = options.game_name.clone();
     let version_id = options.version_id.clone();
     let dm = download_manager.inner().clone();
-    let cancel_token = dm.register_cancellation(&version_id);
+    let cancel_token = dm
+        .try_register_game(&game_name)
+        .ok_or_else(|| format!("游戏 {} 正在下载或更换加载器，请稍后再试", game_name))?;
+    // 同一令牌再按 version_id 登记，供 cancel_version_download 取消
+    dm.cancellations
+        .lock()
+        .unwrap()
+        .insert(version_id.clone(), cancel_token.clone());
 
     let result: Result<DownloadResult, String> = async {
+    // OptiFine 以模组形式组合时，加载器必须支持（在创建游戏前校验）
+    let optifine_mod = options
+        .optifine_version
+        .clone()
+        .filter(|_| options.loader_type != ModLoaderType::OptiFine);
+    if optifine_mod.is_some() {
+        super::optifine::check_combination(options.loader_type)?;
+    }
+
 let game_path = game_manager
         .create_game(
             &options.game_name,
@@ -142,6 +159,33 @@ let game_path = game_manager
     .map_err(|e| format!("解析版本 JSON 失败: {}", e))?;
     let manifest = parse_version_json(&version_detail).await?;
 
+    // 加载器 profile 在统计总量前拉取，其依赖库计入整体进度；
+    // 加载器步骤失败不中断原版部署，结束时报错，游戏因缺少 profile 被标记为损坏
+    let mut loader_error: Option<String> = None;
+    let loader_profile = match super::loader::resolve_loader_profile(
+        &dm,
+        &app_context.libraries_dir(),
+        &options.version_id,
+        &version_detail,
+        options.loader_type,
+        options.loader_version.as_deref(),
+        Some(&cancel_token),
+    )
+    .await
+    {
+        Ok(profile) => profile,
+        Err(e) if cancel_token.is_cancelled() => return Err(e),
+        Err(e) => {
+            log_warn!("加载器 profile 获取失败: {}", e);
+            loader_error = Some(e);
+            None
+        }
+    };
+    let loader_libraries = loader_profile
+        .as_ref()
+        .map(|p| p.libraries.as_slice())
+        .unwrap_or_default();
+
     let total_libraries = manifest.libraries.len();
     let total_assets = manifest.assets.len();
     let total_natives = manifest.natives.len();
@@ -151,6 +195,7 @@ let game_path = game_manager
     let total_files = total_libraries
         + total_assets
         + total_natives
+        + loader_libraries.len()
         + if has_client_jar { 1 } else { 0 }
         + if has_log_config { 1 } else { 0 };
 
@@ -159,6 +204,7 @@ let game_path = game_manager
         .iter()
         .chain(manifest.assets.iter())
         .chain(manifest.natives.iter())
+        .chain(loader_libraries.iter())
         .map(|f| f.size)
         .sum::<u64>()
         + manifest.client_jar.as_ref().map_or(0, |f| f.size)
@@ -235,29 +281,83 @@ let game_path = game_manager
         )
         .await?;
     }
-    // 如果有 natives 的话
-    if !manifest.natives.is_empty() {
-        let extract_start = Instant::now();
-        // 解压原生库到 natives 目录
-        let natives_dir = app_context.natives_dir();
-        fs::create_dir_all(&natives_dir).map_err(|e| format!("创建 natives 目录失败: {}", e))?;
-        for native in &manifest.natives {
-            if cancel_token.is_cancelled() {
-                return Err("下载已取消".to_string());
+    // 原生库不在此解压：启动时按版本原生库集合准备独立的 natives 目录
+
+    // ====== Phase 2.5: 加载器依赖库 + 安装器处理器 + 继承版本 JSON（{game_dir}/{profile_id}.json） ======
+    // profile 仅在依赖库与处理器产物全部就绪后写入，目录内存在 profile 即代表加载器安装完整
+    if let Some(ref profile) = loader_profile {
+        let installed = match dm
+            .download_group_with_progress(
+                &profile.libraries,
+                &app_context.libraries_dir(),
+                "downloading_loader",
+                &tracker,
+                &mut completed,
+                Some(&cancel_token),
+            )
+            .await
+        {
+            Ok(()) => {
+                tracker.set_phase("installing_loader");
+                let java_major = version_detail["javaVersion"]["majorVersion"]
+                    .as_u64()
+                    .unwrap_or(8) as u32;
+                super::loader::finish_install(
+                    app_context.inner(),
+                    &game_dir,
+                    &options.version_id,
+                    java_major,
+                    profile,
+                )
+                .await
+            }
+            Err(e) if cancel_token.is_cancelled() => return Err(e),
+            Err(e) => Err(format!("下载加载器依赖库失败: {}", e)),
+        };
+        match installed {
+            Ok(()) => log_info!("已安装加载器: {}", profile.id),
+            Err(e) => {
+                log_warn!("加载器安装失败: {}", e);
+                loader_error = Some(e);
+            }
+        }
+    }
+
+    // ====== Phase 2.6: OptiFine（独立版本随 Phase 2.5 安装；与 Forge / Fabric 组合时放入 mods/） ======
+    let mut optifine = match (options.loader_type, &options.loader_version) {
+        (ModLoaderType::OptiFine, Some(version)) if loader_error.is_none() => {
+            Some(OptiFineInstall {
+                version: version.clone(),
+                mode: OptiFineMode::Standalone,
+            })
+        }
+        _ => None,
+    };
+    let mut optifine_error: Option<String> = None;
+    if let Some(ref edition) = optifine_mod {
+        tracker.set_phase("installing_optifine");
+        match super::optifine::install_as_mod(
+            &dm,
+            &game_dir,
+            &options.version_id,
+            edition,
+            options.loader_type,
+            Some(&cancel_token),
+        )
+        .await
+        {
+            Ok(()) => {
+                optifine = Some(OptiFineInstall {
+                    version: edition.clone(),
+                    mode: OptiFineMode::Mod,
+                })
+            }
+            Err(e) if cancel_token.is_cancelled() => return Err(e),
+            Err(e) => {
+                log_warn!("OptiFine 安装失败: {}", e);
+                optifine_error = Some(e);
             }
-            let jar_path = app_context.libraries_dir().join(&native.path);
-            extract_jar(
-                &jar_path,
-                &natives_dir,
-                native.extract.as_ref().and_then(|e| e.exclude.as_deref()),
-            )?;
-            log_info!("已解压原生库: {}", native.path);
         }
-        log_info!(
-            "[阶段耗时] natives 解压: {} 个原生库, 耗时 {}ms",
-            manifest.natives.len(),
-            extract_start.elapsed().as_millis()
-        );
     }
 
     // ====== Phase 3: 下载资源文件（全局共享目录） ======
@@ -324,11 +424,29 @@ let game_path = game_manager
         Err(e) => log_info!("⚠️ 完整性校验执行失败（忽略）: {}", e),
     }
 
-    let game = game_manager
+    let mut game = game_manager
         .get_game(&options.game_name)
         .ok_or_else(|| format!("游戏不存在：{}", options.game_name))?;
+    game.optifine = optifine;
 
     game_manager.save_record(&game).map_err(|e| e.to_string())?;
+    let failure = loader_error
+        .map(|e| (e.clone(), format!("加载器安装失败（游戏已标记为损坏）: {}", e)))
+        .or_else(|| optifine_error.map(|e| (e.clone(), format!("OptiFine 安装失败: {}", e))));
+    if let Some((e, message)) = failure {
+        app_handle
+            .emit(
+                "download-complete",
+                serde_json::json!({
+                    "game_name": &options.game_name,
+                    "version_id": &options.version_id,
+                    "status": "failed",
+                    "error": &e,
+                }),
+            )
+            .ok();
+        return Err(message);
+    }
     app_handle
         .emit(
             "download-complete",
@@ -355,6 +473,7 @@ let game_path = game_manager
     .await;
 
     dm.unregister_cancellation(&version_id);
+    dm.unregister_game(&game_name);
     if cancel_token.is_cancelled() {
         log_info!("下载已取消，清理游戏: {}", game_name);
         let _ = game_manager.delete_game(&game_name, true);
@@ -363,3 +482,33 @@ let game_path = game_manager
 
     result
 }
+
+/// 更换已有游戏的加载器（升级 / 降级 / 切换，loader_type 为 Vanilla 时移除加载器）；失败时回滚
+#[tauri::command]
+pub async fn change_game_loader(
+    game_name: String,
+    loader_type: ModLoaderType,
+    loader_version: Option<String>,
+    game_manager: State<'_, GameManager>,
+    download_manager: State<'_, DownloadManager>,
+    app_context: State<'_, AppContext>,
+    app_handle: tauri::AppHandle,
+) -> Result<Game, String> {
+    let dm = download_manager.inner().clone();
+    let cancel_token = dm
+        .try_register_game(&game_name)
+        .ok_or_else(|| format!("游戏 {} 正在下载或更换加载器，请稍后再试", game_name))?;
+    let result = super::swap::change_loader(
+        app_context.inner(),
+        &dm,
+        game_manager.inner(),
+        &app_handle,
+        &game_name,
+        loader_type,
+        loader_version,
+        &cancel_token,
+    )
+    .await;
+    dm.unregister_game(&game_name);
+    result
+}
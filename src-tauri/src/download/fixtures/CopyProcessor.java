@@ -0,0 +1,22 @@
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.nio.file.StandardCopyOption;
+
+/** 安装器处理器测试桩：--input 复制到 --output（父目录自动创建） */
+public class CopyProcessor {
+    public static void main(String[] args) throws Exception {
+        Path input = null;
+        Path output = null;
+        for (int i = 0; i + 1 < args.length; i += 2) {
+            if (args[i].equals("--input")) input = Paths.get(args[i + 1]);
+            if (args[i].equals("--output")) output = Paths.get(args[i + 1]);
+        }
+        if (input == null || output == null) {
+            System.err.println("usage: --input <file> --output <file>");
+            System.exit(1);
+        }
+        if (output.getParent() != null) Files.createDirectories(output.getParent());
+        Files.copy(input, output, StandardCopyOption.REPLACE_EXISTING);
+    }
+}
@@ -0,0 +1,22 @@
+package optifine;
+
+import java.io.ByteArrayOutputStream;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+
+/** OptiFine Patcher 测试桩：<mc jar> <installer> <output>，输出 = 原版 jar 内容 + "+optifine" */
+public class Patcher {
+    public static void main(String[] args) throws Exception {
+        if (args.length != 3) {
+            System.err.println("usage: <minecraft jar> <installer> <output>");
+            System.exit(1);
+        }
+        Path output = Paths.get(args[2]);
+        if (output.getParent() != null) Files.createDirectories(output.getParent());
+        ByteArrayOutputStream buf = new ByteArrayOutputStream();
+        buf.write(Files.readAllBytes(Paths.get(args[0])));
+        buf.write("+optifine".getBytes("UTF-8"));
+        Files.write(output, buf.toByteArray());
+    }
+}
@@ -0,0 +1,532 @@
+// src-tauri/src/download/forge.rs
+// Forge / NeoForge 无界面安装器：读取安装器 jar 内的 install_profile.json 与 version.json，
+// 解出内置 maven 构件，按 data 表替换参数后用所选 Java 依次执行处理器并校验输出
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::loader::{LoaderInstaller, LoaderProfile, profile_from_json};
+use crate::download::utils::verify_file_sha1;
+use crate::launch::maven_path_from_name;
+use crate::log_info;
+
+/// 安装器处理器的运行侧（客户端）
+const SIDE: &str = "client";
+
+/// Forge / NeoForge 安装器 jar
+pub struct ForgeInstaller {
+    /// 安装器 jar 路径
+    path: PathBuf,
+    /// install_profile.json（spec 1，1.13+ 安装器格式）
+    install_profile: serde_json::Value,
+    /// 安装器内置的继承版本 JSON
+    version_json: serde_json::Value,
+}
+
+impl ForgeInstaller {
+    /// 打开安装器并读取 install_profile.json 与其 `json` 字段指向的版本 JSON
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let install_profile: serde_json::Value =
+            serde_json::from_slice(&read_jar_entry(path, "install_profile.json")?)
+                .map_err(|e| format!("解析 install_profile.json 失败: {}", e))?;
+        if install_profile.get("install").is_some() || install_profile.get("versionInfo").is_some()
+        {
+            return Err("不支持 1.12.2 及更早的旧版 Forge 安装器".to_string());
+        }
+
+        let json_entry = install_profile["json"].as_str().unwrap_or("/version.json");
+        let version_json: serde_json::Value =
+            serde_json::from_slice(&read_jar_entry(path, json_entry.trim_start_matches('/'))?)
+                .map_err(|e| format!("解析安装器版本 JSON 失败: {}", e))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            install_profile,
+            version_json,
+        })
+    }
+
+    /// 生成加载器 profile：版本 JSON 原样写入，依赖库含处理器所需的安装期依赖
+    pub fn profile(&self, mc_version: &str) -> Result<LoaderProfile, String> {
+        let mut profile = profile_from_json(self.version_json.clone(), mc_version)?;
+        let install_libraries = serde_json::json!({
+            "id": "install_profile",
+            "inheritsFrom": mc_version,
+            "libraries": self.install_profile["libraries"].clone(),
+        });
+        for file in profile_from_json(install_libraries, mc_version)?.libraries {
+            if !profile.libraries.iter().any(|f| f.path == file.path) {
+                profile.libraries.push(file);
+            }
+        }
+        profile.installer = Some(LoaderInstaller::Forge(self.path.clone()));
+        Ok(profile)
+    }
+
+    /// 解出安装器内置的 maven/ 构件（通常为 Forge 本体）到 libraries 目录，已存在的跳过
+    pub fn extract_embedded_maven(&self, libraries_dir: &Path) -> Result<usize, String> {
+        let file = fs::File::open(&self.path).map_err(|e| format!("打开安装器失败: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| format!("解析安装器失败: {}", e))?;
+        let mut extracted = 0;
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| format!("读取安装器条目失败: {}", e))?;
+            if entry.is_dir() {
+                continue;
+            }
+            let Some(relative) = entry
+                .enclosed_name()
+                .and_then(|p| p.strip_prefix("maven").ok().map(Path::to_path_buf))
+            else {
+                continue;
+            };
+            let dest = libraries_dir.join(relative);
+            if dest.is_file() {
+                continue;
+            }
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            }
+            let mut out = fs::File::create(&dest).map_err(|e| format!("写入构件失败: {}", e))?;
+            std::io::copy(&mut entry, &mut out).map_err(|e| format!("写入构件失败: {}", e))?;
+            extracted += 1;
+        }
+        Ok(extracted)
+    }
+
+    /// 依次执行客户端处理器；输出已存在且 SHA1 匹配的处理器直接跳过
+    pub fn run_processors(
+        &self,
+        java: &Path,
+        libraries_dir: &Path,
+        minecraft_jar: &Path,
+        work_dir: &Path,
+    ) -> Result<(), String> {
+        let data = self.data_table(libraries_dir, minecraft_jar, work_dir)?;
+        let processors = self.install_profile["processors"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        for (idx, processor) in processors.iter().enumerate() {
+            // 未声明 sides 的处理器两端都执行
+            if processor["sides"]
+                .as_array()
+                .is_some_and(|sides| !sides.iter().any(|s| s.as_str() == Some(SIDE)))
+            {
+                continue;
+            }
+            let jar_coord = processor["jar"].as_str().ok_or("处理器缺少 jar")?;
+
+            let outputs = processor["outputs"]
+                .as_object()
+                .map(|map| {
+                    map.iter()
+                        .map(|(key, value)| {
+                            Ok((
+                                PathBuf::from(resolve_arg(key, &data, libraries_dir)?),
+                                resolve_arg(
+                                    value.as_str().unwrap_or_default(),
+                                    &data,
+                                    libraries_dir,
+                                )?,
+                            ))
+                        })
+                        .collect::<Result<Vec<_>, String>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+            if !outputs.is_empty() && outputs_match(&outputs) {
+                log_info!("[处理器 {}] 输出已存在，跳过: {}", idx + 1, jar_coord);
+                continue;
+            }
+
+            let jar = library_path(libraries_dir, jar_coord)?;
+            let main_class = jar_main_class(&jar)?;
+            let mut classpath = vec![jar];
+            for coord in processor["classpath"].as_array().into_iter().flatten() {
+                classpath.push(library_path(
+                    libraries_dir,
+                    coord.as_str().unwrap_or_default(),
+                )?);
+            }
+            let args = processor["args"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|a| resolve_arg(a.as_str().unwrap_or_default(), &data, libraries_dir))
+                .collect::<Result<Vec<_>, String>>()?;
+
+            log_info!("[处理器 {}] 执行 {} ({})", idx + 1, jar_coord, main_class);
+            let mut cmd = Command::new(java);
+            cmd.arg("-cp")
+                .arg(
+                    std::env::join_paths(&classpath)
+                        .map_err(|e| format!("拼接 classpath 失败: {}", e))?,
+                )
+                .arg(&main_class)
+                .args(&args)
+                .current_dir(work_dir);
+            #[cfg(windows)]
+            {
+                use std::os::windows::process::CommandExt;
+                cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+            }
+            let output = cmd
+                .output()
+                .map_err(|e| format!("启动处理器 {} 失败: {}", jar_coord, e))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+                return Err(format!(
+                    "处理器 {} 执行失败（{}）:\n{}",
+                    jar_coord,
+                    output.status,
+                    tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+                ));
+            }
+
+            for (path, sha1) in &outputs {
+                if !path.is_file() {
+                    return Err(format!(
+                        "处理器 {} 未生成输出: {}",
+                        jar_coord,
+                        path.display()
+                    ));
+                }
+                if !sha1.is_empty() && !verify_file_sha1(path, sha1)? {
+                    return Err(format!(
+                        "处理器 {} 输出校验失败: {}（期望 SHA1 {}）",
+                        jar_coord,
+                        path.display(),
+                        sha1
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 构建 data 表：install_profile.data 的客户端值 + 安装器内置变量
+    fn data_table(
+        &self,
+        libraries_dir: &Path,
+        minecraft_jar: &Path,
+        work_dir: &Path,
+    ) -> Result<HashMap<String, String>, String> {
+        let mut data = HashMap::new();
+        data.insert("SIDE".to_string(), SIDE.to_string());
+        data.insert("MINECRAFT_JAR".to_string(), path_string(minecraft_jar));
+        data.insert(
+            "MINECRAFT_VERSION".to_string(),
+            self.install_profile["minecraft"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        );
+        data.insert(
+            "ROOT".to_string(),
+            path_string(libraries_dir.parent().unwrap_or(libraries_dir)),
+        );
+        data.insert("INSTALLER".to_string(), path_string(&self.path));
+        data.insert("LIBRARY_DIR".to_string(), path_string(libraries_dir));
+
+        for (key, value) in self.install_profile["data"]
+            .as_object()
+            .into_iter()
+            .flatten()
+        {
+            let Some(value) = value[SIDE].as_str() else {
+                continue;
+            };
+            let resolved =
+                if let Some(coord) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    path_string(&library_path(libraries_dir, coord)?)
+                } else if let Some(literal) =
+                    value.strip_prefix('\'').and_then(|v| v.strip_suffix('\''))
+                {
+                    literal.to_string()
+                } else if let Some(entry) = value.strip_prefix('/') {
+                    // 安装器内的文件（如 /data/client.lzma）解出到工作目录
+                    let dest = work_dir.join(entry);
+                    if let Some(parent) = dest.parent() {
+                        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+                    }
+                    fs::write(&dest, read_jar_entry(&self.path, entry)?)
+                        .map_err(|e| format!("解出安装器文件失败: {}", e))?;
+                    path_string(&dest)
+                } else {
+                    value.to_string()
+                };
+            data.insert(key.clone(), resolved);
+        }
+        Ok(data)
+    }
+}
+
+/// 替换处理器参数：`[坐标]` → 库文件路径，`{KEY}` → data 表中的值
+fn resolve_arg(
+    arg: &str,
+    data: &HashMap<String, String>,
+    libraries_dir: &Path,
+) -> Result<String, String> {
+    if let Some(coord) = arg.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+        return Ok(path_string(&library_path(libraries_dir, coord)?));
+    }
+    let mut result = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let key = &rest[start + 1..start + len];
+        let value = data
+            .get(key)
+            .ok_or_else(|| format!("处理器参数引用了未知的数据项: {}", key))?;
+        result.push_str(&rest[..start]);
+        result.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    // 输出表中的 SHA1 可能是带引号的字面量
+    Ok(result
+        .strip_prefix('\'')
+        .and_then(|r| r.strip_suffix('\''))
+        .map(String::from)
+        .unwrap_or(result))
+}
+
+/// 所有输出文件都已存在且 SHA1 匹配
+fn outputs_match(outputs: &[(PathBuf, String)]) -> bool {
+    outputs.iter().all(|(path, sha1)| {
+        path.is_file() && (sha1.is_empty() || verify_file_sha1(path, sha1).unwrap_or(false))
+    })
+}
+
+/// Maven 坐标对应的 libraries 目录内绝对路径
+fn library_path(libraries_dir: &Path, coord: &str) -> Result<PathBuf, String> {
+    maven_path_from_name(coord)
+        .map(|p| libraries_dir.join(p))
+        .ok_or_else(|| format!("无效的 Maven 坐标: {}", coord))
+}
+
+/// 读取处理器 jar 清单中的 Main-Class
+fn jar_main_class(jar: &Path) -> Result<String, String> {
+    let manifest = read_jar_entry(jar, "META-INF/MANIFEST.MF")?;
+    String::from_utf8_lossy(&manifest)
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|c| c.trim().to_string())
+        .ok_or_else(|| format!("处理器缺少 Main-Class: {}", jar.display()))
+}
+
+/// 读取 jar 内单个条目
+pub(super) fn read_jar_entry(jar: &Path, name: &str) -> Result<Vec<u8>, String> {
+    let file = fs::File::open(jar).map_err(|e| format!("打开 {} 失败: {}", jar.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("解析 {} 失败: {}", jar.display(), e))?;
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| format!("{} 中缺少 {}", jar.display(), name))?;
+    let mut buf = Vec::new();
+    entry
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+    Ok(buf)
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha1::{Digest, Sha1};
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const PROCESSOR_JAR: &[u8] = include_bytes!("fixtures/copy-processor.jar");
+    const PATCHED: &[u8] = b"patched-client";
+    const MAVEN: &str = "https://maven.test/";
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("wecraft-forge-{}-{}", tag, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn sha1_hex(bytes: &[u8]) -> String {
+        format!("{:x}", Sha1::digest(bytes))
+    }
+
+    /// 构造安装器 fixture：一个仅服务端处理器（应跳过）+ 一个把 /data/client.lzma 复制到 forge client 构件的处理器
+    fn write_installer(dir: &Path, patched_sha: &str) -> PathBuf {
+        let install_profile = serde_json::json!({
+            "spec": 1,
+            "profile": "forge",
+            "version": "1.20.1-forge-47.2.0",
+            "minecraft": "1.20.1",
+            "json": "/version.json",
+            "data": {
+                "BINPATCH": { "client": "/data/client.lzma", "server": "/data/server.lzma" },
+                "PATCHED": {
+                    "client": "[net.minecraftforge:forge:1.20.1-47.2.0:client]",
+                    "server": "[net.minecraftforge:forge:1.20.1-47.2.0:server]"
+                },
+                "PATCHED_SHA": { "client": format!("'{}'", patched_sha), "server": "''" }
+            },
+            "processors": [
+                { "sides": ["server"], "jar": "com.example:missing:1.0", "args": ["--server"] },
+                {
+                    "jar": "com.example:copy-processor:1.0",
+                    "classpath": [],
+                    "args": ["--input", "{BINPATCH}", "--output", "{PATCHED}"],
+                    "outputs": { "{PATCHED}": "{PATCHED_SHA}" }
+                }
+            ],
+            "libraries": [{
+                "name": "com.example:copy-processor:1.0",
+                "url": MAVEN
+            }]
+        });
+        let version_json = serde_json::json!({
+            "id": "1.20.1-forge-47.2.0",
+            "inheritsFrom": "1.20.1",
+            "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            "libraries": [
+                {
+                    "name": "net.minecraftforge:forge:1.20.1-47.2.0:universal",
+                    "downloads": { "artifact": {
+                        "path": "net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-universal.jar",
+                        "url": "", "size": 3
+                    }}
+                },
+                {
+                    "name": "net.minecraftforge:forge:1.20.1-47.2.0:client",
+                    "downloads": { "artifact": {
+                        "path": "net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-client.jar",
+                        "url": "", "size": 0
+                    }}
+                },
+                { "name": "cpw.mods:bootstraplauncher:1.1.2", "url": MAVEN }
+            ]
+        });
+
+        let path = dir.join("forge-1.20.1-47.2.0-installer.jar");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        let mut add = |name: &str, bytes: &[u8]| {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        };
+        add(
+            "install_profile.json",
+            install_profile.to_string().as_bytes(),
+        );
+        add("version.json", version_json.to_string().as_bytes());
+        add("data/client.lzma", PATCHED);
+        add(
+            "maven/net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-universal.jar",
+            b"uni",
+        );
+        zip.finish().unwrap();
+        path
+    }
+
+    /// 本地 maven 替身：按 url 把下载清单中的构件从替身目录复制到 libraries
+    fn fetch_from_stand_in(profile: &LoaderProfile, stand_in: &Path, libraries_dir: &Path) {
+        for file in &profile.libraries {
+            let relative = file.url.strip_prefix(MAVEN).expect("只应请求替身 maven");
+            let source = stand_in.join(relative);
+            if !source.is_file() {
+                continue;
+            }
+            let dest = libraries_dir.join(&file.path);
+            fs::create_dir_all(dest.parent().unwrap()).unwrap();
+            fs::copy(source, dest).unwrap();
+        }
+    }
+
+    #[test]
+    fn reads_profile_and_embedded_maven() {
+        let dir = temp_dir("profile");
+        let installer = ForgeInstaller::open(&write_installer(&dir, &sha1_hex(PATCHED))).unwrap();
+        let profile = installer.profile("1.20.1").unwrap();
+        assert_eq!(profile.id, "1.20.1-forge-47.2.0");
+        assert!(profile.installer.is_some());
+        // 无 url 的构件由安装器生成，不进入下载清单；安装期依赖并入清单
+        let urls: Vec<&str> = profile.libraries.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://maven.test/cpw/mods/bootstraplauncher/1.1.2/bootstraplauncher-1.1.2.jar",
+                "https://maven.test/com/example/copy-processor/1.0/copy-processor-1.0.jar",
+            ]
+        );
+        assert!(installer.profile("1.20.4").is_err());
+
+        let libraries = dir.join("libraries");
+        assert_eq!(installer.extract_embedded_maven(&libraries).unwrap(), 1);
+        assert_eq!(
+            fs::read(
+                libraries.join(
+                    "net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-universal.jar"
+                )
+            )
+            .unwrap(),
+            b"uni"
+        );
+        assert_eq!(installer.extract_embedded_maven(&libraries).unwrap(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[ignore = "需要 PATH 中的 java（cargo test -- --ignored）"]
+    fn runs_processors_and_verifies_outputs() {
+        let dir = temp_dir("run");
+        let stand_in = dir.join("maven");
+        fs::create_dir_all(stand_in.join("com/example/copy-processor/1.0")).unwrap();
+        fs::write(
+            stand_in.join("com/example/copy-processor/1.0/copy-processor-1.0.jar"),
+            PROCESSOR_JAR,
+        )
+        .unwrap();
+        let libraries = dir.join("libraries");
+        let work = dir.join("work");
+        fs::create_dir_all(&work).unwrap();
+        let mc_jar = dir.join("1.20.1.jar");
+
+        let installer = ForgeInstaller::open(&write_installer(&dir, &sha1_hex(PATCHED))).unwrap();
+        fetch_from_stand_in(&installer.profile("1.20.1").unwrap(), &stand_in, &libraries);
+        installer
+            .run_processors(Path::new("java"), &libraries, &mc_jar, &work)
+            .unwrap();
+        let client =
+            libraries.join("net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-client.jar");
+        assert_eq!(fs::read(&client).unwrap(), PATCHED);
+
+        // 输出已校验通过：再次安装不再执行处理器（处理器 jar 已删除也不报错）
+        fs::remove_file(libraries.join("com/example/copy-processor/1.0/copy-processor-1.0.jar"))
+            .unwrap();
+        installer
+            .run_processors(Path::new("java"), &libraries, &mc_jar, &work)
+            .unwrap();
+
+        // 声明的 SHA1 与产物不符 → 报错
+        fs::remove_file(&client).unwrap();
+        fetch_from_stand_in(&installer.profile("1.20.1").unwrap(), &stand_in, &libraries);
+        let bad = ForgeInstaller::open(&write_installer(&dir, &sha1_hex(b"other"))).unwrap();
+        let err = bad
+            .run_processors(Path::new("java"), &libraries, &mc_jar, &work)
+            .unwrap_err();
+        assert!(err.contains("校验失败"), "{}", err);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
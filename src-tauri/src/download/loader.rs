@@ -0,0 +1,366 @@
+// src-tauri/src/download/loader.rs
+// 加载器安装：拉取加载器 profile，生成继承原版的版本 JSON（inheritsFrom）与依赖库下载清单
+
+use std::path::{Path, PathBuf};
+
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::forge::ForgeInstaller;
+use super::optifine::{self, OptiFineInstaller};
+use crate::app_context::AppContext;
+use crate::download::manager::DownloadManager;
+use crate::download::models::{FileDownload, Library};
+use crate::download::utils::should_use_library;
+use crate::launch::maven_path_from_name;
+use crate::log_info;
+use crate::modloader::{self, ModLoaderType};
+
+/// 库缺少 url 时使用的默认 Maven 仓库
+const DEFAULT_MAVEN: &str = "https://libraries.minecraft.net/";
+
+/// 加载器 profile：继承原版的版本 JSON 与需要下载的依赖库
+#[derive(Debug, Clone)]
+pub struct LoaderProfile {
+    /// 版本 id（同时作为游戏目录内的 JSON 文件名）
+    pub id: String,
+    /// 原样写入的版本 JSON（含 inheritsFrom）
+    pub json: serde_json::Value,
+    /// 依赖库下载清单（路径相对 libraries 目录）
+    pub libraries: Vec<FileDownload>,
+    /// 安装器（依赖库就绪后需执行）
+    pub installer: Option<LoaderInstaller>,
+}
+
+/// 依赖库就绪后需执行的安装器
+#[derive(Debug, Clone)]
+pub enum LoaderInstaller {
+    /// Forge / NeoForge 安装器 jar：执行其处理器
+    Forge(PathBuf),
+    /// OptiFine jar：执行 Patcher 生成 OptiFine 库
+    OptiFine {
+        /// 安装器 jar 路径
+        path: PathBuf,
+        /// OptiFine 版本（如 HD_U_I6）
+        edition: String,
+    },
+}
+
+/// 拉取指定加载器的 profile；原版返回 None。
+/// Forge / NeoForge / OptiFine 先将安装器下载到 libraries 目录（Maven 布局）：
+/// Forge 系 profile 取自安装器内置的版本 JSON，OptiFine 按原版 JSON（parent_json）的参数格式生成
+pub async fn resolve_loader_profile(
+    dm: &DownloadManager,
+    libraries_dir: &Path,
+    mc_version: &str,
+    parent_json: &serde_json::Value,
+    loader_type: ModLoaderType,
+    loader_version: Option<&str>,
+    token: Option<&CancellationToken>,
+) -> Result<Option<LoaderProfile>, String> {
+    if loader_type == ModLoaderType::Vanilla {
+        return Ok(None);
+    }
+    let loader_version =
+        loader_version.ok_or_else(|| format!("未指定 {} 加载器版本", loader_type.as_str()))?;
+
+    match loader_type {
+        ModLoaderType::Fabric | ModLoaderType::Quilt | ModLoaderType::LegacyFabric => {
+            let json =
+                modloader::fetch_fabric_profile(loader_type, mc_version, loader_version).await?;
+            profile_from_json(json, mc_version).map(Some)
+        }
+        ModLoaderType::Forge | ModLoaderType::NeoForge => {
+            let (coord, url) =
+                modloader::installer_artifact(loader_type, mc_version, loader_version)
+                    .ok_or_else(|| format!("无法确定 {} 安装器地址", loader_type.as_str()))?;
+            let path =
+                download_installer(dm, libraries_dir, loader_type, &coord, &url, token).await?;
+            ForgeInstaller::open(&path)?.profile(mc_version).map(Some)
+        }
+        ModLoaderType::OptiFine => {
+            let url = optifine::download_url(mc_version, loader_version)
+                .ok_or_else(|| format!("无效的 OptiFine 版本: {}", loader_version))?;
+            let coord = format!(
+                "{}:installer",
+                optifine::library_name(mc_version, loader_version)
+            );
+            let path =
+                download_installer(dm, libraries_dir, loader_type, &coord, &url, token).await?;
+            OptiFineInstaller::open(&path)?
+                .profile(mc_version, loader_version, parent_json)
+                .map(Some)
+        }
+        other => Err(format!("暂不支持自动安装 {} 加载器", other.as_str())),
+    }
+}
+
+/// 将安装器下载到 libraries 目录内坐标对应的位置
+async fn download_installer(
+    dm: &DownloadManager,
+    libraries_dir: &Path,
+    loader_type: ModLoaderType,
+    coord: &str,
+    url: &str,
+    token: Option<&CancellationToken>,
+) -> Result<PathBuf, String> {
+    let path = libraries_dir
+        .join(maven_path_from_name(coord).ok_or_else(|| format!("无效的 Maven 坐标: {}", coord))?);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    dm.download_file_if_needed(url, &path, None, None, &None, token)
+        .await
+        .map_err(|e| format!("下载 {} 安装器失败: {}", loader_type.as_str(), e))?;
+    Ok(path)
+}
+
+/// 校验 profile 继承的原版版本，并提取依赖库下载清单
+pub fn profile_from_json(
+    json: serde_json::Value,
+    mc_version: &str,
+) -> Result<LoaderProfile, String> {
+    let id = json["id"]
+        .as_str()
+        .filter(|id| !id.is_empty())
+        .ok_or("加载器 profile 缺少 id")?
+        .to_string();
+    if id == mc_version || id.contains(['/', '\\']) {
+        return Err(format!("加载器 profile id 非法: {}", id));
+    }
+    match json["inheritsFrom"].as_str() {
+        Some(parent) if parent == mc_version => {}
+        Some(parent) => {
+            return Err(format!(
+                "加载器 profile 继承自 {}，与游戏版本 {} 不一致",
+                parent, mc_version
+            ));
+        }
+        None => return Err("加载器 profile 缺少 inheritsFrom".to_string()),
+    }
+
+    let mut libraries = Vec::new();
+    for lib_json in json["libraries"].as_array().into_iter().flatten() {
+        let library: Library = serde_json::from_value(lib_json.clone())
+            .map_err(|e| format!("解析加载器库失败: {}", e))?;
+        if !should_use_library(&library) {
+            continue;
+        }
+        if let Some(file) = library_download(&library) {
+            libraries.push(file);
+        }
+    }
+
+    Ok(LoaderProfile {
+        id,
+        json,
+        libraries,
+        installer: None,
+    })
+}
+
+/// 库的下载信息：优先 downloads.artifact，否则按 Maven 坐标拼接仓库 url
+fn library_download(library: &Library) -> Option<FileDownload> {
+    if let Some(artifact) = library.downloads.as_ref().and_then(|d| d.artifact.as_ref()) {
+        // 空 url 的构件由安装器本地生成（如 Forge 的补丁产物），不参与下载
+        if artifact.url.is_empty() {
+            return None;
+        }
+        return Some(FileDownload {
+            url: artifact.url.clone(),
+            sha1: artifact.sha1.clone(),
+            size: artifact.size,
+            path: artifact.path.clone(),
+            ..Default::default()
+        });
+    }
+
+    let path = maven_path_from_name(&library.name)?;
+    let base = library
+        .extra
+        .get("url")
+        .and_then(|u| u.as_str())
+        .unwrap_or(DEFAULT_MAVEN);
+    Some(FileDownload {
+        url: format!("{}/{}", base.trim_end_matches('/'), path),
+        sha1: library
+            .extra
+            .get("sha1")
+            .and_then(|s| s.as_str())
+            .map(String::from),
+        size: library
+            .extra
+            .get("size")
+            .and_then(|s| s.as_u64())
+            .unwrap_or(0),
+        path,
+        ..Default::default()
+    })
+}
+
+/// 依赖库就绪后完成安装：执行安装器（如有，使用满足游戏要求的 Java），再写入 profile
+pub async fn finish_install(
+    ctx: &AppContext,
+    game_dir: &Path,
+    mc_version: &str,
+    java_major: u32,
+    profile: &LoaderProfile,
+) -> Result<(), String> {
+    let Some(installer) = &profile.installer else {
+        return write_loader_profile(game_dir, profile);
+    };
+    let installs = crate::java::scan_java_cached(ctx).unwrap_or_default();
+    let java = crate::java::select_java(&installs, java_major, false)
+        .map(|j| j.path.clone())
+        .ok_or_else(|| format!("未找到执行安装器所需的 Java {}", java_major))?;
+    let libraries_dir = ctx.libraries_dir();
+    let minecraft_jar = ctx.version_jar_in_dir(&game_dir.to_path_buf(), mc_version);
+
+    match installer {
+        LoaderInstaller::Forge(path) => {
+            let installer = ForgeInstaller::open(path)?;
+            installer.extract_embedded_maven(&libraries_dir)?;
+
+            // 每次安装独立的工作目录，并行安装同一版本时互不干扰
+            let work_dir =
+                ctx.wecraft_temp_dir()
+                    .join(format!("installer-{}-{}", profile.id, Uuid::new_v4()));
+            std::fs::create_dir_all(&work_dir)
+                .map_err(|e| format!("创建安装器工作目录失败: {}", e))?;
+            log_info!(
+                "执行 {} 安装器处理器（Java: {}）",
+                profile.id,
+                java.display()
+            );
+            let result = {
+                let work_dir = work_dir.clone();
+                tokio::task::spawn_blocking(move || {
+                    installer.run_processors(&java, &libraries_dir, &minecraft_jar, &work_dir)
+                })
+                .await
+                .map_err(|e| format!("安装器任务异常: {}", e))?
+            };
+            let _ = std::fs::remove_dir_all(&work_dir);
+            result?;
+        }
+        LoaderInstaller::OptiFine { path, edition } => {
+            let installer = OptiFineInstaller::open(path)?;
+            let (mc_version, edition) = (mc_version.to_string(), edition.clone());
+            log_info!("安装 OptiFine {}（Java: {}）", edition, java.display());
+            tokio::task::spawn_blocking(move || {
+                installer.install(&java, &minecraft_jar, &libraries_dir, &mc_version, &edition)
+            })
+            .await
+            .map_err(|e| format!("安装器任务异常: {}", e))??;
+        }
+    }
+    write_loader_profile(game_dir, profile)
+}
+
+/// 将 profile 写入游戏目录（{game_dir}/{id}.json）
+pub fn write_loader_profile(game_dir: &Path, profile: &LoaderProfile) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(&profile.json)
+        .map_err(|e| format!("序列化加载器 profile 失败: {}", e))?;
+    std::fs::write(game_dir.join(format!("{}.json", profile.id)), content)
+        .map_err(|e| format!("写入加载器 profile 失败: {}", e))
+}
+
+/// 查找游戏目录内继承自 mc_version 的加载器 profile，返回其版本 id
+pub fn find_loader_profile(game_dir: &Path, mc_version: &str) -> Option<String> {
+    let mut candidates: Vec<String> = std::fs::read_dir(game_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?;
+            if stem == mc_version || stem.starts_with(".wecraft") {
+                return None;
+            }
+            let raw = std::fs::read_to_string(&path).ok()?;
+            let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+            (json["inheritsFrom"].as_str() == Some(mc_version)).then(|| stem.to_string())
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fabric_profile() -> serde_json::Value {
+        json!({
+            "id": "fabric-loader-0.15.7-1.20.4",
+            "inheritsFrom": "1.20.4",
+            "type": "release",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "arguments": { "game": [], "jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "] },
+            "libraries": [
+                {
+                    "name": "net.fabricmc:fabric-loader:0.15.7",
+                    "url": "https://maven.fabricmc.net/",
+                    "sha1": "2ef9ab5b3b8ab0c6e1d2b9a5d8c1e1a0b2c3d4e5",
+                    "size": 1395418
+                },
+                { "name": "org.ow2.asm:asm:9.6" }
+            ]
+        })
+    }
+
+    #[test]
+    fn fabric_profile_libraries_use_maven_layout() {
+        let profile = profile_from_json(fabric_profile(), "1.20.4").unwrap();
+        assert_eq!(profile.id, "fabric-loader-0.15.7-1.20.4");
+        assert_eq!(profile.libraries.len(), 2);
+
+        let loader = &profile.libraries[0];
+        assert_eq!(
+            loader.url,
+            "https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.15.7/fabric-loader-0.15.7.jar"
+        );
+        assert_eq!(
+            loader.path,
+            "net/fabricmc/fabric-loader/0.15.7/fabric-loader-0.15.7.jar"
+        );
+        assert_eq!(loader.size, 1395418);
+        assert!(loader.sha1.is_some());
+        // 缺少 url 时回退官方库仓库
+        assert_eq!(
+            profile.libraries[1].url,
+            "https://libraries.minecraft.net/org/ow2/asm/asm/9.6/asm-9.6.jar"
+        );
+    }
+
+    #[test]
+    fn profile_must_inherit_game_version() {
+        let err = profile_from_json(fabric_profile(), "1.21").unwrap_err();
+        assert!(err.contains("不一致"), "{}", err);
+
+        let mut orphan = fabric_profile();
+        orphan.as_object_mut().unwrap().remove("inheritsFrom");
+        assert!(profile_from_json(orphan, "1.20.4").is_err());
+    }
+
+    #[test]
+    fn written_profile_is_found_by_parent_version() {
+        let dir = std::env::temp_dir().join(format!("wecraft-loader-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("1.20.4.json"), r#"{"id":"1.20.4"}"#).unwrap();
+        assert_eq!(find_loader_profile(&dir, "1.20.4"), None);
+
+        let profile = profile_from_json(fabric_profile(), "1.20.4").unwrap();
+        write_loader_profile(&dir, &profile).unwrap();
+        assert_eq!(
+            find_loader_profile(&dir, "1.20.4").as_deref(),
+            Some("fabric-loader-0.15.7-1.20.4")
+        );
+        assert_eq!(find_loader_profile(&dir, "1.21"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
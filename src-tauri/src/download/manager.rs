@@ -15,7 +15,8 @@ pub struct DownloadManager {
     pub tasks: Arc<Mutex<HashMap<String, DownloadTask>>>,
     /// 版本下载清单缓存
     pub manifest_cache: Arc<Mutex<HashMap<String, VersionJsonManifest>>>,
-    /// 部署中的取消令牌（key = version_id，供 cancel_version_download 触发）
+    /// 部署中的取消令牌（key = version_id，供 cancel_version_download 触发；
+    /// 游戏级任务另以 game:{name} 登记，用于同一游戏的任务互斥）
     pub cancellations: Arc<Mutex<HashMap<String, CancellationToken>>>,
 }
 
@@ -52,6 +53,24 @@ impl DownloadManager {
         token
     }
 
+    /// 注册游戏级取消令牌（key = game:{name}，下载 / 修复 / 更换加载器共用，与 version_id 区分）；
+    /// 该游戏已有进行中的任务时返回 None
+    pub fn try_register_game(&self, game_name: &str) -> Option<CancellationToken> {
+        let mut cancellations = self.cancellations.lock().unwrap();
+        let key = game_key(game_name);
+        if cancellations.contains_key(&key) {
+            return None;
+        }
+        let token = CancellationToken::new();
+        cancellations.insert(key, token.clone());
+        Some(token)
+    }
+
+    /// 注销游戏级取消令牌
+    pub fn unregister_game(&self, game_name: &str) {
+        self.cancellations.lock().unwrap().remove(&game_key(game_name));
+    }
+
     /// 注销部署取消令牌（部署完成或失败后由调用方清理）
     pub fn unregister_cancellation(&self, version_id: &str) {
         self.cancellations.lock().unwrap().remove(version_id);
@@ -92,3 +111,8 @@ impl DownloadManager {
         tasks.values().cloned().collect()
     }
 }
+
+/// 游戏级任务的取消令牌 key
+fn game_key(game_name: &str) -> String {
+    format!("game:{}", game_name)
+}
@@ -4,8 +4,16 @@ pub mod commands;
 pub mod downloader;
 /// 下载管理器
 pub mod manager;
+/// Forge / NeoForge 安装器
+pub mod forge;
+/// 加载器安装
+pub mod loader;
+/// OptiFine 安装器
+pub mod optifine;
 /// 下载相关数据模型
 pub mod models;
+/// 加载器更换
+pub mod swap;
 /// 下载工具函数
 pub mod utils;
 /// 版本清单解析
@@ -250,6 +250,9 @@ pub struct DownloadOptions {
     pub loader_type: ModLoaderType,
     /// 加载器版本
     pub loader_version: Option<String>,
+    /// 与 Forge / Fabric 组合时作为模组安装的 OptiFine 版本（如 HD_U_I6）
+    #[serde(default)]
+    pub optifine_version: Option<String>,
 }
 
 /// 部署结果
@@ -0,0 +1,493 @@
+// src-tauri/src/download/optifine.rs
+// OptiFine 安装：独立版本（用所选 Java 执行安装器内的 optifine.Patcher，产物放入 libraries/optifine/...，
+// 配合 launchwrapper 生成继承原版的 profile），或与 Forge / Fabric 组合时作为模组放入 mods/（Fabric 需 OptiFabric）
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use super::forge::read_jar_entry;
+use super::loader::{LoaderInstaller, LoaderProfile};
+use crate::download::manager::DownloadManager;
+use crate::download::models::FileDownload;
+use crate::launch::maven_path_from_name;
+use crate::log_info;
+use crate::modloader::ModLoaderType;
+
+/// BMCLAPI OptiFine 下载接口
+const BMCLAPI_OPTIFINE: &str = "https://bmclapi2.bangbang93.com/optifine";
+/// Modrinth 上 OptiFabric 的版本列表接口
+const OPTIFABRIC_VERSIONS: &str = "https://api.modrinth.com/v2/project/optifabric/version";
+/// launchwrapper 启动主类
+const LAUNCH_MAIN_CLASS: &str = "net.minecraft.launchwrapper.Launch";
+/// OptiFine 的 launchwrapper tweaker
+const TWEAK_CLASS: &str = "optifine.OptiFineTweaker";
+/// 安装器未内置 launchwrapper 时使用的官方版本
+const OFFICIAL_LAUNCHWRAPPER: &str = "net.minecraft:launchwrapper:1.12";
+
+/// 拆分 OptiFine 版本号：HD_U_I6 → (HD_U, I6)，HD_U_I7_pre3 → (HD_U, I7_pre3)
+pub fn split_edition(edition: &str) -> Option<(&str, &str)> {
+    let patch = edition
+        .strip_prefix("HD_U_")
+        .or_else(|| edition.strip_prefix("HD_"))
+        .filter(|p| !p.is_empty())?;
+    Some((&edition[..edition.len() - patch.len() - 1], patch))
+}
+
+/// OptiFine jar 的下载地址（BMCLAPI）
+pub fn download_url(mc_version: &str, edition: &str) -> Option<String> {
+    let (kind, patch) = split_edition(edition)?;
+    Some(format!(
+        "{}/{}/{}/{}",
+        BMCLAPI_OPTIFINE, mc_version, kind, patch
+    ))
+}
+
+/// OptiFine 库的 Maven 坐标（optifine:OptiFine:{mc}_{edition}），安装器加 `:installer` 分类器
+pub fn library_name(mc_version: &str, edition: &str) -> String {
+    format!("optifine:OptiFine:{}_{}", mc_version, edition)
+}
+
+/// 作为模组安装时 mods/ 内的文件名
+pub fn mod_file_name(mc_version: &str, edition: &str) -> String {
+    format!("OptiFine_{}_{}.jar", mc_version, edition)
+}
+
+/// 远端提供的模组文件名：只接受单个文件名（拒绝路径分隔符与 `..`），避免写到 mods/ 之外
+fn safe_file_name(name: &str) -> Result<&str, String> {
+    let valid = !name.contains(['/', '\\'])
+        && Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name);
+    valid
+        .then_some(name)
+        .ok_or_else(|| format!("非法的模组文件名: {}", name))
+}
+
+/// OptiFine 能否以模组形式与该加载器组合（Forge 直接加载；Fabric 需 OptiFabric）
+pub fn check_combination(loader_type: ModLoaderType) -> Result<(), String> {
+    match loader_type {
+        ModLoaderType::Forge | ModLoaderType::Fabric => Ok(()),
+        other => Err(format!(
+            "OptiFine 只能与 Forge / Fabric 组合安装，不支持 {}",
+            other.as_str()
+        )),
+    }
+}
+
+/// 安装器内置的 launchwrapper
+enum Launchwrapper {
+    /// 安装器内的 jar 条目：(Maven 坐标, 条目名)
+    Embedded(String, String),
+    /// 需从官方库仓库下载
+    Official,
+}
+
+/// OptiFine 安装器 jar（即 BMCLAPI 下载的 OptiFine jar）
+pub struct OptiFineInstaller {
+    /// 安装器 jar 路径
+    path: PathBuf,
+}
+
+impl OptiFineInstaller {
+    /// 打开安装器，确认其为 OptiFine jar
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = fs::File::open(path).map_err(|e| format!("打开 OptiFine 安装器失败: {}", e))?;
+        let archive =
+            zip::ZipArchive::new(file).map_err(|e| format!("解析 OptiFine 安装器失败: {}", e))?;
+        let is_optifine = archive.file_names().any(|name| {
+            matches!(
+                name,
+                "Config.class" | "net/optifine/Config.class" | "notch/net/optifine/Config.class"
+            )
+        });
+        if !is_optifine {
+            return Err(format!("{} 不是 OptiFine 安装器", path.display()));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// 生成继承原版的 profile：launchwrapper 主类 + OptiFineTweaker，参数格式跟随原版 JSON
+    pub fn profile(
+        &self,
+        mc_version: &str,
+        edition: &str,
+        parent_json: &serde_json::Value,
+    ) -> Result<LoaderProfile, String> {
+        let launchwrapper = self.launchwrapper()?;
+        let launchwrapper_name = match &launchwrapper {
+            Launchwrapper::Embedded(name, _) => name.clone(),
+            Launchwrapper::Official => OFFICIAL_LAUNCHWRAPPER.to_string(),
+        };
+
+        let mut json = serde_json::json!({
+            "id": format!("{}-OptiFine_{}", mc_version, edition),
+            "inheritsFrom": mc_version,
+            "type": parent_json["type"].as_str().unwrap_or("release"),
+            "mainClass": LAUNCH_MAIN_CLASS,
+            "libraries": [
+                { "name": library_name(mc_version, edition) },
+                { "name": launchwrapper_name },
+            ],
+        });
+        if parent_json.get("arguments").is_some() {
+            json["arguments"] = serde_json::json!({ "game": ["--tweakClass", TWEAK_CLASS] });
+        } else {
+            let parent_args = parent_json["minecraftArguments"]
+                .as_str()
+                .ok_or("原版 JSON 缺少启动参数")?;
+            json["minecraftArguments"] =
+                format!("{} --tweakClass {}", parent_args, TWEAK_CLASS).into();
+        }
+
+        let mut libraries = Vec::new();
+        if let Launchwrapper::Official = launchwrapper {
+            let path =
+                maven_path_from_name(OFFICIAL_LAUNCHWRAPPER).ok_or("无效的 launchwrapper 坐标")?;
+            libraries.push(FileDownload {
+                url: format!("https://libraries.minecraft.net/{}", path),
+                path,
+                ..Default::default()
+            });
+        }
+
+        Ok(LoaderProfile {
+            id: json["id"].as_str().unwrap_or_default().to_string(),
+            json,
+            libraries,
+            installer: Some(LoaderInstaller::OptiFine {
+                path: self.path.clone(),
+                edition: edition.to_string(),
+            }),
+        })
+    }
+
+    /// 生成 OptiFine 库：安装器含 optifine.Patcher 时以原版 jar 打补丁，否则安装器本身即为库；
+    /// 内置的 launchwrapper 一并解出到 libraries 目录
+    pub fn install(
+        &self,
+        java: &Path,
+        minecraft_jar: &Path,
+        libraries_dir: &Path,
+        mc_version: &str,
+        edition: &str,
+    ) -> Result<(), String> {
+        let output = libraries_dir.join(
+            maven_path_from_name(&library_name(mc_version, edition))
+                .ok_or("无效的 OptiFine 坐标")?,
+        );
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+
+        if read_jar_entry(&self.path, "optifine/Patcher.class").is_ok() {
+            log_info!("执行 OptiFine Patcher: {}", output.display());
+            let mut cmd = Command::new(java);
+            cmd.arg("-cp")
+                .arg(&self.path)
+                .arg("optifine.Patcher")
+                .arg(minecraft_jar)
+                .arg(&self.path)
+                .arg(&output);
+            #[cfg(windows)]
+            {
+                use std::os::windows::process::CommandExt;
+                cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+            }
+            let result = cmd
+                .output()
+                .map_err(|e| format!("启动 OptiFine Patcher 失败: {}", e))?;
+            if !result.status.success() {
+                let stderr = String::from_utf8_lossy(&result.stderr);
+                let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+                return Err(format!(
+                    "OptiFine Patcher 执行失败（{}）:\n{}",
+                    result.status,
+                    tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+                ));
+            }
+            if !output.is_file() {
+                return Err(format!("OptiFine Patcher 未生成输出: {}", output.display()));
+            }
+        } else {
+            fs::copy(&self.path, &output).map_err(|e| format!("复制 OptiFine 库失败: {}", e))?;
+        }
+
+        if let Launchwrapper::Embedded(name, entry) = self.launchwrapper()? {
+            let dest =
+                libraries_dir.join(maven_path_from_name(&name).ok_or("无效的 launchwrapper 坐标")?);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            }
+            fs::write(&dest, read_jar_entry(&self.path, &entry)?)
+                .map_err(|e| format!("解出 launchwrapper 失败: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// 识别安装器内置的 launchwrapper（launchwrapper-of.txt 指明版本 / launchwrapper-2.0.jar）
+    fn launchwrapper(&self) -> Result<Launchwrapper, String> {
+        if let Ok(raw) = read_jar_entry(&self.path, "launchwrapper-of.txt") {
+            let version = String::from_utf8_lossy(&raw).trim().to_string();
+            if version.is_empty() {
+                return Err("launchwrapper-of.txt 为空".to_string());
+            }
+            return Ok(Launchwrapper::Embedded(
+                format!("optifine:launchwrapper-of:{}", version),
+                format!("launchwrapper-of-{}.jar", version),
+            ));
+        }
+        if read_jar_entry(&self.path, "launchwrapper-2.0.jar").is_ok() {
+            return Ok(Launchwrapper::Embedded(
+                "optifine:launchwrapper:2.0".to_string(),
+                "launchwrapper-2.0.jar".to_string(),
+            ));
+        }
+        Ok(Launchwrapper::Official)
+    }
+}
+
+/// 作为模组安装：OptiFine jar 放入 {game_dir}/mods，Fabric 额外安装 OptiFabric
+pub async fn install_as_mod(
+    dm: &DownloadManager,
+    game_dir: &Path,
+    mc_version: &str,
+    edition: &str,
+    loader_type: ModLoaderType,
+    token: Option<&CancellationToken>,
+) -> Result<(), String> {
+    check_combination(loader_type)?;
+    let url = download_url(mc_version, edition)
+        .ok_or_else(|| format!("无效的 OptiFine 版本: {}", edition))?;
+    let mods_dir = game_dir.join("mods");
+    fs::create_dir_all(&mods_dir).map_err(|e| format!("创建 mods 目录失败: {}", e))?;
+
+    dm.download_file_if_needed(
+        &url,
+        &mods_dir.join(mod_file_name(mc_version, edition)),
+        None,
+        None,
+        &None,
+        token,
+    )
+    .await
+    .map_err(|e| format!("下载 OptiFine 失败: {}", e))?;
+
+    if loader_type == ModLoaderType::Fabric {
+        let optifabric = fetch_optifabric(mc_version).await?;
+        dm.download_file_if_needed(
+            &optifabric.url,
+            &mods_dir.join(&optifabric.path),
+            optifabric.sha1.as_deref(),
+            Some(optifabric.size),
+            &None,
+            token,
+        )
+        .await
+        .map_err(|e| format!("下载 OptiFabric 失败: {}", e))?;
+    }
+    log_info!("已将 OptiFine {} 作为模组安装", edition);
+    Ok(())
+}
+
+/// 查询适配该 Minecraft 版本的最新 OptiFabric（path 为文件名）
+async fn fetch_optifabric(mc_version: &str) -> Result<FileDownload, String> {
+    #[derive(Deserialize)]
+    struct ModrinthVersion {
+        files: Vec<ModrinthFile>,
+    }
+    #[derive(Deserialize)]
+    struct ModrinthFile {
+        url: String,
+        filename: String,
+        #[serde(default)]
+        primary: bool,
+        #[serde(default)]
+        size: u64,
+        hashes: std::collections::HashMap<String, String>,
+    }
+
+    let client = reqwest::Client::new();
+    let versions: Vec<ModrinthVersion> = client
+        .get(OPTIFABRIC_VERSIONS)
+        .query(&[
+            ("loaders", r#"["fabric"]"#.to_string()),
+            ("game_versions", format!(r#"["{}"]"#, mc_version)),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("获取 OptiFabric 版本失败: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("解析 OptiFabric 版本失败: {}", e))?;
+
+    let mut files = versions
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("没有适配 Minecraft {} 的 OptiFabric", mc_version))?
+        .files;
+    if files.is_empty() {
+        return Err("OptiFabric 版本不含文件".to_string());
+    }
+    let index = files.iter().position(|f| f.primary).unwrap_or(0);
+    let mut file = files.swap_remove(index);
+    safe_file_name(&file.filename)?;
+    Ok(FileDownload {
+        url: file.url,
+        sha1: file.hashes.remove("sha1"),
+        size: file.size,
+        path: file.filename,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const PATCHER_CLASS: &[u8] = include_bytes!("fixtures/optifine/Patcher.class");
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("wecraft-optifine-{}-{}", tag, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_installer(dir: &Path, entries: &[(&str, &[u8])]) -> PathBuf {
+        let path = dir.join("OptiFine_1.20.1_HD_U_I6.jar");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        for (name, bytes) in [("net/optifine/Config.class", b"cfg".as_slice())]
+            .into_iter()
+            .chain(entries.iter().copied())
+        {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn edition_maps_to_download_and_library() {
+        assert_eq!(split_edition("HD_U_I6"), Some(("HD_U", "I6")));
+        assert_eq!(split_edition("HD_U_I7_pre3"), Some(("HD_U", "I7_pre3")));
+        assert_eq!(split_edition("HD_A4"), Some(("HD", "A4")));
+        assert_eq!(split_edition("I6"), None);
+        assert_eq!(
+            download_url("1.20.1", "HD_U_I6").as_deref(),
+            Some("https://bmclapi2.bangbang93.com/optifine/1.20.1/HD_U/I6")
+        );
+        assert_eq!(
+            maven_path_from_name(&library_name("1.20.1", "HD_U_I6")).unwrap(),
+            "optifine/OptiFine/1.20.1_HD_U_I6/OptiFine-1.20.1_HD_U_I6.jar"
+        );
+        assert!(check_combination(ModLoaderType::Fabric).is_ok());
+        assert!(check_combination(ModLoaderType::NeoForge).is_err());
+        assert!(safe_file_name("optifabric-1.14.3.jar").is_ok());
+        for name in [
+            "../evil.jar",
+            "a/b.jar",
+            "a\\b.jar",
+            "..",
+            ".",
+            "",
+            "/abs.jar",
+        ] {
+            assert!(safe_file_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn profile_follows_parent_argument_style() {
+        let dir = temp_dir("profile");
+        let installer = OptiFineInstaller::open(&write_installer(
+            &dir,
+            &[
+                ("launchwrapper-of.txt", b"2.3\n"),
+                ("launchwrapper-of-2.3.jar", b"lw"),
+            ],
+        ))
+        .unwrap();
+
+        let modern = serde_json::json!({ "type": "release", "arguments": { "game": [] } });
+        let profile = installer.profile("1.20.1", "HD_U_I6", &modern).unwrap();
+        assert_eq!(profile.id, "1.20.1-OptiFine_HD_U_I6");
+        assert_eq!(profile.json["inheritsFrom"], "1.20.1");
+        assert_eq!(profile.json["mainClass"], LAUNCH_MAIN_CLASS);
+        assert_eq!(
+            profile.json["arguments"]["game"],
+            serde_json::json!(["--tweakClass", TWEAK_CLASS])
+        );
+        assert_eq!(
+            profile.json["libraries"][1]["name"],
+            "optifine:launchwrapper-of:2.3"
+        );
+        // 内置 launchwrapper 随安装解出，无需下载
+        assert!(profile.libraries.is_empty());
+
+        let legacy = serde_json::json!({ "minecraftArguments": "--username ${auth_player_name}" });
+        let profile = installer.profile("1.8.9", "HD_U_M5", &legacy).unwrap();
+        assert_eq!(
+            profile.json["minecraftArguments"],
+            "--username ${auth_player_name} --tweakClass optifine.OptiFineTweaker"
+        );
+        assert!(profile.json.get("arguments").is_none());
+
+        let not_optifine = dir.join("other.jar");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&not_optifine).unwrap());
+        zip.start_file("a.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+        assert!(OptiFineInstaller::open(&not_optifine).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[ignore = "需要 PATH 中的 java（cargo test -- --ignored）"]
+    fn install_runs_patcher_and_extracts_launchwrapper() {
+        let dir = temp_dir("install");
+        let installer = OptiFineInstaller::open(&write_installer(
+            &dir,
+            &[
+                ("optifine/Patcher.class", PATCHER_CLASS),
+                ("launchwrapper-of.txt", b"2.3"),
+                ("launchwrapper-of-2.3.jar", b"lw"),
+            ],
+        ))
+        .unwrap();
+        let minecraft_jar = dir.join("1.20.1.jar");
+        fs::write(&minecraft_jar, b"vanilla").unwrap();
+        let libraries_dir = dir.join("libraries");
+
+        installer
+            .install(
+                Path::new("java"),
+                &minecraft_jar,
+                &libraries_dir,
+                "1.20.1",
+                "HD_U_I6",
+            )
+            .unwrap();
+        assert_eq!(
+            fs::read(
+                libraries_dir.join("optifine/OptiFine/1.20.1_HD_U_I6/OptiFine-1.20.1_HD_U_I6.jar")
+            )
+            .unwrap(),
+            b"vanilla+optifine"
+        );
+        assert_eq!(
+            fs::read(libraries_dir.join("optifine/launchwrapper-of/2.3/launchwrapper-of-2.3.jar"))
+                .unwrap(),
+            b"lw"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,378 @@
+// src-tauri/src/download/swap.rs
+// 更换已有游戏的加载器（升级 / 降级 / 切换 / 移除）：备份旧 profile → 生成新 profile 并下载依赖库 →
+// 失败时删除新 profile 并恢复备份；成功后清理旧 profile 独有、且不再被任何游戏引用的依赖库
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+
+use super::loader::{find_loader_profile, finish_install, resolve_loader_profile};
+use crate::app_context::AppContext;
+use crate::download::downloader::DownloadProgressTracker;
+use crate::download::manager::DownloadManager;
+use crate::download::models::Library;
+use crate::game::models::{Game, OptiFineInstall, OptiFineMode};
+use crate::launch::{is_game_active, maven_path_from_name};
+use crate::modloader::{self, ModLoaderType};
+use crate::{GameManager, log_info, log_warn};
+
+/// 旧加载器 profile 的备份：`{id}.json` 改名为 `{id}.json.bak`，不再被 [`find_loader_profile`] 识别
+pub struct ProfileBackup {
+    /// profile 原路径
+    original: PathBuf,
+    /// 备份路径
+    backup: PathBuf,
+}
+
+impl ProfileBackup {
+    /// 备份游戏目录内继承 mc_version 的加载器 profile；没有 profile（原版）时返回 None
+    pub fn create(game_dir: &Path, mc_version: &str) -> Result<Option<Self>, String> {
+        let Some(id) = find_loader_profile(game_dir, mc_version) else {
+            return Ok(None);
+        };
+        let original = game_dir.join(format!("{}.json", id));
+        let backup = game_dir.join(format!("{}.json.bak", id));
+        fs::rename(&original, &backup).map_err(|e| format!("备份加载器 profile 失败: {}", e))?;
+        Ok(Some(Self { original, backup }))
+    }
+
+    /// 恢复备份
+    pub fn restore(self) -> Result<(), String> {
+        fs::rename(&self.backup, &self.original)
+            .map_err(|e| format!("恢复加载器 profile 失败: {}", e))
+    }
+
+    /// 删除备份
+    pub fn discard(self) {
+        let _ = fs::remove_file(&self.backup);
+    }
+}
+
+/// 版本 JSON 引用的依赖库路径（相对 libraries 目录，不区分平台规则）
+pub fn profile_library_paths(json: &serde_json::Value) -> Vec<String> {
+    json["libraries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|lib_json| {
+            let library: Library = serde_json::from_value(lib_json.clone()).ok()?;
+            library
+                .downloads
+                .as_ref()
+                .and_then(|d| d.artifact.as_ref())
+                .map(|a| a.path.clone())
+                .or_else(|| maven_path_from_name(&library.name))
+        })
+        .collect()
+}
+
+/// 所有游戏目录内版本 JSON 引用的依赖库路径
+pub fn referenced_libraries(game_dirs: &[PathBuf]) -> HashSet<String> {
+    let mut referenced = HashSet::new();
+    for dir in game_dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            let is_version_json = path.extension().and_then(|e| e.to_str()) == Some("json")
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| !n.starts_with(".wecraft"));
+            if !is_version_json {
+                continue;
+            }
+            let Some(json) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+            else {
+                continue;
+            };
+            referenced.extend(profile_library_paths(&json));
+        }
+    }
+    referenced
+}
+
+/// 删除未被引用的候选库文件，并清理随之变空的目录；返回删除的文件数
+pub fn prune_libraries(
+    libraries_dir: &Path,
+    candidates: &[String],
+    referenced: &HashSet<String>,
+) -> usize {
+    let mut pruned = 0;
+    for relative in candidates {
+        if referenced.contains(relative) {
+            continue;
+        }
+        let path = libraries_dir.join(relative);
+        if fs::remove_file(&path).is_err() {
+            continue;
+        }
+        pruned += 1;
+        let mut dir = path.parent();
+        while let Some(d) = dir {
+            if d == libraries_dir || fs::remove_dir(d).is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+    pruned
+}
+
+/// 加载器安装器在 libraries 目录内的路径（Forge / NeoForge / OptiFine）
+fn installer_library_path(
+    loader_type: ModLoaderType,
+    mc_version: &str,
+    loader_version: &str,
+) -> Option<String> {
+    let coord = match loader_type {
+        ModLoaderType::Forge | ModLoaderType::NeoForge => {
+            modloader::installer_artifact(loader_type, mc_version, loader_version)?.0
+        }
+        ModLoaderType::OptiFine => format!(
+            "{}:installer",
+            super::optifine::library_name(mc_version, loader_version)
+        ),
+        _ => return None,
+    };
+    maven_path_from_name(&coord)
+}
+
+/// 更换游戏的加载器：重新生成继承版本 JSON 并下载依赖库，失败回滚；成功后更新记录并清理无引用的旧依赖库
+#[allow(clippy::too_many_arguments)]
+pub async fn change_loader(
+    ctx: &AppContext,
+    dm: &DownloadManager,
+    game_manager: &GameManager,
+    app_handle: &tauri::AppHandle,
+    game_name: &str,
+    loader_type: ModLoaderType,
+    loader_version: Option<String>,
+    token: &CancellationToken,
+) -> Result<Game, String> {
+    let mut game = game_manager
+        .get_game(game_name)
+        .ok_or_else(|| format!("游戏不存在：{}", game_name))?;
+    if is_game_active(game_name) {
+        return Err("游戏正在启动或运行中，请先关闭游戏再更换加载器".to_string());
+    }
+    let loader_version = loader_version.filter(|_| loader_type != ModLoaderType::Vanilla);
+    if game.loader_type == loader_type && game.loader_version == loader_version {
+        return Err("加载器未发生变化".to_string());
+    }
+
+    let game_dir = PathBuf::from(&game.path);
+    let mc_version = game.version_id.clone();
+    let parent_path = ctx.version_json_in_dir(&game_dir, &mc_version);
+    let parent_json: serde_json::Value = fs::read_to_string(&parent_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .ok_or_else(|| {
+            format!(
+                "原版 JSON 不存在或已损坏，请先修复游戏: {}",
+                parent_path.display()
+            )
+        })?;
+    let libraries_dir = ctx.libraries_dir();
+
+    log_info!(
+        "更换加载器: {} | {:?} {:?} → {:?} {:?}",
+        game_name,
+        game.loader_type,
+        game.loader_version,
+        loader_type,
+        loader_version
+    );
+
+    // 旧加载器独有的依赖库（profile 中的库 + 安装器），成功后按引用情况清理
+    let mut candidates = find_loader_profile(&game_dir, &mc_version)
+        .and_then(|id| fs::read_to_string(game_dir.join(format!("{}.json", id))).ok())
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .map(|json| profile_library_paths(&json))
+        .unwrap_or_default();
+    if let Some(installer) = game
+        .loader_version
+        .as_deref()
+        .and_then(|v| installer_library_path(game.loader_type, &mc_version, v))
+    {
+        candidates.push(installer);
+    }
+
+    let backup = ProfileBackup::create(&game_dir, &mc_version)?;
+    // 本次可能写入的新 profile id（回滚时只删除该文件，目录内其他 profile 保持不动）
+    let mut written: Option<String> = None;
+    let result: Result<(), String> = async {
+        let profile = resolve_loader_profile(
+            dm,
+            &libraries_dir,
+            &mc_version,
+            &parent_json,
+            loader_type,
+            loader_version.as_deref(),
+            Some(token),
+        )
+        .await?;
+        if let Some(ref profile) = profile {
+            let tracker = Arc::new(DownloadProgressTracker::new(
+                app_handle.clone(),
+                game_name,
+                profile.libraries.iter().map(|f| f.size).sum(),
+                profile.libraries.len() as u64,
+            ));
+            let mut completed = 0usize;
+            dm.download_group_with_progress(
+                &profile.libraries,
+                &libraries_dir,
+                "downloading_loader",
+                &tracker,
+                &mut completed,
+                Some(token),
+            )
+            .await?;
+            tracker.set_phase("installing_loader");
+            let java_major = parent_json["javaVersion"]["majorVersion"]
+                .as_u64()
+                .unwrap_or(8) as u32;
+            written = Some(profile.id.clone());
+            finish_install(ctx, &game_dir, &mc_version, java_major, profile).await?;
+        }
+
+        game.optifine = match (loader_type, &loader_version) {
+            (ModLoaderType::OptiFine, Some(version)) => Some(OptiFineInstall {
+                version: version.clone(),
+                mode: OptiFineMode::Standalone,
+            }),
+            _ => game.optifine.take().filter(|o| {
+                o.mode == OptiFineMode::Mod
+                    && super::optifine::check_combination(loader_type).is_ok()
+            }),
+        };
+        game.loader_type = loader_type;
+        game.loader_version = loader_version.clone();
+        game_manager.save_record(&game)
+    }
+    .await;
+
+    if let Err(e) = result {
+        if let Some(id) = written {
+            let _ = fs::remove_file(game_dir.join(format!("{}.json", id)));
+        }
+        if let Some(Err(restore)) = backup.map(|b| b.restore()) {
+            log_warn!("更换加载器失败: {}；回滚失败: {}", e, restore);
+            return Err(format!("更换加载器失败: {}；回滚失败: {}", e, restore));
+        }
+        log_warn!("更换加载器失败，已回滚: {}", e);
+        return Err(format!("更换加载器失败（已回滚）: {}", e));
+    }
+    if let Some(backup) = backup {
+        backup.discard();
+    }
+
+    let game_dirs: Vec<PathBuf> = game_manager
+        .scan_games()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|g| PathBuf::from(g.path))
+        .collect();
+    let pruned = prune_libraries(
+        &libraries_dir,
+        &candidates,
+        &referenced_libraries(&game_dirs),
+    );
+    log_info!(
+        "加载器已更换: {}，清理无引用依赖库 {} 个",
+        game_name,
+        pruned
+    );
+
+    game_manager
+        .get_game(game_name)
+        .ok_or_else(|| format!("游戏不存在：{}", game_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wecraft-swap-{}-{}", tag, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_json(path: &Path, json: serde_json::Value) {
+        fs::write(path, json.to_string()).unwrap();
+    }
+
+    #[test]
+    fn backup_hides_profile_until_restored() {
+        let dir = temp_dir("backup");
+        write_json(&dir.join("1.20.4.json"), json!({ "id": "1.20.4" }));
+        write_json(
+            &dir.join("fabric-loader-0.15.7-1.20.4.json"),
+            json!({ "id": "fabric-loader-0.15.7-1.20.4", "inheritsFrom": "1.20.4" }),
+        );
+
+        let backup = ProfileBackup::create(&dir, "1.20.4").unwrap().unwrap();
+        assert_eq!(find_loader_profile(&dir, "1.20.4"), None);
+        backup.restore().unwrap();
+        assert_eq!(
+            find_loader_profile(&dir, "1.20.4").as_deref(),
+            Some("fabric-loader-0.15.7-1.20.4")
+        );
+
+        ProfileBackup::create(&dir, "1.20.4")
+            .unwrap()
+            .unwrap()
+            .discard();
+        assert_eq!(find_loader_profile(&dir, "1.20.4"), None);
+        assert!(ProfileBackup::create(&dir, "1.20.4").unwrap().is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_keeps_libraries_referenced_by_other_games() {
+        let root = temp_dir("prune");
+        let libraries_dir = root.join("libraries");
+        let old_profile = json!({
+            "libraries": [
+                { "name": "net.fabricmc:fabric-loader:0.15.7" },
+                { "name": "org.ow2.asm:asm:9.6" }
+            ]
+        });
+        let candidates = profile_library_paths(&old_profile);
+        assert_eq!(
+            candidates,
+            [
+                "net/fabricmc/fabric-loader/0.15.7/fabric-loader-0.15.7.jar",
+                "org/ow2/asm/asm/9.6/asm-9.6.jar"
+            ]
+        );
+        for relative in &candidates {
+            let path = libraries_dir.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"jar").unwrap();
+        }
+
+        // 另一个游戏仍引用 asm
+        let other = root.join("versions/other");
+        fs::create_dir_all(&other).unwrap();
+        write_json(
+            &other.join("fabric-loader-0.16.0-1.21.json"),
+            json!({ "libraries": [{ "name": "org.ow2.asm:asm:9.6" }] }),
+        );
+
+        let referenced = referenced_libraries(&[other]);
+        assert_eq!(prune_libraries(&libraries_dir, &candidates, &referenced), 1);
+        assert!(!libraries_dir.join("net").exists());
+        assert!(libraries_dir.join(&candidates[1]).is_file());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
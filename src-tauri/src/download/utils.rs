@@ -21,7 +21,14 @@ pub fn mirror_url(url: &str) -> Option<String> {
         ("https://launcher.mojang.com/", "/mc/launcher/"),
         ("https://maven.fabricmc.net/", "/maven/"),
         ("https://files.minecraftforge.net/maven/", "/maven/"),
+        ("https://maven.minecraftforge.net/", "/maven/"),
+        ("https://maven.neoforged.net/releases/", "/maven/"),
         ("https://meta.fabricmc.net/", "/fabric-meta/"),
+        ("https://maven.quiltmc.org/repository/release/", "/maven/"),
+        (
+            "https://repo.legacyfabric.net/repository/legacyfabric/",
+            "/maven/",
+        ),
     ];
 
     for (prefix, suffix) in RULES {
@@ -94,21 +101,21 @@ pub fn should_use_library(library: &Library) -> bool {
     rules_allow(&library.rules)
 }
 
-/// 评估平台规则列表（用于库文件与启动参数）
+/// 评估平台规则列表（用于库文件与启动参数），不启用任何特性
 pub fn rules_allow(rules: &[Rule]) -> bool {
+    rules_allow_with(rules, &[])
+}
+
+/// 评估平台规则列表，features 为本次启动启用的特性
+/// （对应 version.json 的 features 规则：is_demo_user / has_custom_resolution / is_quick_play_* 等）。
+/// 规则声明的特性取值与是否启用一致时匹配，未列出的特性视为未启用
+pub fn rules_allow_with(rules: &[Rule], features: &[&str]) -> bool {
     if rules.is_empty() {
         return true;
     }
 
-    /// 当前启动器启用的特性（对应 version.json 的 features 规则）：
-    /// 未启用任何特性（is_demo_user / has_custom_resolution / is_quick_play_* 等均为 false），
-    /// 因此规则声明某特性为 true 时不匹配、为 false 时匹配
-    fn feature_state(_key: &str) -> bool {
-        false
-    }
-
     /// 单条规则是否命中（os 匹配 且 features 匹配），不命中则跳过该规则
-    fn rules_match(rule: &Rule) -> bool {
+    fn rules_match(rule: &Rule, enabled: &[&str]) -> bool {
         let os_match = match &rule.os {
             Some(os) => {
                 let os_name_match =
@@ -128,7 +135,7 @@ pub fn rules_allow(rules: &[Rule]) -> bool {
         let features_match = match &rule.features {
             Some(features) => features
                 .iter()
-                .all(|(key, want)| feature_state(key) == *want),
+                .all(|(key, want)| enabled.contains(&key.as_str()) == *want),
             None => true,
         };
 
@@ -138,7 +145,7 @@ pub fn rules_allow(rules: &[Rule]) -> bool {
     let mut matched_any = false;
     let mut allowed = true;
     for rule in rules {
-        if !rules_match(rule) {
+        if !rules_match(rule, features) {
             continue;
         }
         matched_any = true;
@@ -156,36 +163,15 @@ pub fn rules_allow(rules: &[Rule]) -> bool {
     allowed
 }
 
-/// 获取当前平台的原生库分类器名称
+/// 获取当前平台的原生库分类器，返回 (classifier, 系统键)。
+/// 版本 JSON 的 natives 以系统名为键（linux / osx / windows），值即分类器名称，
+/// 其中 `${arch}` 按当前位数替换为 32 / 64（如 natives-windows-${arch}）
 pub fn get_native_classifier(library: &Library) -> Option<(String, String)> {
     let natives = library.natives.as_ref()?;
     let os = get_current_os();
-    let arch = get_current_arch();
-
-    let native_key = match os {
-        "windows" => {
-            if arch == "x64" {
-                "windows-x64"
-            } else {
-                "windows-x86"
-            }
-        }
-        "osx" => "osx",
-        "linux" => {
-            if arch == "x64" {
-                "linux-x64"
-            } else if arch == "arm64" {
-                "linux-arm64"
-            } else {
-                "linux-x86"
-            }
-        }
-        _ => return None,
-    };
-
-    let classifier_suffix = natives.get(native_key)?;
-    let classifier = format!("natives-{}-{}", os, classifier_suffix);
-    Some((classifier, native_key.to_string()))
+    let bits = if get_current_arch() == "x86" { "32" } else { "64" };
+    let classifier = natives.get(os)?.replace("${arch}", bits);
+    Some((classifier, os.to_string()))
 }
 
 #[cfg(test)]
@@ -210,6 +196,15 @@ mod tests {
             mapped,
             "https://bmclapi2.bangbang93.com/mc/launcher/mc/game/1.20.4/"
         );
+
+        let mapped = mirror_url(
+            "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-loader/0.26.0/quilt-loader-0.26.0.jar",
+        )
+        .unwrap();
+        assert_eq!(
+            mapped,
+            "https://bmclapi2.bangbang93.com/maven/org/quiltmc/quilt-loader/0.26.0/quilt-loader-0.26.0.jar"
+        );
     }
 
     #[test]
@@ -318,19 +313,33 @@ mod tests {
     }
 
     #[test]
-    fn get_native_classifier_detects_current_platform() {
-        let native_key = match (get_current_os(), get_current_arch()) {
-            ("windows", "x64") => "windows-x64",
-            ("windows", _) => "windows-x86",
-            ("osx", _) => "osx",
-            ("linux", "x64") => "linux-x64",
-            ("linux", "arm64") => "linux-arm64",
-            ("linux", _) => "linux-x86",
-            _ => return,
+    fn rules_allow_with_enabled_features() {
+        use std::collections::HashMap;
+
+        let quick_play = Rule {
+            action: "allow".to_string(),
+            features: Some(HashMap::from([(
+                "is_quick_play_multiplayer".to_string(),
+                true,
+            )])),
+            os: None,
         };
+        assert!(!rules_allow(std::slice::from_ref(&quick_play)));
+        assert!(rules_allow_with(
+            std::slice::from_ref(&quick_play),
+            &["is_quick_play_multiplayer"]
+        ));
+        assert!(!rules_allow_with(&[quick_play], &["has_custom_resolution"]));
+    }
+
+    #[test]
+    fn get_native_classifier_detects_current_platform() {
+        let os = get_current_os();
+        let bits = if get_current_arch() == "x86" { "32" } else { "64" };
 
         let mut natives = std::collections::HashMap::new();
-        natives.insert(native_key.to_string(), "test".to_string());
+        natives.insert(os.to_string(), format!("natives-{}-${{arch}}", os));
+        natives.insert("other-os".to_string(), "natives-other".to_string());
         let library = Library {
             name: "test".to_string(),
             downloads: None,
@@ -341,7 +350,7 @@ mod tests {
         };
 
         let (classifier, key) = get_native_classifier(&library).unwrap();
-        assert_eq!(key, native_key);
-        assert!(classifier.starts_with("natives-"));
+        assert_eq!(key, os);
+        assert_eq!(classifier, format!("natives-{}-{}", os, bits));
     }
 }
@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 use super::models::{Game, GameSettings};
 use crate::app_context::AppContext;
+use crate::download::loader::find_loader_profile;
 use crate::modloader::ModLoaderType;
 use crate::log_info;
 
@@ -287,11 +288,14 @@ impl GameManager {
                     }
                 }
 
-                // 损坏判定：记录版本但目录内缺失对应 jar（下载中断/文件被删），或空目录无任何产物
+                // 损坏判定：记录版本但目录内缺失对应 jar（下载中断/文件被删），或空目录无任何产物；
+                // 记录了加载器但目录内没有继承该版本的加载器 profile（加载器安装失败）同样视为损坏
                 if game.version_id.is_empty() {
                     game.broken = candidates.is_empty();
                 } else {
-                    game.broken = !path.join(format!("{}.jar", game.version_id)).is_file();
+                    game.broken = !path.join(format!("{}.jar", game.version_id)).is_file()
+                        || (game.loader_type != ModLoaderType::Vanilla
+                            && find_loader_profile(&path, &game.version_id).is_none());
                 }
 
                 // 空壳判定：目录内除记录与图标外无任何文件（未下载的空壳）→ 前端直接不显示。
@@ -487,6 +491,26 @@ mod tests {
         assert!(!games[0].broken);
     }
 
+    #[test]
+    fn scan_marks_missing_loader_profile() {
+        let gm = manager("loader");
+        gm.create_game("fabric-game", "1.20.4", ModLoaderType::Fabric, Some("0.15.7".into()), None)
+            .unwrap();
+        let dir = gm.ctx.game_dir("fabric-game");
+        fs::write(dir.join("1.20.4.jar"), b"jar").unwrap();
+        // 加载器安装失败：只有原版产物 → 损坏
+        assert!(gm.scan_games().unwrap()[0].broken);
+
+        fs::write(
+            dir.join("fabric-loader-0.15.7-1.20.4.json"),
+            br#"{"id":"fabric-loader-0.15.7-1.20.4","inheritsFrom":"1.20.4"}"#,
+        )
+        .unwrap();
+        let games = gm.scan_games().unwrap();
+        assert_eq!(games[0].version_id, "1.20.4");
+        assert!(!games[0].broken);
+    }
+
     #[test]
     fn scan_skips_hidden_dirs() {
         let gm = manager("hidden");
@@ -1,5 +1,7 @@
+use crate::launch::JvmProfile;
 use crate::modloader::ModLoaderType;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 /// 游戏文件夹（侧边栏条目）：绝对路径 + 用户自定义名称
@@ -13,6 +15,25 @@ pub struct GameFolder {
     pub name: String,
 }
 
+/// OptiFine 安装方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OptiFineMode {
+    /// 独立版本：Patcher 生成的库 + launchwrapper profile
+    Standalone,
+    /// 模组：jar 放入 mods/（Fabric 附带 OptiFabric）
+    Mod,
+}
+
+/// 游戏已安装的 OptiFine
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OptiFineInstall {
+    /// OptiFine 版本（如 HD_U_I6）
+    pub version: String,
+    /// 安装方式
+    pub mode: OptiFineMode,
+}
+
 /// 版本隔离模式
 ///
 /// 决定依赖库（libraries/assets）的存储方式：
@@ -50,6 +71,12 @@ pub struct GameSettings {
     pub min_memory: Option<u64>,
     /// 最大内存（MB）
     pub max_memory: Option<u64>,
+    /// 自动内存（启动时按物理内存、加载器与模组数量计算，忽略 max_memory）
+    #[serde(default)]
+    pub auto_memory: Option<bool>,
+    /// JVM 调优配置（GC 参数预设）
+    #[serde(default)]
+    pub jvm_profile: Option<JvmProfile>,
     /// JVM 附加参数列表
     pub jvm_args: Option<Vec<String>>,
     /// 版本隔离模式
@@ -72,6 +99,23 @@ pub struct GameSettings {
     pub server_address: Option<String>,
     /// 自动连接服务器端口
     pub server_port: Option<u16>,
+    /// 启动包装命令（如 gamemoderun / mangohud / prime-run，置于 java 之前）
+    pub wrapper_command: Option<Vec<String>>,
+    /// 附加环境变量（如 __GL_THREADED_OPTIMIZATIONS）
+    pub env_vars: Option<BTreeMap<String, String>>,
+    /// 启动前命令（游戏目录下经 shell 执行，失败则终止启动）
+    pub pre_launch_command: Option<String>,
+    /// 退出后命令（输出追加到游戏日志）
+    pub post_exit_command: Option<String>,
+    /// 资源限制（仅 Linux cgroups v2）
+    #[serde(default)]
+    pub resource_limits: Option<bool>,
+    /// CPU 权重（cpu.weight，1-10000）
+    #[serde(default)]
+    pub cpu_weight: Option<u32>,
+    /// 最大任务（线程）数（pids.max）
+    #[serde(default)]
+    pub pids_max: Option<u32>,
 }
 
 impl GameSettings {
@@ -89,6 +133,12 @@ impl GameSettings {
         if let Some(v) = update.max_memory {
             self.max_memory = Some(v);
         }
+        if let Some(v) = update.auto_memory {
+            self.auto_memory = Some(v);
+        }
+        if let Some(v) = update.jvm_profile {
+            self.jvm_profile = Some(v);
+        }
         if let Some(v) = &update.jvm_args {
             self.jvm_args = Some(v.clone());
         }
@@ -122,6 +172,27 @@ impl GameSettings {
         if let Some(v) = update.server_port {
             self.server_port = Some(v);
         }
+        if let Some(v) = &update.wrapper_command {
+            self.wrapper_command = Some(v.clone());
+        }
+        if let Some(v) = &update.env_vars {
+            self.env_vars = Some(v.clone());
+        }
+        if let Some(v) = &update.pre_launch_command {
+            self.pre_launch_command = Some(v.clone());
+        }
+        if let Some(v) = &update.post_exit_command {
+            self.post_exit_command = Some(v.clone());
+        }
+        if let Some(v) = update.resource_limits {
+            self.resource_limits = Some(v);
+        }
+        if let Some(v) = update.cpu_weight {
+            self.cpu_weight = Some(v);
+        }
+        if let Some(v) = update.pids_max {
+            self.pids_max = Some(v);
+        }
     }
 }
 
@@ -133,6 +204,8 @@ impl Default for GameSettings {
             java_version: None,
             min_memory: Some(4096),
             max_memory: Some(8192),
+            auto_memory: Some(false),
+            jvm_profile: Some(JvmProfile::None),
             jvm_args: None,
             isolation_mode: Some(IsolationMode::Isolated),
             width: Some(1280),
@@ -144,6 +217,13 @@ impl Default for GameSettings {
             player_name: None,
             server_address: None,
             server_port: None,
+            wrapper_command: None,
+            env_vars: None,
+            pre_launch_command: None,
+            post_exit_command: None,
+            resource_limits: Some(false),
+            cpu_weight: None,
+            pids_max: None,
         }
     }
 }
@@ -165,6 +245,9 @@ pub struct Game {
     pub loader_type: ModLoaderType,
     /// 模组加载器版本
     pub loader_version: Option<String>,
+    /// 已安装的 OptiFine（独立版本或随 Forge / Fabric 作为模组）
+    #[serde(default)]
+    pub optifine: Option<OptiFineInstall>,
     /// 自定义图标路径
     pub icon_path: Option<String>,
     /// 创建时间（Unix 时间戳）
@@ -185,6 +268,12 @@ pub struct Game {
     pub min_memory: u64,
     /// 最大内存（MB）
     pub max_memory: u64,
+    /// 自动内存
+    #[serde(default)]
+    pub auto_memory: bool,
+    /// JVM 调优配置
+    #[serde(default)]
+    pub jvm_profile: JvmProfile,
     /// JVM 附加参数列表
     pub jvm_args: Vec<String>,
     /// 版本隔离模式
@@ -207,6 +296,27 @@ pub struct Game {
     pub server_address: Option<String>,
     /// 自动连接服务器端口
     pub server_port: Option<u16>,
+    /// 启动包装命令（置于 java 之前）
+    #[serde(default)]
+    pub wrapper_command: Vec<String>,
+    /// 附加环境变量
+    #[serde(default)]
+    pub env_vars: BTreeMap<String, String>,
+    /// 启动前命令
+    #[serde(default)]
+    pub pre_launch_command: Option<String>,
+    /// 退出后命令
+    #[serde(default)]
+    pub post_exit_command: Option<String>,
+    /// 资源限制（仅 Linux cgroups v2）
+    #[serde(default)]
+    pub resource_limits: bool,
+    /// CPU 权重
+    #[serde(default)]
+    pub cpu_weight: Option<u32>,
+    /// 最大任务（线程）数
+    #[serde(default)]
+    pub pids_max: Option<u32>,
     // ==================== 计算字段（不持久化，但必须序列化给前端） ====================
     // 注意：不能使用 `#[serde(skip)]`（序列化+反序列化都跳过，前端收不到字段）。
     // `skip_deserializing` = 反序列化忽略（读记录文件时用默认值），序列化保留（invoke 返回前端）。
@@ -239,6 +349,7 @@ impl Game {
             version_id: version_id.to_string(),
             loader_type,
             loader_version,
+            optifine: None,
             icon_path,
             created_at: chrono::Utc::now().timestamp(),
             last_played: None,
@@ -248,6 +359,8 @@ impl Game {
             java_version: None,
             min_memory: 1024,
             max_memory: 2048,
+            auto_memory: false,
+            jvm_profile: JvmProfile::None,
             jvm_args: Vec::new(),
             isolation_mode: IsolationMode::Isolated,
             width: 1280,
@@ -259,6 +372,13 @@ impl Game {
             player_name: None,
             server_address: None,
             server_port: None,
+            wrapper_command: Vec::new(),
+            env_vars: BTreeMap::new(),
+            pre_launch_command: None,
+            post_exit_command: None,
+            resource_limits: false,
+            cpu_weight: None,
+            pids_max: None,
             path: String::new(),
             game_settings: None,
             broken: false,
@@ -281,6 +401,12 @@ impl Game {
         if let Some(v) = settings.max_memory {
             self.max_memory = v;
         }
+        if let Some(v) = settings.auto_memory {
+            self.auto_memory = v;
+        }
+        if let Some(v) = settings.jvm_profile {
+            self.jvm_profile = v;
+        }
         if let Some(v) = &settings.jvm_args {
             self.jvm_args = v.clone();
         }
@@ -314,6 +440,27 @@ impl Game {
         if let Some(v) = settings.server_port {
             self.server_port = Some(v);
         }
+        if let Some(v) = &settings.wrapper_command {
+            self.wrapper_command = v.clone();
+        }
+        if let Some(v) = &settings.env_vars {
+            self.env_vars = v.clone();
+        }
+        if let Some(v) = &settings.pre_launch_command {
+            self.pre_launch_command = Some(v.clone());
+        }
+        if let Some(v) = &settings.post_exit_command {
+            self.post_exit_command = Some(v.clone());
+        }
+        if let Some(v) = settings.resource_limits {
+            self.resource_limits = v;
+        }
+        if let Some(v) = settings.cpu_weight {
+            self.cpu_weight = Some(v);
+        }
+        if let Some(v) = settings.pids_max {
+            self.pids_max = Some(v);
+        }
     }
 
     /// 派生设置视图（供前端读取）
@@ -324,6 +471,8 @@ impl Game {
             java_version: self.java_version.clone(),
             min_memory: Some(self.min_memory),
             max_memory: Some(self.max_memory),
+            auto_memory: Some(self.auto_memory),
+            jvm_profile: Some(self.jvm_profile),
             jvm_args: Some(self.jvm_args.clone()),
             isolation_mode: Some(self.isolation_mode.clone()),
             width: Some(self.width),
@@ -335,6 +484,13 @@ impl Game {
             player_name: self.player_name.clone(),
             server_address: self.server_address.clone(),
             server_port: self.server_port,
+            wrapper_command: Some(self.wrapper_command.clone()),
+            env_vars: Some(self.env_vars.clone()),
+            pre_launch_command: self.pre_launch_command.clone(),
+            post_exit_command: self.post_exit_command.clone(),
+            resource_limits: Some(self.resource_limits),
+            cpu_weight: self.cpu_weight,
+            pids_max: self.pids_max,
         }
     }
 }
@@ -373,6 +529,38 @@ mod tests {
         assert!(loaded.game_settings.is_none());
     }
 
+    /// 旧记录文件没有包装命令 / 环境变量字段，读取时按空值处理
+    #[test]
+    fn wrapper_and_env_default_for_old_records() {
+        let game = Game::new("test", "1.20.4", ModLoaderType::Vanilla, None, None);
+        let mut json = serde_json::to_value(&game).unwrap();
+        let record = json.as_object_mut().unwrap();
+        record.remove("wrapper_command");
+        record.remove("env_vars");
+        record.remove("auto_memory");
+        record.remove("jvm_profile");
+        record.remove("resource_limits");
+        let loaded: Game = serde_json::from_value(json).unwrap();
+        assert!(loaded.wrapper_command.is_empty());
+        assert!(loaded.env_vars.is_empty());
+        assert!(!loaded.auto_memory);
+        assert_eq!(loaded.jvm_profile, JvmProfile::None);
+        assert!(!loaded.resource_limits);
+
+        let mut game = loaded;
+        let update: GameSettings = serde_json::from_value(serde_json::json!({
+            "wrapper_command": ["gamemoderun", "mangohud"],
+            "env_vars": { "__GL_THREADED_OPTIMIZATIONS": "1" }
+        }))
+        .unwrap();
+        game.apply_settings(&update);
+        assert_eq!(game.wrapper_command, vec!["gamemoderun", "mangohud"]);
+        assert_eq!(
+            game.to_game_settings().env_vars.unwrap()["__GL_THREADED_OPTIMIZATIONS"],
+            "1"
+        );
+    }
+
     /// use_game_settings 开关必须真实持久化与回读（而非硬编码 true）
     #[test]
     fn use_game_settings_roundtrip() {
@@ -5,8 +5,12 @@ use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use crate::app_context::AppContext;
+use crate::download::DownloadManager;
+use crate::download::utils::{get_current_arch, get_current_os};
+use crate::log_warn;
 use tauri::State;
 
+mod runtime;
 mod store;
 
 // 扫描策略（对齐 PCL 四层搜索源）：
@@ -16,6 +20,7 @@ mod store;
 // 3. 环境变量：JAVA_HOME / JDK_HOME / JRE_HOME 与 PATH（进程环境 + 系统/用户注册表兜底），
 //    PATH 条目既按 bin 目录也按 JDK/JRE 根目录探测
 // 4. 预设目录递归：Program Files(x86)\Java、{game_root}\runtime、启动器 runtime、游戏实例目录
+//    （Linux 为 /usr/lib/jvm 等系统目录 + 启动器 runtime 下的托管运行时）
 // 候选路径统一经 java -version 校验，解析主版本号与 64 位标记，按 Java Home 目录去重。
 
 /// Java 安装信息结构体
@@ -123,7 +128,7 @@ impl NoConsoleWindow for Command {
 }
 
 /// 校验候选 java 可执行文件：存在 + java -version 可运行，返回封装后的安装信息
-fn probe_java(java_path: &Path) -> Option<JavaInstallation> {
+pub(crate) fn probe_java(java_path: &Path) -> Option<JavaInstallation> {
     if !java_path.exists() {
         return None;
     }
@@ -154,7 +159,7 @@ fn probe_java(java_path: &Path) -> Option<JavaInstallation> {
 // =============================================================================
 
 #[cfg(target_os = "linux")]
-fn scan_java_impl(_ctx: &AppContext) -> Result<Vec<JavaInstallation>, String> {
+fn scan_java_impl(ctx: &AppContext) -> Result<Vec<JavaInstallation>, String> {
     use std::collections::HashSet;
     use std::fs;
     use std::fs::symlink_metadata;
@@ -165,7 +170,13 @@ fn scan_java_impl(_ctx: &AppContext) -> Result<Vec<JavaInstallation>, String> {
 
     let mut javas: Vec<JavaInstallation> = Vec::new();
     let mut seen: HashSet<PathBuf> = HashSet::new();
-    let linux_java_paths = [USR_LIB_JVM, USR_LIB_JAVA, USR_JAVA];
+    let linux_java_paths = [
+        PathBuf::from(USR_LIB_JVM),
+        PathBuf::from(USR_LIB_JAVA),
+        PathBuf::from(USR_JAVA),
+        ctx.launcher_runtime_dir(),
+        ctx.game_runtime_dir(),
+    ];
 
     for path in linux_java_paths {
         if let Ok(entries) = fs::read_dir(path) {
@@ -517,6 +528,11 @@ static JAVA_SCAN_MEMO: Mutex<Option<(Instant, Vec<JavaInstallation>)>> = Mutex::
 pub fn scan_java_installations(
     ctx: State<'_, AppContext>,
 ) -> Result<Vec<JavaInstallation>, String> {
+    scan_java_cached(&ctx)
+}
+
+/// 扫描 Java 安装（内存缓存 → 磁盘缓存 → 全量扫描）
+pub(crate) fn scan_java_cached(ctx: &AppContext) -> Result<Vec<JavaInstallation>, String> {
     // 内存30秒缓存
     if let Ok(guard) = JAVA_SCAN_MEMO.lock() {
         if let Some((at, cached)) = &*guard {
@@ -541,7 +557,7 @@ pub fn scan_java_installations(
     }
 
     // 全量扫描
-    let javas = scan_java_impl(&ctx)?;
+    let javas = scan_java_impl(ctx)?;
 
     // 回写磁盘缓存与内存缓存
     if !javas.is_empty() {
@@ -554,6 +570,76 @@ pub fn scan_java_installations(
     Ok(javas)
 }
 
+/// 登记新安装的 Java 到内存缓存与磁盘缓存（后续扫描无需重新探测）
+fn register_java(ctx: &AppContext, install: &JavaInstallation) {
+    let mut javas = scan_java_cached(ctx).unwrap_or_default();
+    javas.retain(|j| j.path != install.path);
+    javas.push(install.clone());
+    if let Err(e) = store::save_java_cache(&ctx.launcher_config_path(), &javas) {
+        log_warn!("写入 Java 缓存失败: {}", e);
+    }
+    if let Ok(mut guard) = JAVA_SCAN_MEMO.lock() {
+        *guard = Some((Instant::now(), javas));
+    }
+}
+
+/// 下载 Mojang 托管 Java 运行时（component 如 java-runtime-gamma / jre-legacy）
+/// 到启动器 runtime 目录，校验后登记为可用 Java
+#[tauri::command]
+pub async fn install_java_runtime(
+    component: String,
+    ctx: State<'_, AppContext>,
+    dm: State<'_, DownloadManager>,
+) -> Result<JavaInstallation, String> {
+    let platform = runtime::runtime_platform(get_current_os(), get_current_arch())
+        .ok_or_else(|| {
+            format!(
+                "当前平台没有 Mojang Java 运行时: {}-{}",
+                get_current_os(),
+                get_current_arch()
+            )
+        })?;
+    let java = runtime::install_runtime(
+        &dm,
+        runtime::JAVA_RUNTIME_MANIFEST_URL,
+        platform,
+        &component,
+        &ctx.launcher_runtime_dir().join(&component),
+    )
+    .await?;
+    let install =
+        probe_java(&java).ok_or_else(|| format!("Java 运行时无法执行: {}", java.display()))?;
+    register_java(&ctx, &install);
+    Ok(install)
+}
+
+/// 版本号数字分量（"1.8.0_372" → [1, 8, 0, 372]），用于同主版本内比较补丁号
+fn version_key(version: &str) -> Vec<u32> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// 按版本要求自动选择 Java：
+/// 1. 主版本优先精确匹配，否则取高于要求的最低主版本
+/// 2. 同主版本内依次比较：64 位 > 符合 JDK/JRE 偏好 > 补丁号更新
+pub fn select_java(
+    installs: &[JavaInstallation],
+    required: u32,
+    prefer_jdk: bool,
+) -> Option<&JavaInstallation> {
+    let target = installs
+        .iter()
+        .map(|j| j.major_version)
+        .filter(|&m| m >= required)
+        .min()?;
+    installs
+        .iter()
+        .filter(|j| j.major_version == target)
+        .max_by_key(|j| (j.is_64bit, j.is_jdk == prefer_jdk, version_key(&j.version)))
+}
+
 /// 获取指定 Java 路径的版本信息
 #[tauri::command]
 pub async fn get_java_version(path: String) -> Result<JavaInstallation, String> {
@@ -616,6 +702,44 @@ mod tests {
         assert!(!info.is_64bit);
     }
 
+    fn install(version: &str, is_jdk: bool, is_64bit: bool) -> JavaInstallation {
+        JavaInstallation {
+            path: PathBuf::from(format!("/jvm/{}-{}-{}/bin/java", version, is_jdk, is_64bit)),
+            version: version.to_string(),
+            vendor: "Test".to_string(),
+            is_jdk,
+            major_version: parse_major_version(version),
+            is_64bit,
+        }
+    }
+
+    #[test]
+    fn test_select_java_prefers_exact_major_64bit_and_newest_patch() {
+        let installs = vec![
+            install("1.8.0_372", false, false),
+            install("1.8.0_312", false, true),
+            install("1.8.0_392", false, true),
+            install("17.0.9", true, true),
+            install("21.0.1", false, true),
+        ];
+        let picked = select_java(&installs, 8, false).unwrap();
+        assert_eq!(picked.version, "1.8.0_392");
+        assert_eq!(select_java(&installs, 17, false).unwrap().version, "17.0.9");
+        // 无精确匹配时取高于要求的最低主版本
+        assert_eq!(select_java(&installs, 16, false).unwrap().major_version, 17);
+        assert!(select_java(&installs, 22, false).is_none());
+    }
+
+    #[test]
+    fn test_select_java_jdk_preference() {
+        let installs = vec![
+            install("17.0.10", false, true),
+            install("17.0.8", true, true),
+        ];
+        assert!(!select_java(&installs, 17, false).unwrap().is_jdk);
+        assert!(select_java(&installs, 17, true).unwrap().is_jdk);
+    }
+
     #[test]
     fn test_scan_java() {
         match scan_java_impl(&AppContext::new(
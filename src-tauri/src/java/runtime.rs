@@ -0,0 +1,481 @@
+//! Mojang 托管 Java 运行时下载（java-runtime 清单）
+//!
+//! 总清单 all.json 按平台（linux / windows-x64 / mac-os-arm64 ...）列出各组件
+//! （java-runtime-gamma / java-runtime-delta / jre-legacy ...）的组件清单地址；
+//! 组件清单的 files 节列出运行时内全部条目（file / directory / link），
+//! 文件带 SHA1 与可执行标记，链接带相对目标。
+//! 运行时安装到 {launcher_runtime_dir}/{component}，组件清单保存为同级的 {component}.json。
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::download::DownloadManager;
+use crate::log_info;
+
+/// Mojang java-runtime 总清单地址
+pub const JAVA_RUNTIME_MANIFEST_URL: &str = "https://piston-meta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+
+/// 运行时文件并发下载数
+const CONCURRENCY: usize = 8;
+
+/// 远程文件（地址 + SHA1 + 大小）
+#[derive(Deserialize, Clone, Debug)]
+struct RemoteFile {
+    sha1: String,
+    size: u64,
+    url: String,
+}
+
+/// 总清单中某平台某组件的一个版本
+#[derive(Deserialize, Debug)]
+struct RuntimeRelease {
+    manifest: RemoteFile,
+    version: RuntimeVersion,
+}
+
+#[derive(Deserialize, Debug)]
+struct RuntimeVersion {
+    name: String,
+}
+
+/// 组件清单
+#[derive(Deserialize, Debug)]
+struct ComponentManifest {
+    files: BTreeMap<String, RuntimeEntry>,
+}
+
+/// 组件清单条目
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RuntimeEntry {
+    Directory,
+    File {
+        #[serde(default)]
+        executable: bool,
+        downloads: EntryDownloads,
+    },
+    Link {
+        target: String,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+struct EntryDownloads {
+    raw: RemoteFile,
+}
+
+/// 由 get_current_os / get_current_arch 映射到清单中的平台键（无对应运行时返回 None）
+pub fn runtime_platform(os: &str, arch: &str) -> Option<&'static str> {
+    match (os, arch) {
+        ("windows", "x64") => Some("windows-x64"),
+        ("windows", "x86") => Some("windows-x86"),
+        ("windows", "arm64") => Some("windows-arm64"),
+        ("linux", "x64") => Some("linux"),
+        ("linux", "x86") => Some("linux-i386"),
+        ("osx", "x64") => Some("mac-os"),
+        ("osx", "arm64") => Some("mac-os-arm64"),
+        _ => None,
+    }
+}
+
+/// 运行时目录内的 java 可执行文件（macOS 运行时位于 jre.bundle 内）
+pub fn java_executable(home: &Path) -> Option<PathBuf> {
+    let exe = if cfg!(windows) { "java.exe" } else { "java" };
+    [
+        home.join("bin").join(exe),
+        home.join("jre.bundle/Contents/Home/bin").join(exe),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
+/// 清单中的相对路径不得越出运行时目录
+fn safe_join(dest: &Path, rel: &str) -> Result<PathBuf, String> {
+    let rel = Path::new(rel);
+    if rel
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("运行时清单包含非法路径: {}", rel.display()));
+    }
+    Ok(dest.join(rel))
+}
+
+/// 校验链接目标：以链接所在目录解析相对目标后仍须位于 dest 内（拒绝绝对路径与越界的 `..`）
+fn check_link_target(dest: &Path, path: &Path, target: &str) -> Result<(), String> {
+    let invalid = || format!("运行时清单包含非法链接: {} -> {}", path.display(), target);
+    let parent = path
+        .parent()
+        .and_then(|p| p.strip_prefix(dest).ok())
+        .ok_or_else(invalid)?;
+    let mut resolved: Vec<Component> = parent.components().collect();
+    for c in Path::new(target).components() {
+        match c {
+            Component::Normal(_) => resolved.push(c),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop().ok_or_else(invalid)?;
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(())
+}
+
+/// GET 并解析 JSON
+async fn fetch_json<T: DeserializeOwned>(dm: &DownloadManager, url: &str) -> Result<T, String> {
+    dm.client()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("获取 Java 运行时清单失败: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("获取 Java 运行时清单失败: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("解析 Java 运行时清单失败: {}", e))
+}
+
+/// 设置可执行权限（仅 Unix）
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("设置可执行权限失败 {}: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// 创建符号链接（已存在则替换；非 Unix 平台跳过，Windows 运行时清单不含链接）
+#[cfg(unix)]
+fn create_link(path: &Path, target: &str) -> Result<(), String> {
+    if std::fs::symlink_metadata(path).is_ok() {
+        std::fs::remove_file(path)
+            .map_err(|e| format!("替换链接失败 {}: {}", path.display(), e))?;
+    }
+    std::os::unix::fs::symlink(target, path)
+        .map_err(|e| format!("创建链接失败 {}: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn create_link(path: &Path, target: &str) -> Result<(), String> {
+    log_info!("跳过运行时链接: {} -> {}", path.display(), target);
+    Ok(())
+}
+
+/// 下载并安装指定平台的 Java 运行时组件到 dest，返回 java 可执行文件路径。
+///
+/// 组件清单与每个文件都经 SHA1 校验；已存在且校验通过的文件跳过下载，
+/// 因此重复调用即为增量修复。
+pub async fn install_runtime(
+    dm: &DownloadManager,
+    manifest_url: &str,
+    platform: &str,
+    component: &str,
+    dest: &Path,
+) -> Result<PathBuf, String> {
+    let mut all: HashMap<String, HashMap<String, Vec<RuntimeRelease>>> =
+        fetch_json(dm, manifest_url).await?;
+    let release = all
+        .get_mut(platform)
+        .and_then(|p| p.remove(component))
+        .and_then(|v| v.into_iter().next())
+        .ok_or_else(|| format!("平台 {} 没有可用的 Java 运行时 {}", platform, component))?;
+    log_info!(
+        "☕ 安装 Java 运行时 {} {}（{}）到 {}",
+        component,
+        release.version.name,
+        platform,
+        dest.display()
+    );
+
+    let manifest_path = dest.with_file_name(format!("{}.json", component));
+    dm.download_file_if_needed(
+        &release.manifest.url,
+        &manifest_path,
+        Some(&release.manifest.sha1),
+        Some(release.manifest.size),
+        &None,
+        None,
+    )
+    .await?;
+    let manifest: ComponentManifest = serde_json::from_str(
+        &std::fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("读取 Java 运行时清单失败: {}", e))?,
+    )
+    .map_err(|e| format!("解析 Java 运行时清单失败: {}", e))?;
+
+    let mut files = Vec::new();
+    let mut links = Vec::new();
+    for (rel, entry) in manifest.files {
+        let path = safe_join(dest, &rel)?;
+        match entry {
+            RuntimeEntry::Directory => std::fs::create_dir_all(&path)
+                .map_err(|e| format!("创建目录失败 {}: {}", path.display(), e))?,
+            RuntimeEntry::File {
+                executable,
+                downloads,
+            } => files.push((path, downloads.raw, executable)),
+            RuntimeEntry::Link { target } => links.push((path, target)),
+        }
+    }
+
+    let total = files.len();
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(CONCURRENCY));
+    let mut set = tokio::task::JoinSet::new();
+    for (path, remote, executable) in files {
+        let dm = dm.clone();
+        let semaphore = semaphore.clone();
+        set.spawn(async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .map_err(|e| format!("获取并发许可失败: {}", e))?;
+            dm.download_file_if_needed(
+                &remote.url,
+                &path,
+                Some(&remote.sha1),
+                Some(remote.size),
+                &None,
+                None,
+            )
+            .await?;
+            if executable {
+                set_executable(&path)?;
+            }
+            Ok::<(), String>(())
+        });
+    }
+    while let Some(res) = set.join_next().await {
+        res.map_err(|e| format!("下载任务失败: {}", e))??;
+    }
+
+    for (path, target) in &links {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("创建目录失败 {}: {}", parent.display(), e))?;
+        }
+        check_link_target(dest, path, target)?;
+        create_link(path, target)?;
+    }
+    log_info!(
+        "✅ Java 运行时 {} 安装完成（{} 个文件，{} 个链接）",
+        component,
+        total,
+        links.len()
+    );
+
+    java_executable(dest).ok_or_else(|| format!("Java 运行时 {} 缺少 java 可执行文件", component))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sha1::{Digest, Sha1};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+
+    type Routes = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+    /// 本地 HTTP 替身：按请求路径返回路由表中的内容，未知路径 404（路由表可在启动后填充）
+    fn serve() -> (String, Routes) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Routes = Arc::default();
+        let served = routes.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let routes = served.clone();
+                std::thread::spawn(move || {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut buf) {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&request);
+                    let path = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let body = routes.lock().unwrap().get(&path).cloned();
+                    let (status, body) = match body {
+                        Some(body) => ("200 OK", body),
+                        None => ("404 Not Found", Vec::new()),
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        body.len()
+                    );
+                    let _ = stream.write_all(&body);
+                });
+            }
+        });
+        (base, routes)
+    }
+
+    fn remote(base: &str, path: &str, data: &[u8]) -> serde_json::Value {
+        json!({
+            "sha1": format!("{:x}", Sha1::digest(data)),
+            "size": data.len(),
+            "url": format!("{}{}", base, path),
+        })
+    }
+
+    /// 发布一个组件（总清单 + 组件清单 + 文件），java_sha1 覆盖 bin/java 的校验值
+    fn publish(base: &str, routes: &Routes, java_sha1: Option<&str>) {
+        let java = b"#!/bin/sh\necho java\n".to_vec();
+        let lib = b"libjvm".to_vec();
+        let mut java_remote = remote(base, "/files/java", &java);
+        if let Some(sha1) = java_sha1 {
+            java_remote["sha1"] = json!(sha1);
+        }
+        let component = json!({
+            "files": {
+                "bin": { "type": "directory" },
+                "bin/java": { "type": "file", "executable": true, "downloads": { "raw": java_remote } },
+                "lib/libjvm.so": {
+                    "type": "file",
+                    "executable": false,
+                    "downloads": { "raw": remote(base, "/files/libjvm.so", &lib) }
+                },
+                "legal/java.base": { "type": "link", "target": "../lib" }
+            }
+        })
+        .to_string()
+        .into_bytes();
+        let all = json!({
+            "linux": {
+                "java-runtime-gamma": [{
+                    "manifest": remote(base, "/gamma.json", &component),
+                    "version": { "name": "17.0.8", "released": "2023-08-01T00:00:00+00:00" }
+                }]
+            }
+        })
+        .to_string()
+        .into_bytes();
+
+        let mut routes = routes.lock().unwrap();
+        routes.insert("/all.json".to_string(), all);
+        routes.insert("/gamma.json".to_string(), component);
+        routes.insert("/files/java".to_string(), java);
+        routes.insert("/files/libjvm.so".to_string(), lib);
+    }
+
+    fn temp_dest(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "wecraft-runtime-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("java-runtime-gamma")
+    }
+
+    #[test]
+    fn platform_mapping() {
+        assert_eq!(runtime_platform("linux", "x64"), Some("linux"));
+        assert_eq!(runtime_platform("windows", "x64"), Some("windows-x64"));
+        assert_eq!(runtime_platform("osx", "arm64"), Some("mac-os-arm64"));
+        assert_eq!(runtime_platform("linux", "arm64"), None);
+    }
+
+    #[test]
+    fn rejects_paths_escaping_runtime_dir() {
+        let dest = Path::new("/runtime/gamma");
+        assert!(safe_join(dest, "bin/java").is_ok());
+        assert!(safe_join(dest, "../evil").is_err());
+        assert!(safe_join(dest, "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn rejects_links_escaping_runtime_dir() {
+        let dest = Path::new("/runtime/gamma");
+        let link = dest.join("jre.bundle/Contents/Home/bin/java");
+        assert!(check_link_target(dest, &link, "../lib/java").is_ok());
+        assert!(check_link_target(dest, &link, "../../../../bin/java").is_ok());
+        assert!(check_link_target(dest, &link, "../../../../../evil").is_err());
+        assert!(check_link_target(dest, &link, "/usr/bin/java").is_err());
+    }
+
+    #[tokio::test]
+    async fn installs_runtime_from_local_manifest() {
+        let (base, routes) = serve();
+        publish(&base, &routes, None);
+        let dest = temp_dest("ok");
+        let dm = DownloadManager::new();
+        let url = format!("{}/all.json", base);
+
+        let java = install_runtime(&dm, &url, "linux", "java-runtime-gamma", &dest)
+            .await
+            .unwrap();
+        assert_eq!(
+            java,
+            dest.join("bin")
+                .join(if cfg!(windows) { "java.exe" } else { "java" })
+        );
+        assert_eq!(
+            std::fs::read(dest.join("lib/libjvm.so")).unwrap(),
+            b"libjvm"
+        );
+        assert!(dest.with_file_name("java-runtime-gamma.json").is_file());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&java).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+            let link = dest.join("legal/java.base");
+            assert_eq!(std::fs::read_link(&link).unwrap(), Path::new("../lib"));
+        }
+
+        // 重复安装为增量修复：补回缺失的文件
+        std::fs::remove_file(dest.join("lib/libjvm.so")).unwrap();
+        install_runtime(&dm, &url, "linux", "java-runtime-gamma", &dest)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read(dest.join("lib/libjvm.so")).unwrap(),
+            b"libjvm"
+        );
+
+        // 不存在的组件报错
+        assert!(
+            install_runtime(&dm, &url, "linux", "jre-legacy", &dest)
+                .await
+                .is_err()
+        );
+        let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn sha1_mismatch_fails_install() {
+        let (base, routes) = serve();
+        publish(
+            &base,
+            &routes,
+            Some("0000000000000000000000000000000000000000"),
+        );
+        let dest = temp_dest("bad");
+        let err = install_runtime(
+            &DownloadManager::new(),
+            &format!("{}/all.json", base),
+            "linux",
+            "java-runtime-gamma",
+            &dest,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("SHA1"), "{}", err);
+        let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+    }
+}
@@ -0,0 +1,194 @@
+//! 启动参数文件（`@argfile`）：Java 9+ 支持从文件读取启动参数。
+//! 大型整合包的 classpath 可能超出系统命令行长度限制，这里将主类之前的 JVM 参数
+//! （含 classpath）写入单次启动的参数文件，命令行只保留 `@文件路径`、主类与游戏参数。
+//!
+//! 参数文件位于启动器私有临时目录（Unix 下目录 0700、文件 0600 且以独占方式创建），
+//! 避免其他用户预先创建或替换文件注入 JVM 参数。
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::app_context::AppContext;
+
+/// 支持参数文件的最低 Java 主版本
+const MIN_JAVA_MAJOR: u32 = 9;
+
+/// 参数文件目录：{work_dir}/.wecraft/tmp/argfiles
+pub(super) fn argfile_dir(ctx: &AppContext) -> PathBuf {
+    ctx.wecraft_temp_dir().join("argfiles")
+}
+
+/// 单次启动的参数文件路径
+fn argfile_path(dir: &Path, game_id: &str) -> PathBuf {
+    dir.join(format!("{}.args", game_id))
+}
+
+/// 创建仅当前用户可访问的目录（Unix 0700）
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// 独占创建并写入参数文件（已存在则失败，Unix 权限 0600）
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content.as_bytes())
+}
+
+/// 参数文件中的单个参数：一律双引号包裹，引号内 `\` 为转义符
+fn quote(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        match c {
+            '\\' => quoted.push_str(r"\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str(r"\n"),
+            '\r' => quoted.push_str(r"\r"),
+            '\t' => quoted.push_str(r"\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// 参数文件内容（每行一个参数）
+fn render(args: &[String]) -> String {
+    args.iter().map(|a| quote(a) + "\n").collect()
+}
+
+/// 主类之前的 JVM 参数个数
+fn jvm_arg_count(main_class: &str, launch_args: &[String]) -> Option<usize> {
+    launch_args.iter().position(|a| a == main_class)
+}
+
+/// 是否改用参数文件：Java 主版本已知且不低于 9；
+/// Windows 下 java 启动器按系统 ANSI 代码页读取参数文件，JVM 参数含非 ASCII 字符时保持内联
+fn should_use(java_major: Option<u32>, jvm_args: &[String]) -> bool {
+    let supported = java_major.is_some_and(|m| m >= MIN_JAVA_MAJOR);
+    let encoding_safe = !cfg!(windows) || jvm_args.iter().all(|a| a.is_ascii());
+    supported && encoding_safe && !jvm_args.is_empty()
+}
+
+/// 按 Java 版本将 JVM 参数写入参数文件，返回替换后的启动参数；
+/// 不满足条件时返回 None（调用方沿用内联参数）
+pub(super) fn apply(
+    dir: &Path,
+    game_id: &str,
+    java_major: Option<u32>,
+    main_class: &str,
+    launch_args: &[String],
+) -> Result<Option<Vec<String>>, String> {
+    let Some(count) = jvm_arg_count(main_class, launch_args) else {
+        return Ok(None);
+    };
+    let (jvm_args, rest) = launch_args.split_at(count);
+    if !should_use(java_major, jvm_args) {
+        return Ok(None);
+    }
+
+    create_private_dir(dir).map_err(|e| format!("创建参数文件目录失败: {}", e))?;
+    let path = argfile_path(dir, game_id);
+    write_private(&path, &render(jvm_args)).map_err(|e| format!("写入参数文件失败: {}", e))?;
+
+    let mut args = Vec::with_capacity(rest.len() + 1);
+    args.push(format!("@{}", path.to_string_lossy()));
+    args.extend(rest.iter().cloned());
+    Ok(Some(args))
+}
+
+/// 删除本次启动的参数文件（不存在时忽略）
+pub(super) fn remove(dir: &Path, game_id: &str) {
+    let _ = std::fs::remove_file(argfile_path(dir, game_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> Vec<String> {
+        vec![
+            "-Xmx2048M".into(),
+            "-cp".into(),
+            r"C:\mc\libraries\a b.jar;C:\mc\client.jar".into(),
+            "-Dmsg=say \"hi\"".into(),
+            "net.minecraft.client.main.Main".into(),
+            "--accessToken".into(),
+            "secret".into(),
+        ]
+    }
+
+    #[test]
+    fn quotes_backslashes_and_quotes() {
+        assert_eq!(
+            render(&args()[..4]),
+            "\"-Xmx2048M\"\n\"-cp\"\n\"C:\\\\mc\\\\libraries\\\\a b.jar;C:\\\\mc\\\\client.jar\"\n\"-Dmsg=say \\\"hi\\\"\"\n"
+        );
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "wecraft-argfile-test-{}-{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn java_8_and_unknown_versions_stay_inline() {
+        let dir = temp_dir("inline");
+        let _ = std::fs::remove_dir_all(&dir);
+        let main = "net.minecraft.client.main.Main";
+        assert_eq!(apply(&dir, "java-8", Some(8), main, &args()).unwrap(), None);
+        assert_eq!(apply(&dir, "none", None, main, &args()).unwrap(), None);
+        assert_eq!(
+            apply(&dir, "main", Some(17), "Other", &args()).unwrap(),
+            None
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn writes_jvm_args_and_keeps_game_args_inline() {
+        let dir = temp_dir("write");
+        let _ = std::fs::remove_dir_all(&dir);
+        let main = "net.minecraft.client.main.Main";
+        let replaced = apply(&dir, "game", Some(17), main, &args())
+            .unwrap()
+            .unwrap();
+
+        let path = argfile_path(&dir, "game");
+        assert_eq!(replaced[0], format!("@{}", path.to_string_lossy()));
+        assert_eq!(&replaced[1..], &args()[4..]);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, render(&args()[..4]));
+        assert!(!content.contains("secret"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&dir), 0o700);
+            assert_eq!(mode(&path), 0o600);
+        }
+
+        // 已存在的文件（可能被他人预先创建）不会被复用
+        assert!(apply(&dir, "game", Some(17), main, &args()).is_err());
+
+        remove(&dir, "game");
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -2,13 +2,16 @@
 // 启动参数构建：版本 JSON 解析、继承链合并与 ${...} 模板替换
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::app_context::AppContext;
+use crate::download::loader::find_loader_profile;
 use crate::download::models::{Library, Rule};
-use crate::download::utils::rules_allow;
-use crate::launch::LaunchConfig;
-use crate::log_info;
+use crate::download::utils::{rules_allow, rules_allow_with};
+use crate::launch::{JvmProfile, LaunchConfig, QuickPlay};
+use crate::{log_info, log_warn};
+
+use super::memory;
 
 // ======================== 版本 JSON 解析 ========================
 
@@ -53,8 +56,9 @@ fn collect_library_paths(version_json: &serde_json::Value) -> Vec<String> {
     paths
 }
 
-/// 从 Maven 坐标推导库文件路径（缺少 downloads 时）
-fn maven_path_from_name(name: &str) -> Option<String> {
+/// 从 Maven 坐标推导库文件路径（缺少 downloads 时）；支持 `@ext` 后缀指定扩展名
+pub(crate) fn maven_path_from_name(name: &str) -> Option<String> {
+    let (name, ext) = name.split_once('@').unwrap_or((name, "jar"));
     let parts: Vec<&str> = name.split(':').collect();
     if parts.len() < 3 {
         return None;
@@ -66,16 +70,15 @@ fn maven_path_from_name(name: &str) -> Option<String> {
     let classifier = parts.get(3).filter(|c| !c.is_empty());
 
     let file_name = match classifier {
-        Some(c) => format!("{}-{}-{}.jar", artifact, version, c),
-        None => format!("{}-{}.jar", artifact, version),
+        Some(c) => format!("{}-{}-{}.{}", artifact, version, c, ext),
+        None => format!("{}-{}.{}", artifact, version, ext),
     };
 
     Some(format!(
-        "{}/{}/{}/{}/{}",
+        "{}/{}/{}/{}",
         group.replace('.', "/"),
         artifact,
         version,
-        artifact,
         file_name
     ))
 }
@@ -98,7 +101,7 @@ fn expand_argument_list(
                     Err(_) => Vec::new(),
                 };
 
-                if !rules_allow(&rules) {
+                if !rules_allow_with(&rules, &templates.features) {
                     continue;
                 }
 
@@ -247,9 +250,11 @@ fn merge_version_json(
     Ok(merged)
 }
 
-/// 模板上下文（用于 ${...} 替换）
+/// 模板上下文（用于 ${...} 替换与 arguments 规则的特性判断）
 struct TemplateContext {
     values: HashMap<String, String>,
+    /// 本次启动启用的版本 JSON 特性
+    features: Vec<&'static str>,
 }
 
 impl TemplateContext {
@@ -283,19 +288,108 @@ impl TemplateContext {
     }
 }
 
+/// 本次启动启用的版本 JSON 特性（arguments 规则中的 features）
+fn launch_features(config: &LaunchConfig) -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if !config.fullscreen
+        && (config.resolution_width.is_some() || config.resolution_height.is_some())
+    {
+        features.push("has_custom_resolution");
+    }
+    if let Some(quick_play) = &config.quick_play {
+        features.push("has_quick_plays_support");
+        features.push(match quick_play {
+            QuickPlay::Multiplayer { .. } => "is_quick_play_multiplayer",
+            QuickPlay::Singleplayer { .. } => "is_quick_play_singleplayer",
+            QuickPlay::Realms { .. } => "is_quick_play_realms",
+        });
+    }
+    features
+}
+
+/// 快速游戏对应的模板值（${quickPlayPath} / ${quickPlayMultiplayer} 等）
+fn insert_quick_play_values(
+    values: &mut HashMap<String, String>,
+    quick_play: &QuickPlay,
+    game_dir: &Path,
+) {
+    values.insert(
+        "quickPlayPath".into(),
+        game_dir
+            .join("quickPlay")
+            .join("log.json")
+            .to_string_lossy()
+            .to_string(),
+    );
+    match quick_play {
+        QuickPlay::Multiplayer { host, port } => {
+            let address = match port {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.clone(),
+            };
+            values.insert("quickPlayMultiplayer".into(), address);
+        }
+        QuickPlay::Singleplayer { world } => {
+            values.insert("quickPlaySingleplayer".into(), world.clone());
+        }
+        QuickPlay::Realms { realm_id } => {
+            values.insert("quickPlayRealms".into(), realm_id.clone());
+        }
+    }
+}
+
+/// 版本 JSON 未展开 --quickPlay* 参数（1.20 之前）时的回退：
+/// 多人服务器改用 --server/--port，单人世界与 Realms 旧版本不支持，仅记录警告
+fn push_legacy_quick_play(game_args: &mut Vec<String>, quick_play: &QuickPlay) {
+    let has_arg = |flag: &str| game_args.iter().any(|a| a == flag);
+    match quick_play {
+        QuickPlay::Multiplayer { host, port } if !has_arg("--quickPlayMultiplayer") => {
+            game_args.push("--server".to_string());
+            game_args.push(host.clone());
+            if let Some(port) = port {
+                game_args.push("--port".to_string());
+                game_args.push(port.to_string());
+            }
+        }
+        QuickPlay::Singleplayer { world } if !has_arg("--quickPlaySingleplayer") => {
+            log_warn!("当前版本不支持快速进入单人世界，已忽略: {}", world);
+        }
+        QuickPlay::Realms { realm_id } if !has_arg("--quickPlayRealms") => {
+            log_warn!("当前版本不支持快速进入 Realms，已忽略: {}", realm_id);
+        }
+        _ => {}
+    }
+}
+
+/// 版本 JSON `logging.client` 对应的 JVM 参数（配置文件未下载时为 None）
+fn log_config_arg(merged: &serde_json::Value, assets_dir: &Path) -> Option<String> {
+    let client = &merged["logging"]["client"];
+    let log_config = assets_dir
+        .join("log_configs")
+        .join(client["file"]["id"].as_str()?);
+    if !log_config.exists() {
+        return None;
+    }
+    let path = log_config.to_string_lossy();
+    Some(match client["argument"].as_str() {
+        Some(template) => template.replace("${path}", &path),
+        None => format!("-Dlog4j.configurationFile={}", path),
+    })
+}
+
 // ======================== 启动参数构建 ========================
 
-/// 读取本地版本 JSON 并组装启动参数
-/// access_token 由后端从账户管理器注入（token 绝不经过前端）
-pub(super) fn build_launch_args(
+/// 读取游戏目录下的版本 JSON，返回 (原始 JSON, 沿继承链合并后的 JSON)
+///
+/// 目录内存在继承该版本的加载器 profile 时以其为入口，原版 JSON 作为父版本合并
+pub(super) fn load_version_json(
     config: &LaunchConfig,
     ctx: &AppContext,
-    access_token: Option<String>,
-) -> Result<(String, Vec<String>), String> {
-    log_info!("构建启动参数, LaunchConfig: {:?}", config);
+) -> Result<(serde_json::Value, serde_json::Value), String> {
     let game_dir = PathBuf::from(&config.game_dir);
-
-    let version_json_path = ctx.version_json_in_dir(&game_dir, &config.version);
+    let entry_id = find_loader_profile(&game_dir, &config.version)
+        .unwrap_or_else(|| config.version.clone());
+    let version_json_path = ctx.version_json_in_dir(&game_dir, &entry_id);
 
     if !version_json_path.exists() {
         return Err(format!(
@@ -309,13 +403,29 @@ pub(super) fn build_launch_args(
     let version_json: serde_json::Value =
         serde_json::from_str(&raw).map_err(|e| format!("解析版本 JSON 失败: {}", e))?;
 
-    let mut visited = vec![config.version.clone()];
+    let mut visited = vec![entry_id];
     let merged = merge_version_json(&version_json, &game_dir, ctx, &mut visited)?;
+    Ok((version_json, merged))
+}
+
+/// 读取本地版本 JSON 并组装启动参数
+/// access_token 由后端从账户管理器注入（token 绝不经过前端）；
+/// natives_dir 为本次启动已准备好的原生库目录；java_major 为所用 Java 的主版本（未知为 None）
+pub(super) fn build_launch_args(
+    config: &LaunchConfig,
+    ctx: &AppContext,
+    access_token: Option<String>,
+    natives_dir: &Path,
+    java_major: Option<u32>,
+) -> Result<(String, Vec<String>), String> {
+    // 不整体打印 LaunchConfig：环境变量值与启动前 / 退出后命令可能包含令牌等敏感信息
+    log_info!("构建启动参数: 版本 {}, 游戏目录 {}", config.version, config.game_dir);
+    let game_dir = PathBuf::from(&config.game_dir);
+    let (_, merged) = load_version_json(config, ctx)?;
 
     // ---- 目录路径（一律来自 app_context，全局共享目录与下载/校验保持一致） ----
     let libraries_dir = ctx.libraries_dir();
     let assets_dir = ctx.assets_dir();
-    let natives_dir = ctx.natives_dir();
 
     let game_jar = ctx.version_jar_in_dir(&game_dir, &config.version);
 
@@ -421,26 +531,39 @@ pub(super) fn build_launch_args(
             .unwrap_or_else(|| "480".to_string()),
     );
 
+    if let Some(quick_play) = &config.quick_play {
+        insert_quick_play_values(&mut template_values, quick_play, &game_dir);
+    }
+
     let templates = TemplateContext {
         values: template_values,
+        features: launch_features(config),
     };
 
     // ---- JVM 参数 ----
     let mut jvm_args: Vec<String> = Vec::new();
 
-    // 内存参数（PCL 语义：滑块只映射 -Xmx；初始堆 -Xms 不自动生成，
-    // 交由 JVM 默认策略，用户可在自定义 JVM 参数中手动指定）
+    // 内存参数：-Xmx 为分配内存；设置了最小内存时传 -Xms（不超过 -Xmx），否则交由 JVM 默认策略
     jvm_args.push(format!("-Xmx{}M", config.memory_mb));
+    if let Some(min) = config.min_memory_mb.filter(|m| *m > 0) {
+        jvm_args.push(format!("-Xms{}M", min.min(config.memory_mb)));
+    }
 
-    // log4j 配置
-    if let Some(logging_id) = merged["logging"]["client"]["file"]["id"].as_str() {
-        let log_config = assets_dir.join("log_configs").join(logging_id);
-        if log_config.exists() {
-            jvm_args.push(format!(
-                "-Dlog4j.configurationFile={}",
-                log_config.to_string_lossy()
-            ));
+    // JVM 调优配置（自定义 JVM 参数已指定垃圾回收器时跳过，避免 JVM 报回收器冲突）
+    if memory::selects_gc(&config.jvm_args) {
+        if config.jvm_profile != JvmProfile::None {
+            log_warn!(
+                "自定义 JVM 参数已指定垃圾回收器，忽略调优配置 {}",
+                config.jvm_profile.label()
+            );
         }
+    } else {
+        jvm_args.extend(config.jvm_profile.flags(java_major));
+    }
+
+    // log4j 配置（XMLLayout 输出，由日志捕获解析为结构化事件）
+    if let Some(arg) = log_config_arg(&merged, &assets_dir).filter(|_| config.xml_log_layout) {
+        jvm_args.push(arg);
     }
 
     // 版本 JSON 中的 JVM 参数（1.13+）
@@ -486,8 +609,13 @@ pub(super) fn build_launch_args(
     // 用户附加游戏参数
     game_args.extend(config.game_args.iter().cloned());
 
-    // 显式窗口参数：仅非全屏时传宽高（全屏交由 --fullscreen 控制，避免退出全屏后窗口恢复为异常尺寸）
-    if !config.fullscreen {
+    if let Some(quick_play) = &config.quick_play {
+        push_legacy_quick_play(&mut game_args, quick_play);
+    }
+
+    // 显式窗口参数：仅非全屏时传宽高（全屏交由 --fullscreen 控制，避免退出全屏后窗口恢复为异常尺寸）；
+    // 版本 JSON 已按 has_custom_resolution 展开 --width/--height 时不再重复
+    if !config.fullscreen && !game_args.iter().any(|a| a == "--width") {
         if let Some(w) = config.resolution_width {
             game_args.push("--width".to_string());
             game_args.push(w.to_string());
@@ -522,3 +650,137 @@ pub(super) fn build_launch_args(
     Ok((main_class, all_args))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1.20+ 版本 JSON 中与特性相关的游戏参数
+    fn feature_game_args() -> serde_json::Value {
+        serde_json::json!([
+            "--gameDir",
+            "${game_directory}",
+            {
+                "rules": [{ "action": "allow", "features": { "has_custom_resolution": true } }],
+                "value": ["--width", "${resolution_width}", "--height", "${resolution_height}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "has_quick_plays_support": true } }],
+                "value": ["--quickPlayPath", "${quickPlayPath}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "is_quick_play_multiplayer": true } }],
+                "value": ["--quickPlayMultiplayer", "${quickPlayMultiplayer}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "is_quick_play_singleplayer": true } }],
+                "value": ["--quickPlaySingleplayer", "${quickPlaySingleplayer}"]
+            }
+        ])
+    }
+
+    fn templates_for(config: &LaunchConfig) -> TemplateContext {
+        let mut values = HashMap::new();
+        values.insert("game_directory".into(), "/mc".into());
+        values.insert("resolution_width".into(), "1280".into());
+        values.insert("resolution_height".into(), "720".into());
+        if let Some(quick_play) = &config.quick_play {
+            insert_quick_play_values(&mut values, quick_play, Path::new("/mc"));
+        }
+        TemplateContext {
+            values,
+            features: launch_features(config),
+        }
+    }
+
+    #[test]
+    fn expands_quick_play_multiplayer_and_resolution() {
+        let config = LaunchConfig {
+            resolution_width: Some(1280),
+            resolution_height: Some(720),
+            quick_play: Some(QuickPlay::Multiplayer {
+                host: "mc.example.com".into(),
+                port: Some(25566),
+            }),
+            ..Default::default()
+        };
+        let args = expand_argument_list(&feature_game_args(), &templates_for(&config));
+        let quick_play_path = Path::new("/mc").join("quickPlay").join("log.json");
+        assert_eq!(
+            args,
+            vec![
+                "--gameDir".to_string(),
+                "/mc".into(),
+                "--width".into(),
+                "1280".into(),
+                "--height".into(),
+                "720".into(),
+                "--quickPlayPath".into(),
+                quick_play_path.to_string_lossy().to_string(),
+                "--quickPlayMultiplayer".into(),
+                "mc.example.com:25566".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_feature_args_when_disabled() {
+        let config = LaunchConfig {
+            fullscreen: true,
+            resolution_width: Some(1280),
+            ..Default::default()
+        };
+        let args = expand_argument_list(&feature_game_args(), &templates_for(&config));
+        assert_eq!(args, vec!["--gameDir".to_string(), "/mc".into()]);
+    }
+
+    #[test]
+    fn singleplayer_uses_world_name() {
+        let config = LaunchConfig {
+            quick_play: Some(QuickPlay::Singleplayer {
+                world: "New World".into(),
+            }),
+            ..Default::default()
+        };
+        let args = expand_argument_list(&feature_game_args(), &templates_for(&config));
+        assert!(args.ends_with(&["--quickPlaySingleplayer".into(), "New World".into()]));
+        assert!(!args.iter().any(|a| a == "--quickPlayMultiplayer"));
+    }
+
+    #[test]
+    fn maven_path_follows_repository_layout() {
+        assert_eq!(
+            maven_path_from_name("net.fabricmc:fabric-loader:0.15.0").unwrap(),
+            "net/fabricmc/fabric-loader/0.15.0/fabric-loader-0.15.0.jar"
+        );
+        assert_eq!(
+            maven_path_from_name("org.lwjgl:lwjgl:3.3.1:natives-linux").unwrap(),
+            "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-linux.jar"
+        );
+        assert_eq!(
+            maven_path_from_name("de.oceanlabs.mcp:mcp_config:1.20.1:mappings@txt").unwrap(),
+            "de/oceanlabs/mcp/mcp_config/1.20.1/mcp_config-1.20.1-mappings.txt"
+        );
+        assert!(maven_path_from_name("invalid").is_none());
+    }
+
+    #[test]
+    fn legacy_versions_fall_back_to_server_args() {
+        let quick_play = QuickPlay::Multiplayer {
+            host: "mc.example.com".into(),
+            port: Some(25566),
+        };
+        let mut legacy = vec!["--gameDir".to_string(), "/mc".into()];
+        push_legacy_quick_play(&mut legacy, &quick_play);
+        assert_eq!(
+            legacy[2..],
+            ["--server", "mc.example.com", "--port", "25566"]
+        );
+
+        let mut modern = vec![
+            "--quickPlayMultiplayer".to_string(),
+            "mc.example.com:25566".into(),
+        ];
+        push_legacy_quick_play(&mut modern, &quick_play);
+        assert_eq!(modern.len(), 2);
+    }
+}
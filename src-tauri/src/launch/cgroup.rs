@@ -0,0 +1,379 @@
+//! 资源限制（Linux cgroups v2）：游戏进程经 `systemd-run --user --scope` 启动，由 systemd
+//! 为其创建独立的 scope 单元并设置 MemoryMax / CPUWeight / TasksMax，避免并行运行的实例中
+//! 某个整合包耗尽内存或 CPU，并从 scope 的 cgroup 读取各游戏的实时资源占用。
+//!
+//! cgroup 树由 systemd 独占管理，启动器不自行创建 cgroup 或写入 `cgroup.subtree_control`；
+//! scope 随进程退出由 systemd 回收。没有 systemd 用户会话或非 Linux 平台时回退为不限制。
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use super::LaunchConfig;
+
+/// 堆外内存（元空间、线程栈、直接缓冲区与 LWJGL 原生内存）的最低余量（MB）
+const OFF_HEAP_MIN_MB: u64 = 1024;
+
+/// 未设置时的最大任务数（cgroup v2 的 pids.max 按线程计数，整合包常有数百个线程）
+const DEFAULT_PIDS_MAX: u32 = 4096;
+
+/// 游戏 scope 单元（及旧版本自建 cgroup）的名称前缀
+const CGROUP_PREFIX: &str = "wecraft-";
+
+/// 等待 systemd-run 把进程移入 scope 的最长时间
+const SCOPE_WAIT: Duration = Duration::from_secs(3);
+
+/// 游戏 cgroup 的资源限制
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct CgroupLimits {
+    /// memory.max（字节）
+    pub memory_max: u64,
+    /// cpu.weight（1-10000，默认 100）
+    pub cpu_weight: Option<u32>,
+    /// pids.max
+    pub pids_max: u32,
+}
+
+impl CgroupLimits {
+    /// 由启动配置推导：内存上限为堆大小加堆外余量（堆的一半，至少 1 GB）
+    pub(super) fn from_config(config: &LaunchConfig) -> Self {
+        let heap_mb = config.memory_mb as u64;
+        let memory_max_mb = heap_mb + (heap_mb / 2).max(OFF_HEAP_MIN_MB);
+        Self {
+            memory_max: memory_max_mb * 1024 * 1024,
+            cpu_weight: config.cpu_weight.map(|w| w.clamp(1, 10000)),
+            pids_max: config.pids_max.unwrap_or(DEFAULT_PIDS_MAX).max(1),
+        }
+    }
+
+    /// 对应的 systemd 单元属性
+    fn properties(&self) -> Vec<String> {
+        let mut props = vec![format!("MemoryMax={}", self.memory_max)];
+        if let Some(weight) = self.cpu_weight {
+            props.push(format!("CPUWeight={}", weight));
+        }
+        props.push(format!("TasksMax={}", self.pids_max));
+        props
+    }
+}
+
+/// 单个游戏的实时资源占用（暴露给前端）
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// 当前内存占用（字节，memory.current）
+    pub memory_bytes: u64,
+    /// 内存占用峰值（字节，memory.peak，内核 5.19+）
+    pub memory_peak_bytes: Option<u64>,
+    /// 内存上限（字节，None 为不限制）
+    pub memory_max_bytes: Option<u64>,
+    /// 累计 CPU 时间（微秒，cpu.stat 的 usage_usec）
+    pub cpu_usage_usec: u64,
+    /// CPU 权重
+    pub cpu_weight: Option<u32>,
+    /// 当前任务（线程）数
+    pub pids: u64,
+    /// 任务数上限（None 为不限制）
+    pub pids_max: Option<u64>,
+}
+
+/// 单个游戏的 cgroup
+#[derive(Clone, Debug)]
+pub(super) struct GameCgroup {
+    path: PathBuf,
+}
+
+/// 读取 cgroup 接口文件中的数值（"max" 视为 None）
+fn read_limit(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+}
+
+/// cpu.stat 中的 usage_usec
+fn parse_cpu_usage(stat: &str) -> u64 {
+    stat.lines()
+        .find_map(|line| line.strip_prefix("usage_usec "))
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// /proc/self/mounts 中 cgroup2 的挂载点（纯 v2 为 /sys/fs/cgroup，混合模式为 .../unified）
+fn cgroup2_mount(mounts: &str) -> Option<PathBuf> {
+    mounts.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let mount_point = fields.nth(1)?;
+        (fields.next()? == "cgroup2").then(|| PathBuf::from(mount_point))
+    })
+}
+
+/// /proc/[pid]/cgroup 中的 cgroup v2 路径（`0::/...` 行）
+fn unified_path(cgroup: &str) -> Option<&str> {
+    cgroup.lines().find_map(|line| line.strip_prefix("0::"))
+}
+
+/// 启动器所在 cgroup 的上级目录（旧版本在此自建 `wecraft-*` cgroup）；
+/// 启动器位于根 cgroup 时为根
+fn parent_dir(mount: &Path, own: &str) -> PathBuf {
+    let own = mount.join(own.trim_start_matches('/'));
+    match own.parent() {
+        Some(parent) if own != mount && parent.starts_with(mount) => parent.to_path_buf(),
+        _ => mount.to_path_buf(),
+    }
+}
+
+/// cgroup v2 挂载点与指定进程所在 cgroup 的相对路径
+fn process_cgroup(pid: &str) -> Option<(PathBuf, String)> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    let cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    Some((cgroup2_mount(&mounts)?, unified_path(&cgroup)?.to_string()))
+}
+
+/// 游戏的 scope 单元名（不含 `.scope` 后缀）
+fn unit_name(game_id: &str) -> String {
+    format!("{}{}", CGROUP_PREFIX, game_id)
+}
+
+/// 以 scope 运行命令的 systemd-run 参数（置于包装命令与 java 之前）
+fn scope_args(game_id: &str, limits: &CgroupLimits) -> Vec<String> {
+    let mut args: Vec<String> = ["systemd-run", "--user", "--scope", "--quiet", "--collect"]
+        .map(String::from)
+        .to_vec();
+    args.push(format!("--unit={}", unit_name(game_id)));
+    for prop in limits.properties() {
+        args.push("-p".to_string());
+        args.push(prop);
+    }
+    args.push("--".to_string());
+    args
+}
+
+/// systemd 用户管理器能否创建 scope（首次调用时以空命令试探，结果缓存）
+fn systemd_run_available() -> bool {
+    static AVAILABLE: OnceCell<bool> = OnceCell::new();
+    *AVAILABLE.get_or_init(|| {
+        cfg!(target_os = "linux")
+            && Command::new("systemd-run")
+                .args(["--user", "--scope", "--quiet", "--collect", "true"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success())
+    })
+}
+
+/// 启动游戏的命令前缀（systemd-run，exec 后进程 PID 不变）；systemd 用户会话不可用时返回错误
+pub(super) fn scope_prefix(game_id: &str, limits: &CgroupLimits) -> Result<Vec<String>, String> {
+    if !cfg!(target_os = "linux") {
+        return Err("当前平台不支持 cgroups 资源限制".to_string());
+    }
+    if !systemd_run_available() {
+        return Err("systemd 用户会话不可用（systemd-run --user）".to_string());
+    }
+    Ok(scope_args(game_id, limits))
+}
+
+/// 删除旧版本启动器崩溃后残留的自建 `wecraft-*` cgroup（只删除已无进程的空目录）
+pub(super) fn remove_stale() {
+    if !cfg!(target_os = "linux") {
+        return;
+    }
+    let Some((mount, own)) = process_cgroup("self") else {
+        return;
+    };
+    remove_stale_in(&parent_dir(&mount, &own));
+}
+
+fn remove_stale_in(parent: &Path) {
+    let Ok(entries) = std::fs::read_dir(parent) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // systemd 管理的 scope 由其自行回收
+        if !name.starts_with(CGROUP_PREFIX) || name.ends_with(".scope") {
+            continue;
+        }
+        if std::fs::remove_dir(entry.path()).is_ok() {
+            crate::log_info!("已清理残留 cgroup: {}", entry.path().display());
+        }
+    }
+}
+
+impl GameCgroup {
+    /// 定位游戏进程所在的 scope cgroup（等待 systemd-run 完成移入）；未进入 scope 时返回 None
+    pub(super) async fn attach(game_id: &str, pid: u32) -> Option<Self> {
+        let scope = format!("/{}.scope", unit_name(game_id));
+        let deadline = Instant::now() + SCOPE_WAIT;
+        loop {
+            let current = process_cgroup(&pid.to_string());
+            if let Some((mount, path)) = current.filter(|(_, path)| path.ends_with(&scope)) {
+                return Some(Self {
+                    path: mount.join(path.trim_start_matches('/')),
+                });
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// cgroup 目录
+    pub(super) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 当前资源占用
+    pub(super) fn usage(&self) -> ResourceUsage {
+        let read = |file: &str| read_limit(&self.path.join(file));
+        ResourceUsage {
+            memory_bytes: read("memory.current").unwrap_or(0),
+            memory_peak_bytes: read("memory.peak"),
+            memory_max_bytes: read("memory.max"),
+            cpu_usage_usec: std::fs::read_to_string(self.path.join("cpu.stat"))
+                .map(|s| parse_cpu_usage(&s))
+                .unwrap_or(0),
+            cpu_weight: read("cpu.weight").map(|w| w as u32),
+            pids: read("pids.current").unwrap_or(0),
+            pids_max: read("pids.max"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_follow_heap_size_and_settings() {
+        let mut config = LaunchConfig {
+            memory_mb: 4096,
+            ..LaunchConfig::default()
+        };
+        let limits = CgroupLimits::from_config(&config);
+        assert_eq!(limits.memory_max, 6144 * 1024 * 1024);
+        assert_eq!(limits.cpu_weight, None);
+        assert_eq!(limits.pids_max, DEFAULT_PIDS_MAX);
+
+        config.memory_mb = 1024;
+        config.cpu_weight = Some(50_000);
+        config.pids_max = Some(512);
+        let limits = CgroupLimits::from_config(&config);
+        assert_eq!(limits.memory_max, 2048 * 1024 * 1024);
+        assert_eq!(limits.cpu_weight, Some(10000));
+        assert_eq!(limits.pids_max, 512);
+    }
+
+    #[test]
+    fn scope_args_carry_unit_and_limits() {
+        let limits = CgroupLimits {
+            memory_max: 1 << 30,
+            cpu_weight: Some(50),
+            pids_max: 512,
+        };
+        assert_eq!(
+            scope_args("abc", &limits),
+            [
+                "systemd-run",
+                "--user",
+                "--scope",
+                "--quiet",
+                "--collect",
+                "--unit=wecraft-abc",
+                "-p",
+                "MemoryMax=1073741824",
+                "-p",
+                "CPUWeight=50",
+                "-p",
+                "TasksMax=512",
+                "--",
+            ]
+        );
+    }
+
+    #[test]
+    fn removes_only_stale_empty_cgroups() {
+        let dir = std::env::temp_dir().join(format!("wecraft-cgroup-stale-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for name in [
+            "wecraft-old",
+            "wecraft-busy",
+            "wecraft-new.scope",
+            "app-other.scope",
+        ] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        // 非空目录对应仍有进程的 cgroup（真实 cgroupfs 中 rmdir 失败）
+        std::fs::write(dir.join("wecraft-busy").join("cgroup.procs"), "42\n").unwrap();
+
+        remove_stale_in(&dir);
+        assert!(!dir.join("wecraft-old").exists());
+        assert!(dir.join("wecraft-busy").exists());
+        assert!(dir.join("wecraft-new.scope").exists());
+        assert!(dir.join("app-other.scope").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn locates_delegated_parent() {
+        let mounts = "proc /proc proc rw 0 0\n\
+                      cgroup2 /sys/fs/cgroup cgroup2 rw,nosuid,nodev,noexec 0 0\n";
+        let mount = cgroup2_mount(mounts).unwrap();
+        assert_eq!(mount, PathBuf::from("/sys/fs/cgroup"));
+
+        let own = unified_path(
+            "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-wecraft.scope\n",
+        )
+        .unwrap();
+        assert_eq!(
+            parent_dir(&mount, own),
+            PathBuf::from("/sys/fs/cgroup/user.slice/user-1000.slice/user@1000.service/app.slice")
+        );
+        assert_eq!(parent_dir(&mount, "/"), mount);
+
+        // 混合模式：v1 控制器行与 unified 挂载点
+        let hybrid = "cgroup /sys/fs/cgroup/memory cgroup rw 0 0\n\
+                      cgroup2 /sys/fs/cgroup/unified cgroup2 rw 0 0\n";
+        assert_eq!(
+            cgroup2_mount(hybrid).unwrap(),
+            PathBuf::from("/sys/fs/cgroup/unified")
+        );
+        assert_eq!(unified_path("4:memory:/docker\n0::/\n"), Some("/"));
+        assert!(cgroup2_mount("cgroup /sys/fs/cgroup/memory cgroup rw 0 0\n").is_none());
+    }
+
+    #[test]
+    fn reads_usage_from_interface_files() {
+        let dir = std::env::temp_dir().join(format!("wecraft-cgroup-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("memory.current"), "1073741824\n").unwrap();
+        std::fs::write(dir.join("memory.max"), "max\n").unwrap();
+        std::fs::write(
+            dir.join("cpu.stat"),
+            "usage_usec 2500000\nuser_usec 2000000\nsystem_usec 500000\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("pids.current"), "87\n").unwrap();
+        std::fs::write(dir.join("pids.max"), "4096\n").unwrap();
+
+        let cgroup = GameCgroup { path: dir.clone() };
+        assert_eq!(
+            cgroup.usage(),
+            ResourceUsage {
+                memory_bytes: 1 << 30,
+                memory_peak_bytes: None,
+                memory_max_bytes: None,
+                cpu_usage_usec: 2_500_000,
+                cpu_weight: None,
+                pids: 87,
+                pids_max: Some(4096),
+            }
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
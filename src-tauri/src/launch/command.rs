@@ -6,8 +6,11 @@ use crate::{
     GameLogResult, LaunchConfig, LaunchGameInfo, LaunchStatus, LaunchStatusInfo,
     app_context::AppContext, download::DownloadManager, game::GameManager,
     launch::{
-        get_game_log, get_launch_config, get_launch_games, get_launch_status,
-        get_launch_status_by_key, launch_game, stop_game, update_launch_config,
+        DoctorReport, GameCrashDetails, LaunchHistory, LaunchPreview, LogSearchQuery,
+        LogSearchResult, ResourceSeries, ScriptKind, check_launch, get_crash_details,
+        get_game_log, get_game_resources, get_launch_config, get_launch_games, get_launch_history, get_launch_status,
+        get_launch_status_by_key, launch_game, preview_launch, search_game_log, stop_game,
+        update_launch_config,
     },
 };
 
@@ -22,10 +25,11 @@ pub fn front_launch_game(
     launch_game(config, &ctx, dm.inner().clone(), gm.inner().clone())
 }
 
-/// 前端命令：停止 Minecraft 游戏；game_id 为 None 时停止全部
+/// 前端命令：停止 Minecraft 游戏；game_id 为 None 时停止全部。
+/// 默认先请求正常关闭并等待宽限期，force 为 true 时直接强制结束
 #[tauri::command]
-pub fn front_stop_game(game_id: Option<String>) -> Result<String, String> {
-    stop_game(game_id)
+pub async fn front_stop_game(game_id: Option<String>, force: Option<bool>) -> Result<String, String> {
+    stop_game(game_id, force.unwrap_or(false)).await
 }
 
 /// 前端命令：获取聚合启动状态（任一游戏运行中即 Running）
@@ -58,8 +62,60 @@ pub fn front_update_launch_config(config: LaunchConfig) -> Result<String, String
     update_launch_config(config)
 }
 
-/// 前端命令：增量拉取指定游戏的捕获日志（offset 为上次游标，0 表示全量）
+/// 前端命令：增量拉取指定游戏的捕获日志（offset 为上次游标，早于内存缓冲时从日志文件分页）
 #[tauri::command]
 pub fn front_get_game_log(game_id: String, offset: usize) -> Result<GameLogResult, String> {
     Ok(get_game_log(&game_id, offset))
-}
\ No newline at end of file
+}
+
+/// 前端命令：获取指定游戏的资源采样序列（RSS / CPU / 线程数 / 运行时长），新采样另以事件推送
+#[tauri::command]
+pub fn front_get_game_resources(game_id: String) -> Result<ResourceSeries, String> {
+    Ok(get_game_resources(&game_id))
+}
+
+/// 前端命令：解析指定游戏本次启动后生成的崩溃报告（crash-reports / hs_err_pid）
+#[tauri::command]
+pub fn front_get_crash_details(game_id: String) -> Result<GameCrashDetails, String> {
+    get_crash_details(&game_id)
+}
+
+/// 前端命令：查询启动会话历史与总游戏时长（game_name 为 None 时返回全部游戏）
+#[tauri::command]
+pub fn front_get_launch_history(
+    game_name: Option<String>,
+    ctx: State<'_, AppContext>,
+) -> Result<LaunchHistory, String> {
+    Ok(get_launch_history(&ctx, game_name.as_deref()))
+}
+
+/// 前端命令：按等级 / 正则 / 线程 / 记录器 / 时间范围检索游戏日志（含上下文，游标分页）
+#[tauri::command]
+pub fn front_search_game_log(
+    query: LogSearchQuery,
+    ctx: State<'_, AppContext>,
+) -> Result<LogSearchResult, String> {
+    search_game_log(&ctx, &query)
+}
+
+/// 前端命令：启动前环境检查（Java 版本、内存、目录、路径字符、加载器版本），不启动游戏；
+/// config 为 None 时检查默认启动配置
+#[tauri::command]
+pub fn front_check_launch(
+    config: Option<LaunchConfig>,
+    ctx: State<'_, AppContext>,
+) -> Result<DoctorReport, String> {
+    check_launch(config, &ctx)
+}
+
+/// 前端命令：启动预览（dry-run），返回主类、完整命令行、工作目录与环境变量（访问令牌已隐藏）；
+/// 指定 script 时同时导出可运行的启动脚本（script_path 为空时写到游戏目录下 launch.{ext}）
+#[tauri::command]
+pub fn front_preview_launch(
+    config: Option<LaunchConfig>,
+    script: Option<ScriptKind>,
+    script_path: Option<String>,
+    ctx: State<'_, AppContext>,
+) -> Result<LaunchPreview, String> {
+    preview_launch(config, script, script_path, &ctx)
+}
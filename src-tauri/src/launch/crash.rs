@@ -0,0 +1,551 @@
+//! 崩溃诊断：基于可插拔规则集分析日志尾部、crash-reports 与 hs_err_pid 报告
+//!
+//! 内置规则见 `crash_rules.json`；用户可在 `.wecraft/crash_rules.json` 中追加规则，
+//! 同 id 规则覆盖内置规则，`replace_defaults: true` 时完全替换内置规则。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::log_warn;
+
+/// 内置规则集（与用户规则文件格式一致）
+const DEFAULT_RULES: &str = include_str!("crash_rules.json");
+
+/// 单条规则最多保留的证据行数
+const MAX_EVIDENCE: usize = 5;
+
+/// 模板占位符 `{名称}`
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{(\w+)\}").unwrap());
+
+/// 崩溃分类
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashCategory {
+    /// 内存不足
+    OutOfMemory,
+    /// Java 版本不匹配
+    JavaVersion,
+    /// 缺失类
+    MissingClass,
+    /// 缺少前置模组
+    MissingDependency,
+    /// 模组冲突 / 不兼容
+    ModConflict,
+    /// 重复安装的模组
+    DuplicateMod,
+    /// Mixin 注入失败
+    MixinFailure,
+    /// 显卡驱动 / OpenGL 异常
+    GraphicsDriver,
+    /// 游戏或模组文件损坏
+    CorruptFiles,
+    /// JVM 致命错误
+    JvmFatal,
+    /// 未能归类
+    Unknown,
+}
+
+/// 规则匹配的文本来源
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashSource {
+    /// 捕获的游戏日志尾部
+    Log,
+    /// crash-reports/*.txt
+    CrashReport,
+    /// hs_err_pid*.log
+    HsErr,
+}
+
+/// 诊断规则：按行正则匹配，命名捕获组 `mod`/`dep`/`class`/`java`/`class_version`
+/// 会提取到报告字段，并可在 summary/fixes 中以 `{名称}` 引用
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrashRule {
+    pub id: String,
+    pub category: CrashCategory,
+    /// 匹配来源（为空时匹配全部来源）
+    #[serde(default)]
+    pub sources: Vec<CrashSource>,
+    pub pattern: String,
+    /// 置信度（0-1），多条规则命中时取最高者
+    pub confidence: f32,
+    pub summary: String,
+    #[serde(default)]
+    pub fixes: Vec<String>,
+}
+
+/// 规则文件结构
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CrashRuleSet {
+    /// 为 true 时不加载内置规则
+    #[serde(default)]
+    pub replace_defaults: bool,
+    #[serde(default)]
+    pub rules: Vec<CrashRule>,
+}
+
+/// 单条证据行
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CrashEvidence {
+    pub source: CrashSource,
+    /// 来源文本中的行号（从 1 开始）
+    pub line: usize,
+    pub text: String,
+}
+
+/// 结构化崩溃报告（暴露给前端）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrashReport {
+    pub category: CrashCategory,
+    /// 命中的规则 id
+    pub rule_id: String,
+    /// 置信度（0-1）
+    pub confidence: f32,
+    /// 可读摘要
+    pub summary: String,
+    /// 建议修复措施
+    pub suggested_fixes: Vec<String>,
+    /// 命中证据行
+    pub evidence: Vec<CrashEvidence>,
+    /// 疑似肇事模组 id
+    pub culprit_mod: Option<String>,
+    /// 缺失 / 冲突的关联模组 id
+    pub related_mod: Option<String>,
+    /// 缺失的类名
+    pub missing_class: Option<String>,
+    /// 需要的 Java 主版本
+    pub required_java: Option<u32>,
+    /// 参与分析的崩溃报告文件（crash-reports 或 hs_err_pid）
+    pub report_files: Vec<String>,
+    /// 资源监控的附加说明（如退出前 RSS 接近 -Xmx）
+    #[serde(default)]
+    pub resource_notes: Vec<String>,
+}
+
+/// 分析输入：日志尾部与本次启动后生成的报告文件
+#[derive(Clone, Debug, Default)]
+pub struct CrashInputs {
+    pub log: Vec<String>,
+    pub crash_report: Option<(PathBuf, String)>,
+    pub hs_err: Option<(PathBuf, String)>,
+}
+
+impl CrashInputs {
+    fn lines(&self, source: &CrashSource) -> Vec<&str> {
+        match source {
+            CrashSource::Log => self.log.iter().map(|l| l.as_str()).collect(),
+            CrashSource::CrashReport => self
+                .crash_report
+                .as_ref()
+                .map(|(_, t)| t.lines().collect())
+                .unwrap_or_default(),
+            CrashSource::HsErr => self
+                .hs_err
+                .as_ref()
+                .map(|(_, t)| t.lines().collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn report_files(&self) -> Vec<String> {
+        [&self.crash_report, &self.hs_err]
+            .into_iter()
+            .flatten()
+            .map(|(p, _)| p.to_string_lossy().to_string())
+            .collect()
+    }
+}
+
+/// 加载规则：内置规则 + 用户规则文件（同 id 覆盖，新 id 追加）。
+/// 用户文件不存在或解析失败时仅使用内置规则。
+pub fn load_rules(user_path: &Path) -> Vec<CrashRule> {
+    let mut rules = serde_json::from_str::<CrashRuleSet>(DEFAULT_RULES)
+        .map(|s| s.rules)
+        .unwrap_or_default();
+
+    if !user_path.exists() {
+        return rules;
+    }
+    let user = match std::fs::read_to_string(user_path)
+        .map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str::<CrashRuleSet>(&s).map_err(|e| e.to_string()))
+    {
+        Ok(u) => u,
+        Err(e) => {
+            log_warn!(
+                "崩溃规则文件 {} 解析失败，使用内置规则: {}",
+                user_path.display(),
+                e
+            );
+            return rules;
+        }
+    };
+
+    if user.replace_defaults {
+        return user.rules;
+    }
+    for rule in user.rules {
+        match rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => rules.push(rule),
+        }
+    }
+    rules
+}
+
+/// 收集分析输入：`since` 之后修改的最新 crash-reports/*.txt 与 hs_err_pid*.log
+pub fn collect_inputs(game_dir: &Path, log: Vec<String>, since: SystemTime) -> CrashInputs {
+    let crash_report = newest_file(&game_dir.join("crash-reports"), since, |name| {
+        name.ends_with(".txt")
+    });
+    let hs_err = newest_file(game_dir, since, |name| {
+        name.starts_with("hs_err_pid") && name.ends_with(".log")
+    });
+    CrashInputs {
+        log,
+        crash_report: crash_report.and_then(read_lossy),
+        hs_err: hs_err.and_then(read_lossy),
+    }
+}
+
+/// 目录下 `since` 之后修改、且文件名满足条件的最新文件
+fn newest_file(dir: &Path, since: SystemTime, accept: impl Fn(&str) -> bool) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| accept(&e.file_name().to_string_lossy()))
+        .filter_map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            (modified >= since).then(|| (modified, e.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn read_lossy(path: PathBuf) -> Option<(PathBuf, String)> {
+    let bytes = std::fs::read(&path).ok()?;
+    Some((path, String::from_utf8_lossy(&bytes).to_string()))
+}
+
+/// 单条规则的命中结果
+struct RuleMatch<'a> {
+    rule: &'a CrashRule,
+    evidence: Vec<CrashEvidence>,
+    captures: HashMap<String, String>,
+}
+
+/// 对单条规则逐来源逐行匹配；命名捕获取首次出现的非空值
+fn match_rule<'a>(rule: &'a CrashRule, inputs: &CrashInputs) -> Option<RuleMatch<'a>> {
+    let re = match Regex::new(&rule.pattern) {
+        Ok(re) => re,
+        Err(e) => {
+            log_warn!("崩溃规则 {} 正则无效: {}", rule.id, e);
+            return None;
+        }
+    };
+    let sources = if rule.sources.is_empty() {
+        vec![
+            CrashSource::Log,
+            CrashSource::CrashReport,
+            CrashSource::HsErr,
+        ]
+    } else {
+        rule.sources.clone()
+    };
+
+    let mut evidence = Vec::new();
+    let mut captures = HashMap::new();
+    for source in &sources {
+        for (idx, line) in inputs.lines(source).into_iter().enumerate() {
+            let Some(caps) = re.captures(line) else {
+                continue;
+            };
+            for name in re.capture_names().flatten() {
+                if let Some(m) = caps.name(name) {
+                    captures
+                        .entry(name.to_string())
+                        .or_insert_with(|| m.as_str().to_string());
+                }
+            }
+            if evidence.len() < MAX_EVIDENCE {
+                evidence.push(CrashEvidence {
+                    source: source.clone(),
+                    line: idx + 1,
+                    text: line.trim().to_string(),
+                });
+            }
+        }
+    }
+
+    if evidence.is_empty() {
+        return None;
+    }
+    // class file version → Java 主版本（52 = Java 8，61 = Java 17）
+    if let Some(v) = captures
+        .get("class_version")
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|v| *v > 44)
+    {
+        captures
+            .entry("java".to_string())
+            .or_insert_with(|| (v - 44).to_string());
+    }
+    Some(RuleMatch {
+        rule,
+        evidence,
+        captures,
+    })
+}
+
+/// 以捕获组替换模板中的 `{名称}`，未捕获的占位符替换为"未知"
+fn render(template: &str, captures: &HashMap<String, String>) -> String {
+    PLACEHOLDER.replace_all(template, |c: &regex::Captures| {
+        captures
+            .get(&c[1])
+            .cloned()
+            .unwrap_or_else(|| "未知".to_string())
+    })
+    .to_string()
+}
+
+/// 执行诊断：取置信度最高的命中规则（同分按规则顺序），
+/// 其余命中规则的提取字段用于补全；无规则命中但存在报告文件时返回兜底报告。
+pub fn analyze(rules: &[CrashRule], inputs: &CrashInputs) -> Option<CrashReport> {
+    let mut matches: Vec<RuleMatch> = rules.iter().filter_map(|r| match_rule(r, inputs)).collect();
+    // 稳定排序：同置信度保持规则顺序
+    matches.sort_by(|a, b| {
+        b.rule
+            .confidence
+            .partial_cmp(&a.rule.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let Some(best) = matches.first() else {
+        return fallback_report(inputs);
+    };
+
+    let field = |name: &str| -> Option<String> {
+        matches.iter().find_map(|m| m.captures.get(name).cloned())
+    };
+
+    Some(CrashReport {
+        category: best.rule.category.clone(),
+        rule_id: best.rule.id.clone(),
+        confidence: best.rule.confidence,
+        summary: render(&best.rule.summary, &best.captures),
+        suggested_fixes: best
+            .rule
+            .fixes
+            .iter()
+            .map(|f| render(f, &best.captures))
+            .collect(),
+        evidence: best.evidence.clone(),
+        culprit_mod: field("mod"),
+        related_mod: field("dep"),
+        missing_class: field("class").map(|c| c.replace('/', ".")),
+        required_java: field("java").and_then(|j| j.parse().ok()),
+        report_files: inputs.report_files(),
+        resource_notes: Vec::new(),
+    })
+}
+
+/// 无规则命中时的兜底：仅提示已生成的报告文件
+fn fallback_report(inputs: &CrashInputs) -> Option<CrashReport> {
+    let (category, summary) = if inputs.hs_err.is_some() {
+        (
+            CrashCategory::JvmFatal,
+            "检测到 JVM 崩溃报告 (hs_err_pid*.log)，可在游戏目录中查看",
+        )
+    } else if inputs.crash_report.is_some() {
+        (
+            CrashCategory::Unknown,
+            "检测到游戏崩溃报告 (crash-reports)，可在游戏目录中查看",
+        )
+    } else {
+        return None;
+    };
+    Some(CrashReport {
+        category,
+        rule_id: "fallback".to_string(),
+        confidence: 0.2,
+        summary: summary.to_string(),
+        suggested_fixes: Vec::new(),
+        evidence: Vec::new(),
+        culprit_mod: None,
+        related_mod: None,
+        missing_class: None,
+        required_java: None,
+        report_files: inputs.report_files(),
+        resource_notes: Vec::new(),
+    })
+}
+
+/// 附加资源监控说明到已有报告的 resource_notes。RSS 包含堆外内存，接近 -Xmx 不代表堆溢出，
+/// 因此无规则命中时不据此生成报告
+pub fn with_resource_note(report: Option<CrashReport>, note: Option<String>) -> Option<CrashReport> {
+    report.map(|mut report| {
+        report.resource_notes.extend(note);
+        report
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_inputs(lines: &[&str]) -> CrashInputs {
+        CrashInputs {
+            log: lines.iter().map(|l| l.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn defaults() -> Vec<CrashRule> {
+        load_rules(Path::new("/nonexistent/crash_rules.json"))
+    }
+
+    #[test]
+    fn default_rules_parse_and_compile() {
+        let rules = defaults();
+        assert!(!rules.is_empty());
+        for rule in &rules {
+            assert!(
+                Regex::new(&rule.pattern).is_ok(),
+                "规则 {} 正则无效",
+                rule.id
+            );
+        }
+    }
+
+    #[test]
+    fn class_version_maps_to_java_major() {
+        let inputs = log_inputs(&[
+            "Exception in thread \"main\" java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 52.0",
+        ]);
+        let report = analyze(&defaults(), &inputs).unwrap();
+        assert_eq!(report.category, CrashCategory::JavaVersion);
+        assert_eq!(report.required_java, Some(21));
+        assert!(report.summary.contains("Java 21"));
+        assert_eq!(report.evidence.len(), 1);
+    }
+
+    #[test]
+    fn fabric_missing_dependency_extracts_mods() {
+        let inputs = log_inputs(&[
+            "[main/ERROR]: Incompatible mod set!",
+            " - Mod 'Sodium Extra' (sodium-extra) 0.4.10+mc1.19.2 requires version 0.4.4 or later of mod 'Sodium' (sodium), which is missing!",
+        ]);
+        let report = analyze(&defaults(), &inputs).unwrap();
+        assert_eq!(report.category, CrashCategory::MissingDependency);
+        assert_eq!(report.culprit_mod.as_deref(), Some("sodium-extra"));
+        assert_eq!(report.related_mod.as_deref(), Some("sodium"));
+        assert!(report.suggested_fixes.iter().any(|f| f.contains("sodium")));
+    }
+
+    #[test]
+    fn highest_confidence_wins_and_fields_merge() {
+        let inputs = log_inputs(&[
+            "java.lang.NoClassDefFoundError: com/example/Missing",
+            "java.lang.OutOfMemoryError: Java heap space",
+        ]);
+        let report = analyze(&defaults(), &inputs).unwrap();
+        assert_eq!(report.category, CrashCategory::OutOfMemory);
+        assert_eq!(report.missing_class.as_deref(), Some("com.example.Missing"));
+    }
+
+    #[test]
+    fn crash_report_source_is_scanned() {
+        let inputs = CrashInputs {
+            crash_report: Some((
+                PathBuf::from("crash-2024-01-01_00.00.00-client.txt"),
+                "---- Minecraft Crash Report ----\nDescription: Ticking entity\n\nSuspected Mod: Create (create), Version: 0.5.1\n"
+                    .to_string(),
+            )),
+            ..Default::default()
+        };
+        let report = analyze(&defaults(), &inputs).unwrap();
+        assert_eq!(report.rule_id, "suspected_mod");
+        assert_eq!(report.culprit_mod.as_deref(), Some("create"));
+        assert_eq!(report.evidence[0].source, CrashSource::CrashReport);
+        assert_eq!(report.report_files.len(), 1);
+    }
+
+    #[test]
+    fn fallback_when_only_hs_err_exists() {
+        let inputs = CrashInputs {
+            hs_err: Some((PathBuf::from("hs_err_pid1.log"), "# SIGSEGV".to_string())),
+            ..Default::default()
+        };
+        let report = analyze(&defaults(), &inputs).unwrap();
+        assert_eq!(report.category, CrashCategory::JvmFatal);
+        assert_eq!(report.rule_id, "fallback");
+        assert!(analyze(&defaults(), &CrashInputs::default()).is_none());
+    }
+
+    #[test]
+    fn resource_note_only_annotates_existing_report() {
+        let note = || Some("退出前常驻内存（RSS）达到 -Xmx 的 98%".to_string());
+        assert!(with_resource_note(None, None).is_none());
+        assert!(with_resource_note(None, note()).is_none());
+
+        let inputs = CrashInputs {
+            hs_err: Some((PathBuf::from("hs_err_pid1.log"), "# SIGSEGV".to_string())),
+            ..Default::default()
+        };
+        let annotated = with_resource_note(analyze(&defaults(), &inputs), note()).unwrap();
+        assert_eq!(annotated.category, CrashCategory::JvmFatal);
+        assert_eq!(annotated.resource_notes, vec![note().unwrap()]);
+    }
+
+    #[test]
+    fn user_rules_override_and_append() {
+        let dir = std::env::temp_dir().join(format!("wecraft-crash-rules-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("crash_rules.json");
+        std::fs::write(
+            &path,
+            r#"{"rules":[
+                {"id":"out_of_memory","category":"out_of_memory","pattern":"HEAP GONE","confidence":0.9,"summary":"自定义"},
+                {"id":"team_rule","category":"mod_conflict","pattern":"BadMod (?P<mod>\\w+)","confidence":0.99,"summary":"坏模组 {mod}"}
+            ]}"#,
+        )
+        .unwrap();
+        let rules = load_rules(&path);
+        assert_eq!(rules.len(), defaults().len() + 1);
+        assert_eq!(
+            rules
+                .iter()
+                .find(|r| r.id == "out_of_memory")
+                .unwrap()
+                .pattern,
+            "HEAP GONE"
+        );
+
+        let report = analyze(&rules, &log_inputs(&["BadMod foo", "HEAP GONE"])).unwrap();
+        assert_eq!(report.rule_id, "team_rule");
+        assert_eq!(report.summary, "坏模组 foo");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn collect_inputs_ignores_stale_reports() {
+        let dir = std::env::temp_dir().join(format!("wecraft-crash-inputs-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("crash-reports")).unwrap();
+        std::fs::write(dir.join("crash-reports").join("old.txt"), "old").unwrap();
+        std::fs::write(dir.join("hs_err_pid42.log"), "# SIGSEGV").unwrap();
+
+        let all = collect_inputs(&dir, Vec::new(), SystemTime::UNIX_EPOCH);
+        assert!(all.crash_report.is_some());
+        assert!(all.hs_err.is_some());
+
+        let future = SystemTime::now() + std::time::Duration::from_secs(3600);
+        let none = collect_inputs(&dir, Vec::new(), future);
+        assert!(none.crash_report.is_none());
+        assert!(none.hs_err.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
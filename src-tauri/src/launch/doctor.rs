@@ -0,0 +1,609 @@
+//! 启动前环境检查（doctor）：在启动 Java 进程前发现必然失败或高风险的配置
+//!
+//! 检查项：Java 主版本（对照版本 JSON `javaVersion.majorVersion`）与位数、
+//! 分配内存与物理内存、游戏目录可写与剩余空间、路径中会导致 JVM / LWJGL
+//! 加载失败的字符、模组加载器要求的 Minecraft 版本、JVM 调优配置与 Java 的兼容性。
+//! 每项检查为独立的纯函数，结果汇总为错误（阻止启动）与警告（仅提示）。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::LaunchConfig;
+use super::args::load_version_json;
+use super::memory::{self, JvmProfile};
+use crate::app_context::AppContext;
+use crate::java::JavaInstallation;
+
+/// 剩余空间低于该值（字节）阻止启动
+const MIN_FREE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// 剩余空间低于该值（字节）给出警告
+const LOW_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// 32 位 JVM 可稳定分配的最大堆（MB）
+const MAX_32BIT_HEAP_MB: u32 = 1536;
+
+/// 正式版版本号：1.20 / 1.20.4
+static RELEASE_VERSION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^1\.\d+(\.\d+)?$").unwrap());
+
+/// 问题严重程度
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorSeverity {
+    /// 阻止启动
+    Error,
+    /// 仅提示
+    Warning,
+}
+
+/// 检查项
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorCheck {
+    Java,
+    Memory,
+    GameDir,
+    DiskSpace,
+    Path,
+    Loader,
+    JvmProfile,
+}
+
+/// 单条检查结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DoctorIssue {
+    pub check: DoctorCheck,
+    pub severity: DoctorSeverity,
+    pub message: String,
+    /// 修复建议
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+/// 启动前检查报告
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DoctorReport {
+    pub issues: Vec<DoctorIssue>,
+    /// 是否存在阻止启动的错误
+    pub blocking: bool,
+    /// 检测到的 Java（路径无效或无法运行时为空）
+    #[serde(default)]
+    pub java: Option<JavaInstallation>,
+}
+
+impl DoctorReport {
+    fn new(issues: Vec<DoctorIssue>) -> Self {
+        let blocking = issues.iter().any(|i| i.severity == DoctorSeverity::Error);
+        Self {
+            issues,
+            blocking,
+            java: None,
+        }
+    }
+
+    /// 全部错误信息（分号连接，用作启动失败原因）
+    pub fn error_summary(&self) -> String {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == DoctorSeverity::Error)
+            .map(|i| i.message.as_str())
+            .collect::<Vec<_>>()
+            .join("；")
+    }
+}
+
+fn issue(
+    check: DoctorCheck,
+    severity: DoctorSeverity,
+    message: String,
+    suggestion: Option<&str>,
+) -> DoctorIssue {
+    DoctorIssue {
+        check,
+        severity,
+        message,
+        suggestion: suggestion.map(|s| s.to_string()),
+    }
+}
+
+/// Java：可执行、主版本满足要求、32 位 JVM 的堆上限
+pub fn check_java(
+    java: Option<&JavaInstallation>,
+    java_path: &str,
+    required: Option<u32>,
+    memory_mb: u32,
+) -> Vec<DoctorIssue> {
+    let Some(java) = java else {
+        return vec![issue(
+            DoctorCheck::Java,
+            DoctorSeverity::Error,
+            format!("找不到可用的 Java: {}", java_path),
+            Some("在设置中重新选择 Java 路径，或安装对应版本的 Java"),
+        )];
+    };
+    let mut issues = Vec::new();
+    match (java.major_version, required) {
+        (0, _) => issues.push(issue(
+            DoctorCheck::Java,
+            DoctorSeverity::Warning,
+            format!("无法识别 Java 版本: {}", java.path.display()),
+            None,
+        )),
+        (major, Some(req)) if major < req => issues.push(issue(
+            DoctorCheck::Java,
+            DoctorSeverity::Error,
+            format!("该版本需要 Java {}，当前 Java 为 {}", req, major),
+            Some("切换到满足要求的 Java 版本"),
+        )),
+        (major, Some(8)) if major > 8 => issues.push(issue(
+            DoctorCheck::Java,
+            DoctorSeverity::Warning,
+            format!(
+                "该版本为 Java 8 设计，使用 Java {} 可能无法运行部分模组加载器",
+                major
+            ),
+            Some("如遇启动崩溃，请改用 Java 8"),
+        )),
+        _ => {}
+    }
+    if !java.is_64bit && java.major_version != 0 && memory_mb > MAX_32BIT_HEAP_MB {
+        issues.push(issue(
+            DoctorCheck::Java,
+            DoctorSeverity::Error,
+            format!(
+                "32 位 Java 无法分配 {} MB 内存（上限约 {} MB）",
+                memory_mb, MAX_32BIT_HEAP_MB
+            ),
+            Some("改用 64 位 Java，或降低分配内存"),
+        ));
+    }
+    issues
+}
+
+/// 内存：分配量不超过物理内存，并为系统保留余量
+pub fn check_memory(memory_mb: u32, total_mb: u64) -> Vec<DoctorIssue> {
+    let memory = memory_mb as u64;
+    if total_mb == 0 {
+        return Vec::new();
+    }
+    if memory > total_mb {
+        vec![issue(
+            DoctorCheck::Memory,
+            DoctorSeverity::Error,
+            format!("分配内存 {} MB 超过物理内存 {} MB", memory, total_mb),
+            Some("降低分配内存"),
+        )]
+    } else if memory > total_mb * 85 / 100 {
+        vec![issue(
+            DoctorCheck::Memory,
+            DoctorSeverity::Warning,
+            format!(
+                "分配内存 {} MB 接近物理内存 {} MB，系统可能卡顿",
+                memory, total_mb
+            ),
+            Some("为系统保留至少 15% 的内存"),
+        )]
+    } else if memory < 512 {
+        vec![issue(
+            DoctorCheck::Memory,
+            DoctorSeverity::Warning,
+            format!("分配内存 {} MB 过低，游戏可能内存不足", memory),
+            None,
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+/// 游戏目录：可写、剩余空间充足
+pub fn check_game_dir(dir: &Path, free_bytes: Option<u64>) -> Vec<DoctorIssue> {
+    let mut issues = Vec::new();
+    let probe = dir.join(".wecraft_write_test");
+    let writable = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&probe, b""))
+        .and_then(|_| std::fs::remove_file(&probe));
+    if let Err(e) = writable {
+        issues.push(issue(
+            DoctorCheck::GameDir,
+            DoctorSeverity::Error,
+            format!("游戏目录不可写 {}: {}", dir.display(), e),
+            Some("检查目录权限，或将游戏移动到用户目录下"),
+        ));
+    }
+    match free_bytes {
+        Some(free) if free < MIN_FREE_BYTES => issues.push(issue(
+            DoctorCheck::DiskSpace,
+            DoctorSeverity::Error,
+            format!("磁盘剩余空间仅 {} MB", free / 1024 / 1024),
+            Some("清理磁盘空间后重试"),
+        )),
+        Some(free) if free < LOW_FREE_BYTES => issues.push(issue(
+            DoctorCheck::DiskSpace,
+            DoctorSeverity::Warning,
+            format!("磁盘剩余空间不足 1 GB（{} MB）", free / 1024 / 1024),
+            None,
+        )),
+        _ => {}
+    }
+    issues
+}
+
+/// 路径字符：`!` 破坏 jar URL，classpath 分隔符破坏 -cp，非 ASCII 字符导致旧版 LWJGL 原生库加载失败
+pub fn check_path_chars(path: &str) -> Vec<DoctorIssue> {
+    let mut issues = Vec::new();
+    if path.contains('!') {
+        issues.push(issue(
+            DoctorCheck::Path,
+            DoctorSeverity::Error,
+            format!("路径包含 '!'，Java 无法从中加载 jar: {}", path),
+            Some("重命名目录，去掉 '!'"),
+        ));
+    }
+    // Windows 盘符（C:）中的冒号不是分隔符
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    let body = if cfg!(windows) {
+        path.get(2..).unwrap_or_default()
+    } else {
+        path
+    };
+    if body.contains(separator) {
+        issues.push(issue(
+            DoctorCheck::Path,
+            DoctorSeverity::Error,
+            format!("路径包含 classpath 分隔符 '{}': {}", separator, path),
+            Some(&format!("重命名目录，去掉 '{}'", separator)),
+        ));
+    }
+    if !path.is_ascii() {
+        issues.push(issue(
+            DoctorCheck::Path,
+            DoctorSeverity::Warning,
+            format!(
+                "路径包含非 ASCII 字符，旧版本（LWJGL 2）可能无法加载原生库: {}",
+                path
+            ),
+            Some("如遇原生库加载失败，请将游戏移动到纯英文路径"),
+        ));
+    }
+    issues
+}
+
+/// NeoForge 版本号 → Minecraft 版本：20.4.80 → 1.20.4，21.0.1 → 1.21
+fn neoforge_mc_version(version: &str) -> Option<String> {
+    let mut parts = version.split('.');
+    let major = parts.next()?;
+    let minor = parts.next()?;
+    Some(match minor {
+        "0" => format!("1.{}", major),
+        _ => format!("1.{}.{}", major, minor),
+    })
+}
+
+/// 从加载器依赖库推断其要求的 Minecraft 版本：(加载器名, MC 版本)
+pub fn loader_mc_version(merged: &serde_json::Value) -> Option<(&'static str, String)> {
+    let libraries = merged["libraries"].as_array()?;
+    libraries.iter().find_map(|lib| {
+        let name = lib["name"].as_str()?;
+        let mut parts = name.split(':');
+        let coord = (parts.next()?, parts.next()?);
+        let version = parts.next()?;
+        match coord {
+            ("net.fabricmc", "intermediary") => Some(("Fabric", version.to_string())),
+            ("org.quiltmc", "hashed") => Some(("Quilt", version.to_string())),
+            ("net.legacyfabric", "intermediary") => Some(("LegacyFabric", version.to_string())),
+            ("net.minecraftforge", "forge" | "fmlloader" | "minecraftforge")
+            | ("net.neoforged", "forge") => Some(("Forge", version.split('-').next()?.to_string())),
+            ("net.neoforged", "neoforge") => Some(("NeoForge", neoforge_mc_version(version)?)),
+            ("optifine", "OptiFine") => Some(("OptiFine", version.split('_').next()?.to_string())),
+            _ => None,
+        }
+    })
+}
+
+/// 游戏的 Minecraft 版本：继承的父版本 / clientVersion / 形如正式版的版本 id
+fn game_mc_version(raw: &serde_json::Value) -> Option<String> {
+    raw["inheritsFrom"]
+        .as_str()
+        .or_else(|| raw["clientVersion"].as_str())
+        .or_else(|| raw["id"].as_str().filter(|id| RELEASE_VERSION.is_match(id)))
+        .map(|s| s.to_string())
+}
+
+/// 加载器：要求的 Minecraft 版本与游戏版本一致
+pub fn check_loader(raw: &serde_json::Value, merged: &serde_json::Value) -> Vec<DoctorIssue> {
+    let (Some((loader, required)), Some(actual)) =
+        (loader_mc_version(merged), game_mc_version(raw))
+    else {
+        return Vec::new();
+    };
+    if required == actual {
+        return Vec::new();
+    }
+    vec![issue(
+        DoctorCheck::Loader,
+        DoctorSeverity::Error,
+        format!(
+            "{} 需要 Minecraft {}，当前游戏版本为 {}",
+            loader, required, actual
+        ),
+        Some("重新安装与游戏版本匹配的加载器"),
+    )]
+}
+
+/// JVM 调优配置：所选 Java 支持该回收器；自定义 JVM 参数已指定回收器时忽略配置
+pub fn check_jvm_profile(
+    profile: JvmProfile,
+    java: Option<&JavaInstallation>,
+    jvm_args: &[String],
+) -> Vec<DoctorIssue> {
+    if profile == JvmProfile::None {
+        return Vec::new();
+    }
+    if memory::selects_gc(jvm_args) {
+        return vec![issue(
+            DoctorCheck::JvmProfile,
+            DoctorSeverity::Warning,
+            format!(
+                "自定义 JVM 参数已指定垃圾回收器，将忽略调优配置 {}",
+                profile.label()
+            ),
+            Some("从 JVM 参数中移除 -XX:+Use…GC，或将调优配置设为默认"),
+        )];
+    }
+    match java.and_then(|j| profile.unsupported_reason(j)) {
+        Some(reason) => vec![issue(
+            DoctorCheck::JvmProfile,
+            DoctorSeverity::Error,
+            reason,
+            Some("更换 Java，或改用 G1 (Aikar) 调优配置"),
+        )],
+        None => Vec::new(),
+    }
+}
+
+/// 检测 Java 路径对应的安装（版本、发行商、位数），无法运行时为 None
+pub fn detect_java(java_path: &str) -> Option<JavaInstallation> {
+    resolve_java(java_path).and_then(|p| crate::java::probe_java(&p))
+}
+
+/// 解析 Java 路径：直接路径不存在时按 PATH 查找（如 "java"）
+fn resolve_java(java_path: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(java_path);
+    if path.is_file() {
+        return Some(path);
+    }
+    if path.components().count() != 1 {
+        return None;
+    }
+    let names = if cfg!(windows) {
+        vec![format!("{}.exe", java_path), java_path.to_string()]
+    } else {
+        vec![java_path.to_string()]
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| names.iter().map(move |n| dir.join(n)))
+        .find(|p| p.is_file())
+}
+
+/// 执行全部启动前检查
+pub fn run(config: &LaunchConfig, ctx: &AppContext) -> DoctorReport {
+    let mut issues = Vec::new();
+
+    let version = load_version_json(config, ctx).ok();
+    let required_java = version
+        .as_ref()
+        .and_then(|(_, merged)| merged["javaVersion"]["majorVersion"].as_u64())
+        .map(|v| v as u32);
+    let java = detect_java(&config.java_path);
+    issues.extend(check_java(
+        java.as_ref(),
+        &config.java_path,
+        required_java,
+        config.memory_mb,
+    ));
+    issues.extend(check_jvm_profile(
+        config.jvm_profile,
+        java.as_ref(),
+        &config.jvm_args,
+    ));
+
+    issues.extend(check_memory(
+        config.memory_mb,
+        crate::system::get_system_memory(),
+    ));
+
+    let game_dir = PathBuf::from(&config.game_dir);
+    // 按实际启动的游戏目录所在卷统计（目录尚未创建时取最近的已存在上级目录）
+    let free = game_dir
+        .ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| crate::system::get_disk_free_space(p.to_string_lossy().to_string()).ok());
+    issues.extend(check_game_dir(&game_dir, free));
+
+    // 游戏目录位于根目录下，已覆盖 libraries/natives 所在的路径前缀
+    issues.extend(check_path_chars(&config.game_dir));
+    if let Some(natives) = config
+        .natives_dir
+        .as_deref()
+        .filter(|n| !Path::new(n).starts_with(ctx.game_root()))
+    {
+        issues.extend(check_path_chars(natives));
+    }
+
+    if let Some((raw, merged)) = &version {
+        issues.extend(check_loader(raw, merged));
+    }
+    DoctorReport {
+        java,
+        ..DoctorReport::new(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn java(major: u32, is_64bit: bool) -> JavaInstallation {
+        JavaInstallation {
+            path: PathBuf::from("/usr/bin/java"),
+            version: format!("{}.0.1", major),
+            vendor: "Test".to_string(),
+            is_jdk: false,
+            major_version: major,
+            is_64bit,
+        }
+    }
+
+    fn severities(issues: &[DoctorIssue]) -> Vec<DoctorSeverity> {
+        issues.iter().map(|i| i.severity.clone()).collect()
+    }
+
+    #[test]
+    fn java_version_requirements() {
+        assert_eq!(
+            severities(&check_java(None, "java", Some(17), 2048)),
+            [DoctorSeverity::Error]
+        );
+        assert_eq!(
+            severities(&check_java(Some(&java(8, true)), "java", Some(17), 2048)),
+            [DoctorSeverity::Error]
+        );
+        assert!(check_java(Some(&java(21, true)), "java", Some(17), 2048).is_empty());
+        assert_eq!(
+            severities(&check_java(Some(&java(17, true)), "java", Some(8), 2048)),
+            [DoctorSeverity::Warning]
+        );
+        assert!(check_java(Some(&java(17, true)), "java", None, 2048).is_empty());
+    }
+
+    #[test]
+    fn java_32bit_heap_limit() {
+        let issues = check_java(Some(&java(8, false)), "java", Some(8), 4096);
+        assert_eq!(severities(&issues), [DoctorSeverity::Error]);
+        assert!(check_java(Some(&java(8, false)), "java", Some(8), 1024).is_empty());
+    }
+
+    #[test]
+    fn memory_limits() {
+        assert_eq!(
+            severities(&check_memory(16384, 8192)),
+            [DoctorSeverity::Error]
+        );
+        assert_eq!(
+            severities(&check_memory(7800, 8192)),
+            [DoctorSeverity::Warning]
+        );
+        assert!(check_memory(4096, 8192).is_empty());
+        assert!(check_memory(4096, 0).is_empty());
+    }
+
+    #[test]
+    fn game_dir_writable_and_free_space() {
+        let dir = std::env::temp_dir().join(format!("wecraft-doctor-{}", std::process::id()));
+        assert!(check_game_dir(&dir, Some(10 * LOW_FREE_BYTES)).is_empty());
+        assert_eq!(
+            severities(&check_game_dir(&dir, Some(MIN_FREE_BYTES / 2))),
+            [DoctorSeverity::Error]
+        );
+        assert_eq!(
+            severities(&check_game_dir(&dir, Some(LOW_FREE_BYTES / 2))),
+            [DoctorSeverity::Warning]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn path_characters() {
+        assert!(check_path_chars("/home/steve/.minecraft/versions/1.20.4").is_empty());
+        assert_eq!(
+            severities(&check_path_chars("/home/steve/mc!/versions/x")),
+            [DoctorSeverity::Error]
+        );
+        assert_eq!(
+            severities(&check_path_chars("/home/史蒂夫/.minecraft")),
+            [DoctorSeverity::Warning]
+        );
+    }
+
+    #[test]
+    fn loader_version_detection() {
+        let lib = |name: &str| json!({ "libraries": [{ "name": "org.ow2.asm:asm:9.6" }, { "name": name }] });
+        assert_eq!(
+            loader_mc_version(&lib("net.fabricmc:intermediary:1.20.4")),
+            Some(("Fabric", "1.20.4".to_string()))
+        );
+        assert_eq!(
+            loader_mc_version(&lib("net.minecraftforge:forge:1.20.1-47.2.0:universal")),
+            Some(("Forge", "1.20.1".to_string()))
+        );
+        assert_eq!(
+            loader_mc_version(&lib("net.neoforged:neoforge:20.4.80")),
+            Some(("NeoForge", "1.20.4".to_string()))
+        );
+        assert_eq!(
+            loader_mc_version(&lib("net.neoforged:neoforge:21.0.1")),
+            Some(("NeoForge", "1.21".to_string()))
+        );
+        assert_eq!(
+            loader_mc_version(&lib("net.legacyfabric:intermediary:1.8.9")),
+            Some(("LegacyFabric", "1.8.9".to_string()))
+        );
+        assert_eq!(loader_mc_version(&json!({ "libraries": [] })), None);
+    }
+
+    #[test]
+    fn loader_mismatch_is_blocking() {
+        let merged = json!({ "libraries": [{ "name": "net.fabricmc:intermediary:1.20.1" }] });
+        let raw = json!({ "id": "fabric-1.20.4", "inheritsFrom": "1.20.4" });
+        assert_eq!(
+            severities(&check_loader(&raw, &merged)),
+            [DoctorSeverity::Error]
+        );
+        let raw = json!({ "id": "fabric-1.20.1", "inheritsFrom": "1.20.1" });
+        assert!(check_loader(&raw, &merged).is_empty());
+        // 自定义 id 且无继承信息时无法判断，不报错
+        let raw = json!({ "id": "MyPack" });
+        assert!(check_loader(&raw, &merged).is_empty());
+    }
+
+    #[test]
+    fn jvm_profile_compatibility() {
+        assert!(check_jvm_profile(JvmProfile::None, Some(&java(8, true)), &[]).is_empty());
+        assert_eq!(
+            severities(&check_jvm_profile(
+                JvmProfile::ZgcGenerational,
+                Some(&java(17, true)),
+                &[]
+            )),
+            [DoctorSeverity::Error]
+        );
+        assert!(
+            check_jvm_profile(JvmProfile::ZgcGenerational, Some(&java(21, true)), &[]).is_empty()
+        );
+        assert_eq!(
+            severities(&check_jvm_profile(
+                JvmProfile::G1Aikar,
+                Some(&java(21, true)),
+                &["-XX:+UseZGC".to_string()]
+            )),
+            [DoctorSeverity::Warning]
+        );
+    }
+
+    #[test]
+    fn report_blocking_and_summary() {
+        let report = DoctorReport::new(
+            check_memory(16384, 8192)
+                .into_iter()
+                .chain(check_path_chars("/a/史"))
+                .collect(),
+        );
+        assert!(report.blocking);
+        assert_eq!(report.issues.len(), 2);
+        assert!(report.error_summary().contains("16384"));
+        assert!(!DoctorReport::new(check_path_chars("/a/史")).blocking);
+    }
+}
@@ -0,0 +1,123 @@
+//! 启动状态推送：进度、状态变化与游戏日志以 Tauri 事件广播给前端，
+//! 前端监听事件即可，无需轮询 `front_get_launch_status_by_key` / `front_get_game_log`。
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use super::LaunchStatusInfo;
+use super::log::LogLine;
+use super::monitor::ResourceSample;
+
+/// 启动进度事件
+pub const LAUNCH_PROGRESS_EVENT: &str = "launch-progress";
+
+/// 启动状态变化事件
+pub const LAUNCH_STATUS_EVENT: &str = "launch-status-changed";
+
+/// 游戏日志事件（按时间批量推送）
+pub const GAME_LOG_EVENT: &str = "game-log-lines";
+
+/// 游戏资源采样事件
+pub const GAME_RESOURCE_EVENT: &str = "game-resource-sample";
+
+/// 启动进度（后台管线阶段推进时推送）
+#[derive(Serialize, Clone, Debug)]
+pub struct LaunchProgressEvent {
+    pub game_id: String,
+    /// 真实进度（0-100）
+    pub progress: u32,
+    /// 当前阶段文案
+    pub stage: String,
+}
+
+/// 启动状态变化（启动中 / 运行中 / 崩溃 / 停止），附完整状态快照
+#[derive(Serialize, Clone, Debug)]
+pub struct LaunchStatusEvent {
+    pub game_id: String,
+    #[serde(flatten)]
+    pub info: LaunchStatusInfo,
+}
+
+/// 一批新增日志行
+#[derive(Serialize, Clone, Debug)]
+pub struct GameLogLinesEvent {
+    pub game_id: String,
+    /// 首行的绝对编号（与上一批 offset 不连续时说明有行已移出缓冲，可用 get_game_log 补齐）
+    pub from: usize,
+    /// 下次拉取的游标位置
+    pub offset: usize,
+    pub lines: Vec<LogLine>,
+}
+
+/// 一次资源采样
+#[derive(Serialize, Clone, Debug)]
+pub struct GameResourceEvent {
+    pub game_id: String,
+    #[serde(flatten)]
+    pub sample: ResourceSample,
+}
+
+/// 推送事件使用的应用句柄（首次启动游戏时登记）
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+/// 登记应用句柄（重复登记忽略）
+pub(super) fn attach(app: Option<AppHandle>) {
+    if let Some(app) = app {
+        let _ = APP_HANDLE.set(app);
+    }
+}
+
+/// 向所有前端窗口广播事件（未登记应用句柄时忽略，如单元测试）
+fn emit<S: Serialize + Clone>(event: &str, payload: S) {
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit(event, payload);
+    }
+}
+
+/// 推送启动进度
+pub(super) fn emit_progress(game_id: &str, progress: u32, stage: &str) {
+    emit(
+        LAUNCH_PROGRESS_EVENT,
+        LaunchProgressEvent {
+            game_id: game_id.to_string(),
+            progress,
+            stage: stage.to_string(),
+        },
+    );
+}
+
+/// 推送状态变化
+pub(super) fn emit_status(game_id: &str, info: LaunchStatusInfo) {
+    emit(
+        LAUNCH_STATUS_EVENT,
+        LaunchStatusEvent {
+            game_id: game_id.to_string(),
+            info,
+        },
+    );
+}
+
+/// 推送一批日志行
+pub(super) fn emit_log_lines(game_id: &str, from: usize, offset: usize, lines: Vec<LogLine>) {
+    emit(
+        GAME_LOG_EVENT,
+        GameLogLinesEvent {
+            game_id: game_id.to_string(),
+            from,
+            offset,
+            lines,
+        },
+    );
+}
+
+/// 推送一次资源采样
+pub(super) fn emit_resource_sample(game_id: &str, sample: ResourceSample) {
+    emit(
+        GAME_RESOURCE_EVENT,
+        GameResourceEvent {
+            game_id: game_id.to_string(),
+            sample,
+        },
+    );
+}
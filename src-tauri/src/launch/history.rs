@@ -0,0 +1,217 @@
+//! 启动会话历史：追加写入 `.wecraft/launch_history.jsonl`（每行一条记录）
+//!
+//! 启动时写入一条未结束记录，退出时再写入完整记录；读取时按 session_id
+//! 合并（后写覆盖先写），启动器中途崩溃也能保留"已开始"的会话。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::LaunchStatus;
+
+/// 串行化追加写入，避免多游戏同时退出时行交错
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 单次启动会话记录
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LaunchSession {
+    /// 会话 ID（即启动时生成的游戏 ID）
+    pub session_id: String,
+    pub game_name: String,
+    pub version: String,
+    /// 模组加载器类型（vanilla/fabric/forge 等）
+    #[serde(default)]
+    pub loader_type: Option<String>,
+    #[serde(default)]
+    pub loader_version: Option<String>,
+    pub java_path: String,
+    pub memory_mb: u32,
+    pub username: String,
+    #[serde(default)]
+    pub account_type: Option<String>,
+    /// 开始时间（Unix 秒）
+    pub started_at: i64,
+    /// 结束时间（Unix 秒，未结束为 None）
+    #[serde(default)]
+    pub ended_at: Option<i64>,
+    /// 结束时的状态（Stopped/Crashed；未结束为 Launching）
+    pub status: LaunchStatus,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub crash_summary: Option<String>,
+    /// 游戏输出日志文件路径
+    #[serde(default)]
+    pub log_file: Option<String>,
+}
+
+impl LaunchSession {
+    /// 会话时长（秒），未结束为 0
+    pub fn play_seconds(&self) -> i64 {
+        self.ended_at
+            .map(|end| (end - self.started_at).max(0))
+            .unwrap_or(0)
+    }
+}
+
+/// 历史查询结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LaunchHistory {
+    /// 会话列表（按开始时间倒序）
+    pub sessions: Vec<LaunchSession>,
+    /// 总游戏时长（秒）
+    pub total_play_seconds: i64,
+}
+
+/// 追加一条会话记录
+pub fn append_session(path: &Path, session: &LaunchSession) -> Result<(), String> {
+    let mut line =
+        serde_json::to_string(session).map_err(|e| format!("序列化启动记录失败: {}", e))?;
+    line.push('\n');
+
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| format!("获取启动记录锁失败: {}", e))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建启动记录目录失败: {}", e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("打开启动记录失败: {}", e))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("写入启动记录失败: {}", e))
+}
+
+/// 读取全部会话（按 session_id 合并，跳过损坏行）
+pub fn load_sessions(path: &Path) -> Vec<LaunchSession> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut order: Vec<String> = Vec::new();
+    let mut merged: HashMap<String, LaunchSession> = HashMap::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(session) = serde_json::from_str::<LaunchSession>(line) else {
+            continue;
+        };
+        if !merged.contains_key(&session.session_id) {
+            order.push(session.session_id.clone());
+        }
+        merged.insert(session.session_id.clone(), session);
+    }
+    order
+        .into_iter()
+        .filter_map(|id| merged.remove(&id))
+        .collect()
+}
+
+/// 查询会话历史：可按游戏名过滤，附带总时长
+pub fn query(path: &Path, game_name: Option<&str>) -> LaunchHistory {
+    let mut sessions: Vec<LaunchSession> = load_sessions(path)
+        .into_iter()
+        .filter(|s| game_name.is_none_or(|name| s.game_name == name))
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+    let total_play_seconds = sessions.iter().map(|s| s.play_seconds()).sum();
+    LaunchHistory {
+        sessions,
+        total_play_seconds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, game: &str, started_at: i64) -> LaunchSession {
+        LaunchSession {
+            session_id: id.to_string(),
+            game_name: game.to_string(),
+            version: "1.20.4".to_string(),
+            loader_type: Some("vanilla".to_string()),
+            loader_version: None,
+            java_path: "java".to_string(),
+            memory_mb: 2048,
+            username: "Steve".to_string(),
+            account_type: None,
+            started_at,
+            ended_at: None,
+            status: LaunchStatus::Launching,
+            exit_code: None,
+            crash_summary: None,
+            log_file: None,
+        }
+    }
+
+    fn temp_path(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "wecraft-history-{}-{}",
+            tag,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("launch_history.jsonl")
+    }
+
+    #[test]
+    fn end_record_overrides_start_record() {
+        let path = temp_path("merge");
+        let start = session("a", "g1", 100);
+        append_session(&path, &start).unwrap();
+        let mut end = start.clone();
+        end.ended_at = Some(160);
+        end.status = LaunchStatus::Crashed;
+        end.exit_code = Some(1);
+        append_session(&path, &end).unwrap();
+
+        let sessions = load_sessions(&path);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].exit_code, Some(1));
+        assert_eq!(sessions[0].play_seconds(), 60);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn query_filters_by_game_and_sums_play_time() {
+        let path = temp_path("query");
+        let mut a = session("a", "g1", 100);
+        a.ended_at = Some(130);
+        let mut b = session("b", "g2", 200);
+        b.ended_at = Some(500);
+        let mut c = session("c", "g1", 300);
+        c.ended_at = Some(400);
+        for s in [&a, &b, &c] {
+            append_session(&path, s).unwrap();
+        }
+        // 损坏行与未结束会话不影响统计
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{broken\n")
+            .unwrap();
+        append_session(&path, &session("d", "g1", 50)).unwrap();
+
+        let history = query(&path, Some("g1"));
+        let ids: Vec<&str> = history
+            .sessions
+            .iter()
+            .map(|s| s.session_id.as_str())
+            .collect();
+        assert_eq!(ids, ["c", "a", "d"]);
+        assert_eq!(history.total_play_seconds, 130);
+        assert_eq!(query(&path, None).total_play_seconds, 430);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn missing_file_is_empty_history() {
+        let history = query(&temp_path("missing"), None);
+        assert!(history.sessions.is_empty());
+        assert_eq!(history.total_play_seconds, 0);
+    }
+}
@@ -0,0 +1,284 @@
+//! 游戏启动前 / 退出后的钩子命令：在游戏目录下通过系统 shell 执行，
+//! 以 `WECRAFT_*` 环境变量传入游戏信息（用于备份存档、从 git 同步配置等）。
+//! 命令以独立进程组运行，超时或启动被取消时结束整个进程组。
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::stop::{self, OwnProcessGroup};
+use super::{LaunchConfig, LaunchStatus};
+
+/// 等待命令退出的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 失败信息中保留的输出行数
+const ERROR_TAIL_LINES: usize = 5;
+
+/// 钩子命令执行结果
+#[derive(Debug)]
+pub(super) struct HookOutput {
+    /// 退出码（被信号终止时为 None）
+    pub code: Option<i32>,
+    pub success: bool,
+    /// 输出行（stdout 在前，stderr 在后）
+    pub lines: Vec<String>,
+}
+
+impl HookOutput {
+    /// 退出码文本（被信号终止时为"无"）
+    pub fn code_text(&self) -> String {
+        self.code
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "无".to_string())
+    }
+
+    /// 输出末尾若干行（用于错误信息）
+    pub fn tail(&self) -> String {
+        let skip = self.lines.len().saturating_sub(ERROR_TAIL_LINES);
+        self.lines[skip..].join("\n")
+    }
+}
+
+/// 退出信息（仅退出后命令）
+pub(super) struct ExitInfo {
+    pub code: Option<i32>,
+    pub status: LaunchStatus,
+}
+
+/// 钩子环境变量：游戏附加环境变量 + WECRAFT_* 游戏信息
+pub(super) fn hook_env(
+    game_id: &str,
+    game_name: &str,
+    config: &LaunchConfig,
+    exit: Option<&ExitInfo>,
+) -> BTreeMap<String, String> {
+    let mut env = config.env_vars.clone();
+    env.insert("WECRAFT_GAME_ID".into(), game_id.to_string());
+    env.insert("WECRAFT_GAME_NAME".into(), game_name.to_string());
+    env.insert("WECRAFT_GAME_DIR".into(), config.game_dir.clone());
+    env.insert("WECRAFT_VERSION".into(), config.version.clone());
+    env.insert("WECRAFT_JAVA".into(), config.java_path.clone());
+    env.insert("WECRAFT_USERNAME".into(), config.username.clone());
+    if let Some(exit) = exit {
+        env.insert(
+            "WECRAFT_EXIT_CODE".into(),
+            exit.code.map(|c| c.to_string()).unwrap_or_default(),
+        );
+        let status = match exit.status {
+            LaunchStatus::Crashed => "crashed",
+            LaunchStatus::Stopped => "stopped",
+            _ => "exited",
+        };
+        env.insert("WECRAFT_EXIT_STATUS".into(), status.into());
+    }
+    env
+}
+
+/// 系统 shell 执行命令行（Unix: sh -c，Windows: cmd /C）
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        use super::NoConsoleWindow;
+        use std::os::windows::process::CommandExt;
+        let mut cmd = Command::new("cmd");
+        // 命令行原样交给 cmd，避免 std 重新加引号破坏其中的引号
+        cmd.arg("/C").raw_arg(command).no_console_window();
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// 后台线程读完一路输出（避免管道写满阻塞命令）
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// 在游戏目录下执行钩子命令并收集输出。
+/// 超过 `timeout` 或 `cancelled` 返回 true 时结束命令的整个进程组并返回错误。
+pub(super) async fn run_hook(
+    command: &str,
+    cwd: &Path,
+    env: &BTreeMap<String, String>,
+    timeout: Duration,
+    cancelled: impl Fn() -> bool,
+) -> Result<HookOutput, String> {
+    let mut cmd = shell_command(command);
+    cmd.current_dir(cwd)
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .own_process_group();
+
+    let mut child = cmd.spawn().map_err(|e| format!("执行命令失败: {}", e))?;
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| format!("执行命令失败: {}", e))?
+        {
+            break status;
+        }
+        let error = if cancelled() {
+            "启动已取消".to_string()
+        } else if Instant::now() >= deadline {
+            format!("执行超时（{} 秒）", timeout.as_secs())
+        } else {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        };
+        let _ = stop::kill_tree(child.id());
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(format!("{}，已结束命令", error));
+    };
+
+    // shell 已退出：结束其留在进程组内的后台进程（如 `daemon &`），否则它们持有的输出管道不会关闭
+    let _ = stop::kill_tree(child.id());
+    // 逃逸出进程组的子进程仍可能持有管道：最多等到截止时间，之后放弃未读完的输出
+    while !(stdout.is_finished() && stderr.is_finished()) && Instant::now() < deadline {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    let output: Vec<Vec<u8>> = [stdout, stderr]
+        .into_iter()
+        .map(|h| {
+            if h.is_finished() {
+                h.join().unwrap_or_default()
+            } else {
+                Vec::new()
+            }
+        })
+        .collect();
+
+    let lines = output
+        .iter()
+        .flat_map(|bytes| {
+            String::from_utf8_lossy(bytes)
+                .lines()
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    Ok(HookOutput {
+        code: status.code(),
+        success: status.success(),
+        lines,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn hook_runs_in_game_dir_with_env() {
+        let dir = std::env::temp_dir().join(format!("wecraft-hook-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = LaunchConfig {
+            version: "1.20.4".to_string(),
+            game_dir: dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let exit = ExitInfo {
+            code: Some(1),
+            status: LaunchStatus::Crashed,
+        };
+        let env = hook_env("id-1", "survival", &config, Some(&exit));
+
+        let output = run_hook(
+            "pwd; echo $WECRAFT_GAME_NAME $WECRAFT_VERSION $WECRAFT_EXIT_CODE $WECRAFT_EXIT_STATUS",
+            &dir,
+            &env,
+            Duration::from_secs(10),
+            || false,
+        )
+        .await
+        .unwrap();
+        assert!(output.success);
+        assert_eq!(
+            std::fs::canonicalize(&output.lines[0]).unwrap(),
+            std::fs::canonicalize(&dir).unwrap()
+        );
+        assert_eq!(output.lines[1], "survival 1.20.4 1 crashed");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn failing_hook_reports_code_and_tail() {
+        let output = run_hook(
+            "echo syncing; echo 'fatal: not a git repository' >&2; exit 128",
+            &std::env::temp_dir(),
+            &BTreeMap::new(),
+            Duration::from_secs(10),
+            || false,
+        )
+        .await
+        .unwrap();
+        assert!(!output.success);
+        assert_eq!(output.code, Some(128));
+        assert_eq!(output.tail(), "syncing\nfatal: not a git repository");
+    }
+
+    #[tokio::test]
+    async fn background_child_does_not_block_hook() {
+        let started = Instant::now();
+        let output = run_hook(
+            "echo started; sleep 30 &",
+            &std::env::temp_dir(),
+            &BTreeMap::new(),
+            Duration::from_secs(20),
+            || false,
+        )
+        .await
+        .unwrap();
+        assert!(output.success);
+        assert_eq!(output.lines, vec!["started"]);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn hook_is_killed_on_timeout_and_cancel() {
+        let started = Instant::now();
+        let err = run_hook(
+            "sleep 30 & sleep 30",
+            &std::env::temp_dir(),
+            &BTreeMap::new(),
+            Duration::from_millis(300),
+            || false,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("超时"), "{}", err);
+        // 超时即结束，不等待命令完成
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let err = run_hook(
+            "sleep 30",
+            &std::env::temp_dir(),
+            &BTreeMap::new(),
+            Duration::from_secs(30),
+            || true,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("取消"), "{}", err);
+    }
+}
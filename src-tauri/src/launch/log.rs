@@ -1,28 +1,59 @@
 //! 游戏进程 stdout/stderr 管道捕获与日志缓冲（环形存储，供前端增量拉取）
+//!
+//! 每行同时追加写入本次启动的日志文件 `{logs}/games/{name}/{时间}.log`，
+//! 文件行号与缓冲绝对编号一致，游标早于缓冲起点时从磁盘分页读取；
+//! 新启动时将同目录的旧日志压缩为 `.log.gz` 并只保留最近若干份。
 
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::io::{BufRead, BufReader};
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
 use std::process::Child;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
+use tokio::sync::oneshot;
 
-/// 日志等级（源自 MC 日志格式 `[时间] [线程/等级]` 的简单文本匹配）
+use super::events;
+use super::log_parse::{LogParser, ParsedRecord};
+use crate::log_warn;
+
+/// 日志等级（log4j 事件的 level 属性，纯文本按 `[线程/等级]` 标记解析）
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum LogLevel {
+    /// DEBUG / TRACE
+    Debug,
     Info,
     Warn,
     Error,
     Fatal,
 }
 
-/// 单条日志行
+/// 单条日志（一条 log4j 事件或一行纯文本，堆栈并入 throwable）
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct LogLine {
     pub level: LogLevel,
+    /// 展示文本（纯文本为原始首行，log4j 事件按 `[时间] [线程/等级]: 消息` 渲染）
     pub text: String,
+    /// 事件时间（Unix 毫秒，纯文本行为 None）
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    #[serde(default)]
+    pub thread: Option<String>,
+    #[serde(default)]
+    pub logger: Option<String>,
+    /// 日志消息（不含时间/线程等前缀）
+    #[serde(default)]
+    pub message: String,
+    /// 异常堆栈（多行）
+    #[serde(default)]
+    pub throwable: Option<String>,
 }
 
 /// 日志拉取结果（增量游标）
@@ -40,6 +71,12 @@ struct LogBuffer {
     /// 队首行的绝对编号（增量游标 = start + len）
     start: usize,
     max: usize,
+    /// 落盘文件路径（创建失败为 None；写入结束后保留，轮转压缩后从同名 `.gz` 回读）
+    path: Option<PathBuf>,
+    /// 落盘写入器（写入失败或捕获结束后置空，不再重试）
+    file: Option<LineWriter<File>>,
+    /// 已通过事件推送到的游标
+    emitted: usize,
 }
 
 impl LogBuffer {
@@ -48,10 +85,32 @@ impl LogBuffer {
             lines: VecDeque::new(),
             start: 0,
             max,
+            path: None,
+            file: None,
+            emitted: 0,
         }
     }
 
-    fn push(&mut self, line: LogLine) {
+    /// 创建带落盘文件的缓冲
+    fn with_file(max: usize, path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建日志目录失败: {}", e))?;
+        }
+        let file = File::create(path).map_err(|e| format!("创建日志文件失败: {}", e))?;
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            file: Some(LineWriter::new(file)),
+            ..Self::new(max)
+        })
+    }
+
+    /// 入队一条记录，原始文本同步落盘（回读时按相同规则重新解析）
+    fn push(&mut self, record: ParsedRecord) {
+        let ParsedRecord { line, raw } = record;
+        if let Some(Err(e)) = self.file.as_mut().map(|f| writeln!(f, "{}", raw)) {
+            log_warn!("写入游戏日志文件失败: {}", e);
+            self.file = None;
+        }
         if self.lines.len() >= self.max {
             self.lines.pop_front();
             self.start += 1;
@@ -66,6 +125,16 @@ impl LogBuffer {
         let lines: Vec<LogLine> = self.lines.iter().skip(skip).cloned().collect();
         (from + lines.len(), lines)
     }
+
+    /// 取出尚未推送的行，返回 (首行编号, 新游标, 行列表)；无新行时返回 None
+    fn take_unemitted(&mut self) -> Option<(usize, usize, Vec<LogLine>)> {
+        if self.emitted >= self.start + self.lines.len() {
+            return None;
+        }
+        let (offset, lines) = self.slice_from(self.emitted);
+        self.emitted = offset;
+        Some((offset - lines.len(), offset, lines))
+    }
 }
 
 /// 全局游戏日志存储：game_id → 缓冲
@@ -84,24 +153,32 @@ fn store() -> &'static GameLogStore {
 /// 默认缓冲上限（行）
 const MAX_LINES: usize = 2000;
 
-/// 按 MC 日志格式解析等级：`[线程/FATAL]`/`[FATAL]`/`[SEVERE]` → Fatal，
-/// `[ERROR]` → Error，`[WARN]` → Warn，其余（含 JVM 早期输出、无标记文本）按 Info 处理
-fn parse_level(line: &str) -> LogLevel {
-    if line.contains("/FATAL]") || line.contains("[FATAL]") || line.contains("/SEVERE]") || line.contains("[SEVERE]") {
-        LogLevel::Fatal
-    } else if line.contains("/ERROR]") || line.contains("[ERROR]") {
-        LogLevel::Error
-    } else if line.contains("/WARN]") || line.contains("[WARN]") {
-        LogLevel::Warn
-    } else {
-        LogLevel::Info
-    }
+/// 磁盘分页单次最多返回的行数
+const MAX_PAGE_LINES: usize = 2000;
+
+/// 每个游戏保留的压缩日志份数
+const MAX_ARCHIVED_LOGS: usize = 20;
+
+/// 日志事件批量推送间隔
+const LOG_EVENT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 日志捕获句柄
+pub struct LogCapture {
+    /// 实际写入的日志文件
+    pub file: Option<PathBuf>,
+    /// stdout/stderr 均读到 EOF（进程已退出或关闭了输出）时完成
+    pub closed: oneshot::Receiver<()>,
 }
 
-/// 单路管道读取线程：逐行解析入队，读到 EOF（进程退出）后自然结束。
+/// 单路管道读取线程：逐行解析为日志事件入队，读到 EOF（进程退出）后自然结束。
 /// 使用 read_until + 有损 UTF-8 转换，避免非 UTF-8 字节（如中文 Windows 的 GBK
 /// 控制台输出）触发 `BufRead::lines` 的 InvalidData 而提前中断导致整段日志丢失。
-fn read_loop<R: std::io::Read + Send + 'static>(reader: R, game_id: String) {
+/// 解析器与推送线程共享（推送线程定时结束空闲的挂起记录），入队时持有解析器锁以保持记录顺序。
+fn read_loop<R: std::io::Read + Send + 'static>(
+    reader: R,
+    game_id: String,
+    parser: Arc<Mutex<LogParser>>,
+) {
     let mut buf = BufReader::new(reader);
     let mut bytes: Vec<u8> = Vec::new();
     loop {
@@ -112,41 +189,287 @@ fn read_loop<R: std::io::Read + Send + 'static>(reader: R, game_id: String) {
                 let line = String::from_utf8_lossy(&bytes)
                     .trim_end_matches(['\r', '\n'])
                     .to_string();
-                if let Ok(mut store) = store().inner.lock() {
-                    store
-                        .entry(game_id.clone())
-                        .or_insert_with(|| LogBuffer::new(MAX_LINES))
-                        .push(LogLine {
-                            level: parse_level(&line),
-                            text: line,
-                        });
+                if let Ok(mut parser) = parser.lock() {
+                    push_records(&game_id, parser.feed(&line));
                 }
             }
             Err(_) => break,
         }
     }
+    if let Ok(mut parser) = parser.lock() {
+        push_records(&game_id, parser.finish());
+    }
+}
+
+/// 把解析完成的记录写入指定游戏的缓冲
+fn push_records(game_id: &str, records: impl IntoIterator<Item = ParsedRecord>) {
+    let mut records = records.into_iter().peekable();
+    if records.peek().is_none() {
+        return;
+    }
+    if let Ok(mut store) = store().inner.lock() {
+        let buf = store
+            .entry(game_id.to_string())
+            .or_insert_with(|| LogBuffer::new(MAX_LINES));
+        for record in records {
+            buf.push(record);
+        }
+    }
+}
+
+/// 向游戏日志追加启动器产生的文本行（如退出后命令的输出），与游戏输出同样解析、落盘并立即推送
+pub fn append_lines(game_id: &str, lines: &[String]) {
+    let mut parser = LogParser::default();
+    let mut records: Vec<ParsedRecord> = lines.iter().flat_map(|l| parser.feed(l)).collect();
+    records.extend(parser.finish());
+    push_records(game_id, records);
+    flush_events(game_id);
+}
+
+/// 把指定游戏尚未推送的日志行作为一批事件推送（推送时不持锁）
+fn flush_events(game_id: &str) {
+    let batch = store()
+        .inner
+        .lock()
+        .ok()
+        .and_then(|mut store| store.get_mut(game_id).and_then(|b| b.take_unemitted()));
+    if let Some((from, offset, lines)) = batch {
+        events::emit_log_lines(game_id, from, offset, lines);
+    }
+}
+
+/// 在日志目录下生成本次启动的日志文件路径（`{时间}.log`，同秒重复时追加序号）
+pub fn new_log_path(dir: &Path) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let mut path = dir.join(format!("{}.log", stamp));
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.log", stamp, n));
+        n += 1;
+    }
+    path
+}
+
+/// 压缩目录下除 `keep` 外的 `.log` 文件（正在写入的日志不处理），
+/// 并按文件名（即时间）只保留最近 `MAX_ARCHIVED_LOGS` 份 `.log.gz`
+fn rotate_logs(dir: &Path, keep: &[PathBuf]) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut archived: Vec<PathBuf> = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if name.ends_with(".log.gz") {
+            archived.push(path);
+        } else if name.ends_with(".log") && !keep.contains(&path) {
+            match gzip_file(&path) {
+                Ok(gz) => archived.push(gz),
+                Err(e) => log_warn!("压缩旧日志失败 {}: {}", path.display(), e),
+            }
+        }
+    }
+    archived.sort();
+    let excess = archived.len().saturating_sub(MAX_ARCHIVED_LOGS);
+    for path in archived.into_iter().take(excess) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// 压缩为同名 `.gz` 并删除原文件，返回压缩文件路径
+fn gzip_file(path: &Path) -> Result<PathBuf, String> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+    let mut input = File::open(path).map_err(|e| format!("打开日志失败: {}", e))?;
+    let output = File::create(&gz_path).map_err(|e| format!("创建压缩文件失败: {}", e))?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    std::io::copy(&mut input, &mut encoder).map_err(|e| format!("写入压缩文件失败: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("写入压缩文件失败: {}", e))?;
+    std::fs::remove_file(path).map_err(|e| format!("删除原日志失败: {}", e))?;
+    Ok(gz_path)
+}
+
+/// 当前正在写入的日志文件（轮转时跳过，支持同一游戏多开）
+fn active_log_files() -> Vec<PathBuf> {
+    store()
+        .inner
+        .lock()
+        .map(|guard| {
+            guard
+                .values()
+                .filter(|b| b.file.is_some())
+                .filter_map(|b| b.path.clone())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 /// 开启游戏日志捕获：把子进程 stdout/stderr 接为管道，各起一个线程异步读取。
 /// 必须持续读取直至 EOF，否则管道缓冲写满会阻塞游戏进程。
-pub fn start_capture(game_id: &str, child: &mut Child) {
+/// 新增行每 200ms 批量推送一次；两路管道均结束后推送剩余行并完成 `closed`。
+/// 传入 `log_file` 时同时落盘，并在后台轮转同目录旧日志。
+pub fn start_capture(game_id: &str, child: &mut Child, log_file: Option<&Path>) -> LogCapture {
+    let buffer = log_file.and_then(|path| match LogBuffer::with_file(MAX_LINES, path) {
+        Ok(buf) => Some(buf),
+        Err(e) => {
+            log_warn!("游戏日志将不会落盘: {}", e);
+            None
+        }
+    });
+    let written = buffer.as_ref().and_then(|b| b.path.clone());
+    if let Ok(mut store) = store().inner.lock() {
+        store.insert(
+            game_id.to_string(),
+            buffer.unwrap_or_else(|| LogBuffer::new(MAX_LINES)),
+        );
+    }
+    if let Some(dir) = written.as_ref().and_then(|p| p.parent()) {
+        let dir = dir.to_path_buf();
+        thread::spawn(move || rotate_logs(&dir, &active_log_files()));
+    }
+
+    let mut readers = Vec::new();
+    let mut parsers = Vec::new();
     if let Some(stdout) = child.stdout.take() {
-        thread::spawn({
+        let parser = Arc::new(Mutex::new(LogParser::default()));
+        parsers.push(parser.clone());
+        readers.push(thread::spawn({
             let id = game_id.to_string();
-            move || read_loop(stdout, id)
-        });
+            move || read_loop(stdout, id, parser)
+        }));
     }
     if let Some(stderr) = child.stderr.take() {
-        thread::spawn({
+        let parser = Arc::new(Mutex::new(LogParser::default()));
+        parsers.push(parser.clone());
+        readers.push(thread::spawn({
             let id = game_id.to_string();
-            move || read_loop(stderr, id)
-        });
+            move || read_loop(stderr, id, parser)
+        }));
+    }
+
+    let (closed_tx, closed) = oneshot::channel();
+    thread::spawn({
+        let id = game_id.to_string();
+        move || {
+            loop {
+                // 先判断再推送，保证读取线程结束前入队的行都在最后一批内
+                let done = readers.iter().all(|r| r.is_finished());
+                for parser in &parsers {
+                    if let Ok(mut parser) = parser.lock() {
+                        push_records(&id, parser.flush_idle());
+                    }
+                }
+                flush_events(&id);
+                if done {
+                    break;
+                }
+                thread::sleep(LOG_EVENT_INTERVAL);
+            }
+            let _ = closed_tx.send(());
+        }
+    });
+
+    LogCapture {
+        file: written,
+        closed,
     }
 }
 
-/// 增量拉取指定游戏的日志
+/// 从日志文件读取第 [from, to) 条记录（文件按记录原文写入，重新解析后编号与缓冲一致）
+fn read_file_lines(path: &Path, from: usize, to: usize) -> Vec<LogLine> {
+    parse_log_file(path, to).into_iter().skip(from).collect()
+}
+
+/// 读取并解析整个日志文件；原文件已被轮转压缩时读取同名 `.gz`
+pub fn read_log_file(path: &Path) -> Vec<LogLine> {
+    parse_log_file(path, usize::MAX)
+}
+
+/// 打开日志文件（不存在时回退到轮转压缩后的同名 `.gz`）
+fn open_log_file(path: &Path) -> Option<Box<dyn BufRead>> {
+    if let Ok(file) = File::open(path) {
+        return Some(Box::new(BufReader::new(file)));
+    }
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let file = File::open(PathBuf::from(gz_name)).ok()?;
+    Some(Box::new(BufReader::new(GzDecoder::new(file))))
+}
+
+/// 逐行流式解析日志文件，得到 `limit` 条记录后立即停止（分页无需读完整个文件）
+fn parse_log_file(path: &Path, limit: usize) -> Vec<LogLine> {
+    let Some(mut reader) = open_log_file(path) else {
+        return Vec::new();
+    };
+    let mut parser = LogParser::default();
+    let mut out: Vec<LogLine> = Vec::new();
+    let mut bytes: Vec<u8> = Vec::new();
+    while out.len() < limit {
+        bytes.clear();
+        match reader.read_until(b'\n', &mut bytes) {
+            Ok(0) | Err(_) => {
+                out.extend(parser.finish().map(|r| r.line));
+                break;
+            }
+            Ok(_) => {
+                let text = String::from_utf8_lossy(&bytes);
+                let line = text.trim_end_matches(['\r', '\n']);
+                out.extend(parser.feed(line).into_iter().map(|r| r.line));
+            }
+        }
+    }
+    out.truncate(limit);
+    out
+}
+
+/// 运行中（或本次启动器运行期间启动过的）游戏的全部记录：
+/// 有落盘文件时读文件（含已移出缓冲的记录），否则取缓冲内容；未捕获返回 None
+pub fn captured_records(game_id: &str) -> Option<Vec<LogLine>> {
+    let (path, lines) = {
+        let guard = store().inner.lock().ok()?;
+        let buf = guard.get(game_id)?;
+        match &buf.path {
+            Some(path) => (Some(path.clone()), Vec::new()),
+            None => (None, buf.lines.iter().cloned().collect()),
+        }
+    };
+    Some(match path {
+        Some(path) => read_log_file(&path),
+        None => lines,
+    })
+}
+
+/// 增量拉取指定游戏的日志；游标早于缓冲起点时从落盘文件分页读取
 pub fn get_game_log(game_id: &str, offset: usize) -> GameLogResult {
     let store = store();
+    let disk = {
+        let Ok(guard) = store.inner.lock() else {
+            return GameLogResult {
+                offset,
+                lines: Vec::new(),
+            };
+        };
+        guard
+            .get(game_id)
+            .filter(|buf| offset < buf.start)
+            .and_then(|buf| buf.path.clone().map(|p| (p, buf.start)))
+    };
+    // 磁盘读取不持锁，避免阻塞读取线程
+    if let Some((path, start)) = disk {
+        let lines = read_file_lines(&path, offset, start.min(offset + MAX_PAGE_LINES));
+        if !lines.is_empty() {
+            return GameLogResult {
+                offset: offset + lines.len(),
+                lines,
+            };
+        }
+    }
+
     let Ok(guard) = store.inner.lock() else {
         return GameLogResult {
             offset,
@@ -177,6 +500,17 @@ pub fn scan_logs(game_id: &str, max: usize) -> Vec<LogLine> {
         .unwrap_or_default()
 }
 
+/// 结束落盘：关闭日志文件（游戏与退出后命令的输出均已写入），此后该文件可被轮转压缩。
+/// 缓冲与文件路径保留，已退出游戏的日志仍可分页回读
+pub fn close_capture(game_id: &str) {
+    let Ok(mut guard) = store().inner.lock() else {
+        return;
+    };
+    if let Some(buf) = guard.get_mut(game_id) {
+        buf.file = None;
+    }
+}
+
 /// 释放指定游戏的日志缓冲
 pub fn drop_capture(game_id: &str) {
     if let Ok(mut guard) = store().inner.lock() {
@@ -188,25 +522,20 @@ pub fn drop_capture(game_id: &str) {
 mod tests {
     use super::*;
 
-    #[test]
-    fn parse_level_matches_mc_format() {
-        assert_eq!(parse_level("[12:00:01] [main/INFO]: loading"), LogLevel::Info);
-        assert_eq!(parse_level("[12:00:02] [main/WARN]: deprecated"), LogLevel::Warn);
-        assert_eq!(parse_level("[12:00:03] [Render thread/ERROR]: boom"), LogLevel::Error);
-        assert_eq!(parse_level("[12:00:04] [main/FATAL]: fatal"), LogLevel::Fatal);
-        assert_eq!(parse_level("[12:00:05] [main/SEVERE]: old"), LogLevel::Fatal);
-        assert_eq!(parse_level("[12:00:06] [INFO]: standalone"), LogLevel::Info);
-        assert_eq!(parse_level("Picked up JAVA_TOOL_OPTIONS: -Xmx1G"), LogLevel::Info);
+    /// 解析单条原始记录（可含多行）
+    fn record(raw: &str) -> ParsedRecord {
+        let mut parser = LogParser::default();
+        let mut done: Vec<ParsedRecord> = raw.lines().flat_map(|l| parser.feed(l)).collect();
+        done.extend(parser.finish());
+        assert_eq!(done.len(), 1);
+        done.pop().unwrap()
     }
 
     #[test]
     fn buffer_ring_evicts_oldest() {
         let mut buf = LogBuffer::new(3);
         for i in 0..5 {
-            buf.push(LogLine {
-                level: LogLevel::Info,
-                text: format!("line{}", i),
-            });
+            buf.push(record(&format!("line{}", i)));
         }
         assert_eq!(buf.start, 2);
         assert_eq!(buf.lines.len(), 3);
@@ -216,14 +545,62 @@ mod tests {
         assert_eq!(lines[0].text, "line2");
     }
 
+    #[test]
+    fn unemitted_batches_skip_evicted_lines() {
+        let mut buf = LogBuffer::new(3);
+        buf.push(record("line0"));
+        let (from, offset, lines) = buf.take_unemitted().unwrap();
+        assert_eq!((from, offset, lines.len()), (0, 1, 1));
+        assert!(buf.take_unemitted().is_none());
+
+        for i in 1..6 {
+            buf.push(record(&format!("line{}", i)));
+        }
+        let (from, offset, lines) = buf.take_unemitted().unwrap();
+        assert_eq!((from, offset), (3, 6));
+        assert_eq!(lines[0].text, "line3");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn capture_closes_after_process_output_ends() {
+        use std::process::{Command, Stdio};
+
+        let id = "test-game-closed";
+        let mut child = Command::new("sh")
+            .args(["-c", "echo out; echo err >&2"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let capture = start_capture(id, &mut child, None);
+        capture.closed.await.unwrap();
+        child.wait().unwrap();
+
+        let mut texts: Vec<String> = get_game_log(id, 0).lines.into_iter().map(|l| l.text).collect();
+        texts.sort();
+        assert_eq!(texts, ["err", "out"]);
+        let guard = store().inner.lock().unwrap();
+        assert_eq!(guard.get(id).unwrap().emitted, 2);
+        drop(guard);
+        drop_capture(id);
+    }
+
+    #[test]
+    fn append_lines_reaches_buffer() {
+        let id = "append-lines-test";
+        append_lines(id, &["[WeCraft] hook".to_string(), "done".to_string()]);
+        let result = get_game_log(id, 0);
+        assert_eq!(result.offset, 2);
+        assert_eq!(result.lines[1].text, "done");
+        drop_capture(id);
+    }
+
     #[test]
     fn slice_from_mid_offset() {
         let mut buf = LogBuffer::new(10);
         for i in 0..5 {
-            buf.push(LogLine {
-                level: LogLevel::Info,
-                text: format!("line{}", i),
-            });
+            buf.push(record(&format!("line{}", i)));
         }
         let (offset, lines) = buf.slice_from(3);
         assert_eq!(offset, 5);
@@ -239,10 +616,7 @@ mod tests {
             let mut guard = store.inner.lock().unwrap();
             let buf = guard.entry(id.to_string()).or_insert_with(|| LogBuffer::new(10));
             for i in 0..4 {
-                buf.push(LogLine {
-                    level: LogLevel::Info,
-                    text: format!("l{}", i),
-                });
+                buf.push(record(&format!("l{}", i)));
             }
         }
         let first = get_game_log(id, 0);
@@ -254,4 +628,149 @@ mod tests {
         drop_capture(id);
         assert!(get_game_log(id, 0).lines.is_empty());
     }
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("wecraft-gamelog-{}-{}", tag, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn pages_from_disk_when_offset_predates_ring() {
+        let dir = temp_dir("page");
+        let path = dir.join("run.log");
+        let id = "test-game-disk";
+        {
+            let mut guard = store().inner.lock().unwrap();
+            let mut buf = LogBuffer::with_file(3, &path).unwrap();
+            for i in 0..6 {
+                buf.push(record(&format!(
+                    "[12:00:0{}] [main/WARN]: l{}\n\tat a.B.c(B.java:{})",
+                    i, i, i
+                )));
+            }
+            guard.insert(id.to_string(), buf);
+        }
+        // 缓冲仅剩 l3..l5，游标 1 从磁盘读取 l1..l2（多行记录按条计数）
+        let disk = get_game_log(id, 1);
+        assert_eq!(disk.offset, 3);
+        let messages: Vec<&str> = disk.lines.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, ["l1", "l2"]);
+        assert_eq!(disk.lines[0].level, LogLevel::Warn);
+        assert_eq!(
+            disk.lines[1].throwable.as_deref(),
+            Some("\tat a.B.c(B.java:2)")
+        );
+        let ring = get_game_log(id, disk.offset);
+        assert_eq!(ring.offset, 6);
+        assert_eq!(ring.lines[0].message, "l3");
+        drop_capture(id);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn closed_capture_is_rotated_and_still_readable() {
+        let dir = temp_dir("close");
+        let path = dir.join("run.log");
+        let id = "test-game-close";
+        {
+            let mut guard = store().inner.lock().unwrap();
+            let mut buf = LogBuffer::with_file(2, &path).unwrap();
+            for i in 0..4 {
+                buf.push(record(&format!("l{}", i)));
+            }
+            guard.insert(id.to_string(), buf);
+        }
+        assert!(active_log_files().contains(&path));
+        close_capture(id);
+        assert!(!active_log_files().contains(&path));
+
+        rotate_logs(&dir, &active_log_files());
+        assert!(!path.exists());
+        let disk = get_game_log(id, 0);
+        let texts: Vec<&str> = disk.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["l0", "l1"]);
+        drop_capture(id);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotate_compresses_old_logs_and_prunes_archives() {
+        let dir = temp_dir("rotate");
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..MAX_ARCHIVED_LOGS {
+            std::fs::write(dir.join(format!("2020-01-01_00-00-{:02}.log.gz", i)), b"").unwrap();
+        }
+        std::fs::write(dir.join("2024-05-01_10-00-00.log"), b"old run\n").unwrap();
+        let active = dir.join("2024-05-02_10-00-00.log");
+        std::fs::write(&active, b"current\n").unwrap();
+
+        rotate_logs(&dir, std::slice::from_ref(&active));
+
+        assert!(active.exists());
+        assert!(!dir.join("2024-05-01_10-00-00.log").exists());
+        let gz = dir.join("2024-05-01_10-00-00.log.gz");
+        let mut text = String::new();
+        std::io::Read::read_to_string(
+            &mut flate2::read::GzDecoder::new(File::open(&gz).unwrap()),
+            &mut text,
+        )
+        .unwrap();
+        assert_eq!(text, "old run\n");
+        // 最早的一份压缩日志被清理
+        assert!(!dir.join("2020-01-01_00-00-00.log.gz").exists());
+        assert!(dir.join("2020-01-01_00-00-01.log.gz").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn read_log_file_falls_back_to_archive() {
+        let dir = temp_dir("archive");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2024-05-01_10-00-00.log");
+        std::fs::write(
+            &path,
+            "[10:00:00] [main/ERROR]: boom\n\tat a.B.c(B.java:1)\n",
+        )
+        .unwrap();
+        gzip_file(&path).unwrap();
+        assert!(!path.exists());
+        let lines = read_log_file(&path);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].level, LogLevel::Error);
+        assert!(lines[0].throwable.is_some());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn read_file_lines_pages_records() {
+        let dir = temp_dir("page");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2024-05-01_10-00-00.log");
+        std::fs::write(
+            &path,
+            "[10:00:00] [main/INFO]: a\r\n[10:00:01] [main/ERROR]: b\n\tat a.B.c(B.java:1)\n[10:00:02] [main/INFO]: c\n",
+        )
+        .unwrap();
+        let page = read_file_lines(&path, 1, 2);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].level, LogLevel::Error);
+        assert!(page[0].throwable.is_some());
+        assert_eq!(read_file_lines(&path, 2, 10).len(), 1);
+        assert_eq!(read_log_file(&path)[0].text, read_file_lines(&path, 0, 1)[0].text);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn new_log_path_avoids_collisions() {
+        let dir = temp_dir("name");
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = new_log_path(&dir);
+        std::fs::write(&first, b"").unwrap();
+        let second = new_log_path(&dir);
+        assert_ne!(first, second);
+        assert!(second.to_string_lossy().ends_with("-1.log"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
\ No newline at end of file
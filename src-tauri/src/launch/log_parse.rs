@@ -0,0 +1,517 @@
+//! 游戏输出解析：把 stdout/stderr 原始行组装为结构化日志事件
+//!
+//! 逐行自动识别三种格式：
+//! - log4j XMLLayout（官方 `logging.client` 配置）：`<log4j:Event …>` 至 `</log4j:Event>` 为一条事件
+//! - log4j JsonLayout（单行紧凑模式）：以 `{` 开头且含 `level` 字段的 JSON 对象
+//! - 纯文本：`[时间] [线程/等级] [记录器]: 消息`，其后的堆栈行（`\tat`、`Caused by:` 等）归入同一条
+//!
+//! 解析是确定性的：同一段原始文本（含落盘文件）重新解析得到相同的记录序列
+//! （唯一例外是实时输出中堆栈续行晚于空闲推送到达，回读时会并入上一条）。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::log::{LogLevel, LogLine};
+
+/// 一条解析完成的记录：结构化日志 + 对应的原始文本（落盘用，可能含多行）
+pub struct ParsedRecord {
+    pub line: LogLine,
+    pub raw: String,
+}
+
+/// 纯文本日志头：`[12:00:01] [main/INFO] [net.minecraft.X/]: 消息`（记录器段为 Forge 格式，可选）
+static PLAIN_HEAD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[[^\]]*\] \[([^\]]+)/([A-Z]+)\](?: \[([^\]/]*)(?:/[^\]]*)?\])?: (.*)$").unwrap()
+});
+
+/// 单条 XML 事件最多缓冲的行数（超出仍无结束标签时按纯文本逐行输出）
+const MAX_XML_EVENT_LINES: usize = 4096;
+
+/// XML 属性：`name="value"`
+static XML_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(\w+)="([^"]*)""#).unwrap());
+
+/// 按 MC 日志格式解析等级：`[线程/FATAL]`/`[FATAL]`/`[SEVERE]` → Fatal，
+/// `[ERROR]` → Error，`[WARN]` → Warn，其余（含 JVM 早期输出、无标记文本）按 Info 处理
+pub fn parse_level(line: &str) -> LogLevel {
+    if line.contains("/FATAL]")
+        || line.contains("[FATAL]")
+        || line.contains("/SEVERE]")
+        || line.contains("[SEVERE]")
+    {
+        LogLevel::Fatal
+    } else if line.contains("/ERROR]") || line.contains("[ERROR]") {
+        LogLevel::Error
+    } else if line.contains("/WARN]") || line.contains("[WARN]") {
+        LogLevel::Warn
+    } else {
+        LogLevel::Info
+    }
+}
+
+/// log4j 等级名 → 日志等级（未知等级按 Info 处理）
+fn level_from_name(name: &str) -> LogLevel {
+    match name.to_ascii_uppercase().as_str() {
+        "TRACE" | "DEBUG" => LogLevel::Debug,
+        "WARN" | "WARNING" => LogLevel::Warn,
+        "ERROR" => LogLevel::Error,
+        "FATAL" | "SEVERE" => LogLevel::Fatal,
+        _ => LogLevel::Info,
+    }
+}
+
+/// 堆栈续行：`\tat …`、`\t... 5 more`、`Caused by: …`、`Suppressed: …`
+fn is_continuation(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    line.starts_with('\t')
+        || (trimmed.starts_with("at ") && line.len() > trimmed.len())
+        || trimmed.starts_with("Caused by: ")
+        || trimmed.starts_with("Suppressed: ")
+}
+
+/// 反转义 XML 实体
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+/// 取 `<tag>…</tag>` 的文本内容（支持 CDATA）
+fn xml_element(raw: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = raw.find(&open)? + open.len();
+    let end = start + raw[start..].find(&close)?;
+    let inner = raw[start..end].trim();
+    let text = match inner
+        .strip_prefix("<![CDATA[")
+        .and_then(|s| s.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.to_string(),
+        None => unescape_xml(inner),
+    };
+    Some(text.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// 按原版控制台格式渲染展示文本：`[HH:MM:SS] [线程/等级]: 消息`
+fn render_text(timestamp: Option<i64>, thread: &str, level: &str, message: &str) -> String {
+    let time = timestamp
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "??:??:??".to_string());
+    format!("[{}] [{}/{}]: {}", time, thread, level, message)
+}
+
+/// 解析完整的 `<log4j:Event>` 块
+fn parse_xml_event(raw: &str) -> LogLine {
+    let tag_end = raw.find('>').unwrap_or(raw.len());
+    let mut level = "INFO".to_string();
+    let mut timestamp = None;
+    let mut thread = None;
+    let mut logger = None;
+    for cap in XML_ATTR.captures_iter(&raw[..tag_end]) {
+        let value = unescape_xml(&cap[2]);
+        match &cap[1] {
+            "level" => level = value,
+            "timestamp" => timestamp = value.parse::<i64>().ok(),
+            "thread" => thread = Some(value),
+            "logger" => logger = Some(value),
+            _ => {}
+        }
+    }
+    let message = xml_element(raw, "log4j:Message").unwrap_or_default();
+    let throwable = xml_element(raw, "log4j:Throwable").filter(|t| !t.is_empty());
+    LogLine {
+        level: level_from_name(&level),
+        text: render_text(
+            timestamp,
+            thread.as_deref().unwrap_or("?"),
+            &level,
+            &message,
+        ),
+        timestamp,
+        thread,
+        logger,
+        message,
+        throwable,
+    }
+}
+
+/// 解析单行 JsonLayout 事件（非事件对象返回 None）
+fn parse_json_event(raw: &str) -> Option<LogLine> {
+    let value: serde_json::Value = serde_json::from_str(raw.trim()).ok()?;
+    let level = value["level"].as_str()?.to_string();
+    let message = value["message"].as_str().unwrap_or_default().to_string();
+    let timestamp = value["timeMillis"].as_i64().or_else(|| {
+        let instant = &value["instant"];
+        Some(
+            instant["epochSecond"].as_i64()? * 1000
+                + instant["nanoOfSecond"].as_i64().unwrap_or(0) / 1_000_000,
+        )
+    });
+    let thread = value["thread"].as_str().map(|s| s.to_string());
+    let logger = value["loggerName"].as_str().map(|s| s.to_string());
+    let throwable = value.get("thrown").map(|thrown| {
+        let mut lines = vec![match thrown["message"].as_str() {
+            Some(msg) => format!("{}: {}", thrown["name"].as_str().unwrap_or("?"), msg),
+            None => thrown["name"].as_str().unwrap_or("?").to_string(),
+        }];
+        for frame in thrown["extendedStackTrace"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            lines.push(format!(
+                "\tat {}.{}({}:{})",
+                frame["class"].as_str().unwrap_or("?"),
+                frame["method"].as_str().unwrap_or("?"),
+                frame["file"].as_str().unwrap_or("Unknown Source"),
+                frame["line"].as_i64().unwrap_or(-1)
+            ));
+        }
+        lines.join("\n")
+    });
+    Some(LogLine {
+        level: level_from_name(&level),
+        text: render_text(
+            timestamp,
+            thread.as_deref().unwrap_or("?"),
+            &level,
+            &message,
+        ),
+        timestamp,
+        thread,
+        logger,
+        message,
+        throwable,
+    })
+}
+
+/// 解析纯文本记录：首行为日志头，其余为堆栈续行
+fn parse_plain(lines: &[String]) -> LogLine {
+    let head = lines.first().cloned().unwrap_or_default();
+    let throwable = (lines.len() > 1).then(|| lines[1..].join("\n"));
+    let (level, thread, logger, message) = match PLAIN_HEAD.captures(&head) {
+        Some(cap) => (
+            level_from_name(&cap[2]),
+            Some(cap[1].to_string()),
+            cap.get(3)
+                .map(|m| m.as_str().to_string())
+                .filter(|s| !s.is_empty()),
+            cap[4].to_string(),
+        ),
+        None => {
+            // 无等级标记的异常输出（如 `Exception in thread "main" …`）按错误处理
+            let level = match parse_level(&head) {
+                LogLevel::Info if throwable.is_some() => LogLevel::Error,
+                level => level,
+            };
+            (level, None, None, head.clone())
+        }
+    };
+    LogLine {
+        level,
+        text: head,
+        timestamp: None,
+        thread,
+        logger,
+        message,
+        throwable,
+    }
+}
+
+/// 解析器当前挂起的记录
+enum Pending {
+    None,
+    /// 纯文本：等待可能的堆栈续行
+    Plain(Vec<String>),
+    /// XML 事件：等待 `</log4j:Event>`
+    Xml(Vec<String>),
+}
+
+/// 单路输出流的增量解析器（stdout / stderr 各持一个，状态互不干扰）
+pub struct LogParser {
+    pending: Pending,
+    /// 上次 `flush_idle` 以来是否有新输入
+    fed: bool,
+}
+
+impl Default for LogParser {
+    fn default() -> Self {
+        Self {
+            pending: Pending::None,
+            fed: false,
+        }
+    }
+}
+
+impl LogParser {
+    /// 输入一行原始文本（不含换行符），返回因此完成的记录
+    pub fn feed(&mut self, line: &str) -> Vec<ParsedRecord> {
+        self.fed = true;
+        let mut done = Vec::new();
+        if let Pending::Xml(lines) = &mut self.pending {
+            lines.push(line.to_string());
+            if line.contains("</log4j:Event>") {
+                done.extend(self.finish());
+            } else if lines.len() >= MAX_XML_EVENT_LINES {
+                // 迟迟没有结束标签（输出被截断等）：逐行按纯文本输出，避免吞掉后续全部日志
+                if let Pending::Xml(lines) = std::mem::replace(&mut self.pending, Pending::None) {
+                    done.extend(lines.into_iter().map(|l| ParsedRecord {
+                        line: parse_plain(std::slice::from_ref(&l)),
+                        raw: l,
+                    }));
+                }
+            }
+            return done;
+        }
+        if let (true, Pending::Plain(lines)) = (is_continuation(line), &mut self.pending) {
+            lines.push(line.to_string());
+            return done;
+        }
+
+        done.extend(self.finish());
+        if line.trim_start().starts_with("<log4j:Event") {
+            self.pending = Pending::Xml(vec![line.to_string()]);
+            if line.contains("</log4j:Event>") {
+                done.extend(self.finish());
+            }
+        } else if let Some(event) = line
+            .trim_start()
+            .starts_with('{')
+            .then(|| parse_json_event(line))
+            .flatten()
+        {
+            done.push(ParsedRecord {
+                line: event,
+                raw: line.to_string(),
+            });
+        } else {
+            self.pending = Pending::Plain(vec![line.to_string()]);
+        }
+        done
+    }
+
+    /// 实时输出定时调用：自上次调用以来没有新输入时结束挂起的纯文本记录，
+    /// 使最后一行（常为致命错误）无需等到下一行输出即可显示与搜索。
+    /// XML 事件仍等待结束标签
+    pub fn flush_idle(&mut self) -> Option<ParsedRecord> {
+        let idle = !std::mem::replace(&mut self.fed, false);
+        match self.pending {
+            Pending::Plain(_) if idle => self.finish(),
+            _ => None,
+        }
+    }
+
+    /// 结束挂起的记录（流结束或遇到新记录时调用）
+    pub fn finish(&mut self) -> Option<ParsedRecord> {
+        match std::mem::replace(&mut self.pending, Pending::None) {
+            Pending::None => None,
+            Pending::Plain(lines) => Some(ParsedRecord {
+                line: parse_plain(&lines),
+                raw: lines.join("\n"),
+            }),
+            Pending::Xml(lines) => {
+                let raw = lines.join("\n");
+                Some(ParsedRecord {
+                    line: parse_xml_event(&raw),
+                    raw,
+                })
+            }
+        }
+    }
+}
+
+/// 一次性解析整段原始文本（测试用；落盘文件回读见 `log::read_log_file` 的流式解析）
+#[cfg(test)]
+pub fn parse_all<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<LogLine> {
+    let mut parser = LogParser::default();
+    let mut out: Vec<LogLine> = Vec::new();
+    for line in lines {
+        out.extend(parser.feed(line).into_iter().map(|r| r.line));
+    }
+    out.extend(parser.finish().map(|r| r.line));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_level_matches_mc_format() {
+        assert_eq!(
+            parse_level("[12:00:01] [main/INFO]: loading"),
+            LogLevel::Info
+        );
+        assert_eq!(
+            parse_level("[12:00:02] [main/WARN]: deprecated"),
+            LogLevel::Warn
+        );
+        assert_eq!(
+            parse_level("[12:00:03] [Render thread/ERROR]: boom"),
+            LogLevel::Error
+        );
+        assert_eq!(
+            parse_level("[12:00:04] [main/FATAL]: fatal"),
+            LogLevel::Fatal
+        );
+        assert_eq!(
+            parse_level("[12:00:05] [main/SEVERE]: old"),
+            LogLevel::Fatal
+        );
+        assert_eq!(parse_level("[12:00:06] [INFO]: standalone"), LogLevel::Info);
+        assert_eq!(
+            parse_level("Picked up JAVA_TOOL_OPTIONS: -Xmx1G"),
+            LogLevel::Info
+        );
+    }
+
+    #[test]
+    fn xml_event_with_throwable_is_one_record() {
+        let raw = r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000000" level="ERROR" thread="Render thread">
+  <log4j:Message><![CDATA[Unreported exception thrown!]]></log4j:Message>
+  <log4j:Throwable><![CDATA[java.lang.IllegalStateException: boom
+	at net.minecraft.client.Minecraft.run(Minecraft.java:42)
+]]></log4j:Throwable>
+</log4j:Event>
+[12:00:01] [main/INFO]: after"#;
+        let lines = parse_all(raw.lines());
+        assert_eq!(lines.len(), 2);
+        let event = &lines[0];
+        assert_eq!(event.level, LogLevel::Error);
+        assert_eq!(event.timestamp, Some(1_700_000_000_000));
+        assert_eq!(event.thread.as_deref(), Some("Render thread"));
+        assert_eq!(
+            event.logger.as_deref(),
+            Some("net.minecraft.client.Minecraft")
+        );
+        assert_eq!(event.message, "Unreported exception thrown!");
+        assert!(
+            event
+                .text
+                .ends_with("[Render thread/ERROR]: Unreported exception thrown!")
+        );
+        assert_eq!(
+            event.throwable.as_deref(),
+            Some(
+                "java.lang.IllegalStateException: boom\n\tat net.minecraft.client.Minecraft.run(Minecraft.java:42)"
+            )
+        );
+        assert_eq!(lines[1].message, "after");
+    }
+
+    #[test]
+    fn xml_message_without_cdata_is_unescaped() {
+        let raw = r#"<log4j:Event logger="x" timestamp="1" level="WARN" thread="main"><log4j:Message>a &lt;b&gt; &amp; c</log4j:Message></log4j:Event>"#;
+        let lines = parse_all([raw]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].level, LogLevel::Warn);
+        assert_eq!(lines[0].message, "a <b> & c");
+    }
+
+    #[test]
+    fn plain_stack_trace_groups_with_head() {
+        let raw = "[12:00:01] [main/INFO] [cpw.mods.modlauncher.Launcher/MODLAUNCHER]: start\n\
+Exception in thread \"main\" java.lang.RuntimeException: x\n\
+\tat a.B.c(B.java:1)\n\
+Caused by: java.lang.NullPointerException\n\
+\t... 3 more\n\
+[12:00:02] [Worker-Main-1/DEBUG]: next";
+        let lines = parse_all(raw.lines());
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].thread.as_deref(), Some("main"));
+        assert_eq!(
+            lines[0].logger.as_deref(),
+            Some("cpw.mods.modlauncher.Launcher")
+        );
+        assert_eq!(lines[0].message, "start");
+        assert!(lines[0].throwable.is_none());
+        assert_eq!(lines[1].level, LogLevel::Error);
+        assert_eq!(
+            lines[1].throwable.as_deref().map(|t| t.lines().count()),
+            Some(3)
+        );
+        assert_eq!(lines[2].level, LogLevel::Debug);
+        assert_eq!(lines[2].thread.as_deref(), Some("Worker-Main-1"));
+    }
+
+    #[test]
+    fn idle_plain_record_is_flushed() {
+        let mut parser = LogParser::default();
+        assert!(parser.feed("[12:00:01] [main/ERROR]: fatal").is_empty());
+        // 本周期内有新输入，仍等待可能的堆栈续行
+        assert!(parser.flush_idle().is_none());
+        parser.feed("\tat a.B.c(B.java:1)");
+        assert!(parser.flush_idle().is_none());
+        let record = parser.flush_idle().unwrap();
+        assert_eq!(record.line.message, "fatal");
+        assert_eq!(
+            record.line.throwable.as_deref(),
+            Some("\tat a.B.c(B.java:1)")
+        );
+        assert!(parser.flush_idle().is_none());
+
+        // XML 事件不会被提前结束
+        parser.feed("<log4j:Event logger=\"a\" level=\"INFO\" thread=\"main\">");
+        assert!(parser.flush_idle().is_none());
+        assert!(parser.flush_idle().is_none());
+    }
+
+    #[test]
+    fn unterminated_xml_event_falls_back_to_plain_lines() {
+        let mut lines =
+            vec!["<log4j:Event logger=\"a\" level=\"INFO\" thread=\"main\">".to_string()];
+        lines.extend((1..MAX_XML_EVENT_LINES).map(|i| format!("partial {}", i)));
+        lines.push("[12:00:01] [main/INFO]: after".to_string());
+        let parsed = parse_all(lines.iter().map(|l| l.as_str()));
+        assert_eq!(parsed.len(), MAX_XML_EVENT_LINES + 1);
+        assert_eq!(parsed[1].text, "partial 1");
+        assert_eq!(parsed[MAX_XML_EVENT_LINES].message, "after");
+    }
+
+    #[test]
+    fn json_layout_event() {
+        let raw = r#"{"instant":{"epochSecond":1700000000,"nanoOfSecond":5000000},"thread":"main","level":"FATAL","loggerName":"root","message":"dead","thrown":{"name":"java.lang.Error","message":"e","extendedStackTrace":[{"class":"a.B","method":"c","file":"B.java","line":3}]}}"#;
+        let lines = parse_all([raw, "{not json"]);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].level, LogLevel::Fatal);
+        assert_eq!(lines[0].timestamp, Some(1_700_000_000_005));
+        assert_eq!(lines[0].logger.as_deref(), Some("root"));
+        assert_eq!(
+            lines[0].throwable.as_deref(),
+            Some("java.lang.Error: e\n\tat a.B.c(B.java:3)")
+        );
+        assert_eq!(lines[1].text, "{not json");
+    }
+
+    #[test]
+    fn reparsing_raw_records_is_stable() {
+        let input = [
+            "[12:00:01] [main/WARN]: a",
+            "\tat x.Y.z(Y.java:1)",
+            r#"<log4j:Event logger="l" timestamp="2" level="INFO" thread="t">"#,
+            "<log4j:Message><![CDATA[m]]></log4j:Message>",
+            "</log4j:Event>",
+            "",
+        ];
+        let mut parser = LogParser::default();
+        let mut records = Vec::new();
+        for line in input {
+            records.extend(parser.feed(line));
+        }
+        records.extend(parser.finish());
+        let raw: Vec<String> = records.iter().map(|r| r.raw.clone()).collect();
+        let joined = raw.join("\n");
+        let reparsed = parse_all(joined.split('\n'));
+        let live: Vec<LogLine> = records.into_iter().map(|r| r.line).collect();
+        assert_eq!(reparsed, live);
+    }
+}
@@ -0,0 +1,326 @@
+//! 游戏日志检索：在后端按等级 / 正则 / 线程 / 记录器 / 时间范围过滤，
+//! 返回命中记录及上下文，避免前端拉取全量日志再自行过滤。
+//!
+//! 检索范围为某个游戏的启动会话（按开始时间从新到旧），每个会话的记录
+//! 来自运行中游戏的日志缓冲 / 落盘文件，或历史会话的（已压缩）日志文件。
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::log::{LogLevel, LogLine};
+
+/// 单页默认返回的命中条数
+const DEFAULT_LIMIT: usize = 200;
+
+/// 单页最多返回的命中条数
+const MAX_LIMIT: usize = 1000;
+
+/// 上下文最多行数（前后各）
+const MAX_CONTEXT: usize = 20;
+
+/// 检索游标：从指定会话的第 index 条记录继续
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LogSearchCursor {
+    pub session_id: String,
+    pub index: usize,
+}
+
+/// 日志检索条件（除 game_name 外均可省略）
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LogSearchQuery {
+    /// 游戏名（游戏目录名）
+    pub game_name: String,
+    /// 仅检索最近一次启动
+    pub latest_only: bool,
+    /// 等级集合（空为全部）
+    pub levels: Vec<LogLevel>,
+    /// 正则表达式（匹配展示文本与异常堆栈）
+    pub pattern: Option<String>,
+    /// 正则是否区分大小写
+    pub case_sensitive: bool,
+    /// 线程名（包含匹配，不区分大小写）
+    pub thread: Option<String>,
+    /// 记录器名（包含匹配，不区分大小写）
+    pub logger: Option<String>,
+    /// 起始时间（Unix 毫秒，含）
+    pub since: Option<i64>,
+    /// 结束时间（Unix 毫秒，含）
+    pub until: Option<i64>,
+    /// 每条命中前后附带的上下文条数
+    pub context: usize,
+    /// 本页最多命中条数（0 为默认值）
+    pub limit: usize,
+    /// 上一页返回的游标（None 从头开始）
+    pub cursor: Option<LogSearchCursor>,
+}
+
+/// 单条命中
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LogMatch {
+    pub session_id: String,
+    /// 记录在会话中的编号（与 get_game_log 游标一致）
+    pub index: usize,
+    pub line: LogLine,
+    /// 前置上下文
+    pub before: Vec<LogLine>,
+    /// 后置上下文
+    pub after: Vec<LogLine>,
+}
+
+/// 检索结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LogSearchResult {
+    pub matches: Vec<LogMatch>,
+    /// 下一页游标（None 表示已检索完毕）
+    pub next_cursor: Option<LogSearchCursor>,
+    /// 本次扫描的记录数
+    pub scanned: usize,
+}
+
+/// 待检索的会话：ID、开始时间（Unix 秒，用于无时间戳的纯文本行）与按需加载的记录
+pub struct SearchSource<'a> {
+    pub session_id: String,
+    pub started_at: i64,
+    pub load: Box<dyn FnOnce() -> Vec<LogLine> + 'a>,
+}
+
+/// 编译后的过滤条件
+struct Filter {
+    levels: Vec<LogLevel>,
+    pattern: Option<Regex>,
+    thread: Option<String>,
+    logger: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+}
+
+impl Filter {
+    fn new(query: &LogSearchQuery) -> Result<Self, String> {
+        let pattern = match query.pattern.as_deref().filter(|p| !p.is_empty()) {
+            Some(p) => Some(
+                RegexBuilder::new(p)
+                    .case_insensitive(!query.case_sensitive)
+                    .build()
+                    .map_err(|e| format!("无效的正则表达式: {}", e))?,
+            ),
+            None => None,
+        };
+        let lower = |s: &Option<String>| {
+            s.as_deref()
+                .filter(|s| !s.is_empty())
+                .map(str::to_lowercase)
+        };
+        Ok(Self {
+            levels: query.levels.clone(),
+            pattern,
+            thread: lower(&query.thread),
+            logger: lower(&query.logger),
+            since: query.since,
+            until: query.until,
+        })
+    }
+
+    /// `time` 为记录的有效时间（纯文本行沿用上一条事件时间）
+    fn matches(&self, line: &LogLine, time: i64) -> bool {
+        let contains = |field: &Option<String>, needle: &Option<String>| match needle {
+            Some(n) => field
+                .as_deref()
+                .is_some_and(|f| f.to_lowercase().contains(n.as_str())),
+            None => true,
+        };
+        (self.levels.is_empty() || self.levels.contains(&line.level))
+            && contains(&line.thread, &self.thread)
+            && contains(&line.logger, &self.logger)
+            && self.since.is_none_or(|t| time >= t)
+            && self.until.is_none_or(|t| time <= t)
+            && self.pattern.as_ref().is_none_or(|re| {
+                re.is_match(&line.text) || line.throwable.as_deref().is_some_and(|t| re.is_match(t))
+            })
+    }
+}
+
+/// 按条件检索会话列表（顺序即检索顺序），命中数达到上限时返回下一页游标
+pub fn search(
+    query: &LogSearchQuery,
+    sources: Vec<SearchSource>,
+) -> Result<LogSearchResult, String> {
+    let filter = Filter::new(query)?;
+    let limit = match query.limit {
+        0 => DEFAULT_LIMIT,
+        n => n.min(MAX_LIMIT),
+    };
+    let context = query.context.min(MAX_CONTEXT);
+
+    // 游标指向的会话之前的会话已检索过
+    let skip_sessions = query
+        .cursor
+        .as_ref()
+        .and_then(|c| sources.iter().position(|s| s.session_id == c.session_id))
+        .unwrap_or(0);
+
+    let mut matches: Vec<LogMatch> = Vec::new();
+    let mut scanned = 0;
+    for source in sources.into_iter().skip(skip_sessions) {
+        let start = query
+            .cursor
+            .as_ref()
+            .filter(|c| c.session_id == source.session_id)
+            .map(|c| c.index)
+            .unwrap_or(0);
+        let lines = (source.load)();
+        let mut time = source.started_at * 1000;
+        for (index, line) in lines.iter().enumerate() {
+            time = line.timestamp.unwrap_or(time);
+            if index < start {
+                continue;
+            }
+            if matches.len() >= limit {
+                return Ok(LogSearchResult {
+                    matches,
+                    next_cursor: Some(LogSearchCursor {
+                        session_id: source.session_id,
+                        index,
+                    }),
+                    scanned,
+                });
+            }
+            scanned += 1;
+            if filter.matches(line, time) {
+                matches.push(LogMatch {
+                    session_id: source.session_id.clone(),
+                    index,
+                    line: line.clone(),
+                    before: lines[index.saturating_sub(context)..index].to_vec(),
+                    after: lines[index + 1..(index + 1 + context).min(lines.len())].to_vec(),
+                });
+            }
+        }
+    }
+    Ok(LogSearchResult {
+        matches,
+        next_cursor: None,
+        scanned,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launch::log_parse::parse_all;
+
+    fn source(id: &str, started_at: i64, raw: &'static str) -> SearchSource<'static> {
+        SearchSource {
+            session_id: id.to_string(),
+            started_at,
+            load: Box::new(move || parse_all(raw.lines())),
+        }
+    }
+
+    const NEW: &str = "[10:00:00] [main/INFO]: boot\n\
+[10:00:01] [Render thread/WARN] [net.minecraft.client/]: missing texture\n\
+[10:00:02] [Render thread/ERROR]: crash\n\
+\tat a.B.c(B.java:1)\n\
+[10:00:03] [main/INFO]: stop";
+
+    const OLD: &str = "[09:00:00] [main/ERROR]: old failure\n\
+[09:00:01] [main/INFO]: old stop";
+
+    fn sources() -> Vec<SearchSource<'static>> {
+        vec![source("new", 200, NEW), source("old", 100, OLD)]
+    }
+
+    #[test]
+    fn filters_by_level_with_context() {
+        let query = LogSearchQuery {
+            levels: vec![LogLevel::Error],
+            context: 1,
+            ..Default::default()
+        };
+        let result = search(&query, sources()).unwrap();
+        let ids: Vec<(&str, usize)> = result
+            .matches
+            .iter()
+            .map(|m| (m.session_id.as_str(), m.index))
+            .collect();
+        assert_eq!(ids, [("new", 2), ("old", 0)]);
+        assert_eq!(result.matches[0].before[0].level, LogLevel::Warn);
+        assert_eq!(result.matches[0].after[0].message, "stop");
+        assert!(result.matches[1].before.is_empty());
+        assert!(result.next_cursor.is_none());
+        assert_eq!(result.scanned, 6);
+    }
+
+    #[test]
+    fn regex_matches_throwable_and_thread_logger_filters() {
+        let query = LogSearchQuery {
+            pattern: Some("B\\.JAVA".to_string()),
+            ..Default::default()
+        };
+        let result = search(&query, sources()).unwrap();
+        assert_eq!(result.matches.len(), 1);
+        assert_eq!(result.matches[0].line.message, "crash");
+
+        let query = LogSearchQuery {
+            pattern: Some("B\\.JAVA".to_string()),
+            case_sensitive: true,
+            ..Default::default()
+        };
+        assert!(search(&query, sources()).unwrap().matches.is_empty());
+
+        let query = LogSearchQuery {
+            thread: Some("render".to_string()),
+            logger: Some("minecraft".to_string()),
+            ..Default::default()
+        };
+        let result = search(&query, sources()).unwrap();
+        assert_eq!(result.matches.len(), 1);
+        assert_eq!(result.matches[0].line.message, "missing texture");
+    }
+
+    #[test]
+    fn invalid_regex_is_error() {
+        let query = LogSearchQuery {
+            pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(search(&query, sources()).is_err());
+    }
+
+    #[test]
+    fn time_range_uses_session_start_for_plain_lines() {
+        let query = LogSearchQuery {
+            since: Some(150_000),
+            ..Default::default()
+        };
+        let result = search(&query, sources()).unwrap();
+        assert!(result.matches.iter().all(|m| m.session_id == "new"));
+        assert_eq!(result.matches.len(), 4);
+    }
+
+    #[test]
+    fn cursor_pages_across_sessions() {
+        let mut query = LogSearchQuery {
+            limit: 3,
+            ..Default::default()
+        };
+        let first = search(&query, sources()).unwrap();
+        assert_eq!(first.matches.len(), 3);
+        assert_eq!(
+            first.next_cursor,
+            Some(LogSearchCursor {
+                session_id: "new".to_string(),
+                index: 3,
+            })
+        );
+        query.cursor = first.next_cursor;
+        let second = search(&query, sources()).unwrap();
+        let ids: Vec<(&str, usize)> = second
+            .matches
+            .iter()
+            .map(|m| (m.session_id.as_str(), m.index))
+            .collect();
+        assert_eq!(ids, [("new", 3), ("old", 0), ("old", 1)]);
+        assert!(second.next_cursor.is_none());
+    }
+}
@@ -0,0 +1,259 @@
+//! 内存与 JVM 调优：自动内存模式（按物理内存、当前占用、加载器与模组数量估算堆大小）
+//! 与命名的 GC 调优配置（G1 Aikar 参数 / 分代 ZGC / Shenandoah）。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::java::JavaInstallation;
+
+/// 自动内存的下限（MB）
+const AUTO_MIN_HEAP_MB: u32 = 1024;
+
+/// 自动内存的上限（MB，更大的堆对 Minecraft 收益有限且拉长 GC 停顿）
+const AUTO_MAX_HEAP_MB: u32 = 16384;
+
+/// 为系统与其他程序保留的可用内存（MB）
+const SYSTEM_RESERVE_MB: u64 = 1536;
+
+/// 每个模组额外分配的内存（MB）
+const PER_MOD_MB: u32 = 24;
+
+/// 自定义 JVM 参数中的垃圾回收器选择（-XX:+UseG1GC / -XX:+UseZGC 等）
+static GC_SELECTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-XX:\+Use\w+GC$").unwrap());
+
+/// JVM 调优配置
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JvmProfile {
+    /// 不附加调优参数（JVM 默认）
+    #[default]
+    None,
+    /// G1 + Aikar 参数（Java 8+，通用推荐）
+    G1Aikar,
+    /// 分代 ZGC（Java 21+，低停顿，适合大内存）
+    ZgcGenerational,
+    /// Shenandoah（Java 17+，Oracle 构建不包含）
+    Shenandoah,
+}
+
+impl JvmProfile {
+    /// 显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            JvmProfile::None => "默认",
+            JvmProfile::G1Aikar => "G1 (Aikar)",
+            JvmProfile::ZgcGenerational => "分代 ZGC",
+            JvmProfile::Shenandoah => "Shenandoah",
+        }
+    }
+
+    /// 要求的最低 Java 主版本
+    pub fn min_java_major(self) -> u32 {
+        match self {
+            JvmProfile::None | JvmProfile::G1Aikar => 8,
+            JvmProfile::ZgcGenerational => 21,
+            JvmProfile::Shenandoah => 17,
+        }
+    }
+
+    /// 不满足要求时的原因（Java 版本过低 / 发行版不包含该回收器）
+    pub fn unsupported_reason(self, java: &JavaInstallation) -> Option<String> {
+        if java.major_version != 0 && java.major_version < self.min_java_major() {
+            return Some(format!(
+                "{} 需要 Java {}+，当前为 Java {}",
+                self.label(),
+                self.min_java_major(),
+                java.major_version
+            ));
+        }
+        if self == JvmProfile::Shenandoah && java.vendor.to_lowercase().contains("oracle") {
+            return Some(format!(
+                "{} 不包含在 Oracle 构建的 Java 中（当前 {}）",
+                self.label(),
+                java.vendor
+            ));
+        }
+        None
+    }
+
+    /// 调优参数（java_major 未知时按配置的最低版本处理）
+    pub fn flags(self, java_major: Option<u32>) -> Vec<String> {
+        let flags: &[&str] = match self {
+            JvmProfile::None => &[],
+            JvmProfile::G1Aikar => &[
+                "-XX:+UseG1GC",
+                "-XX:+ParallelRefProcEnabled",
+                "-XX:MaxGCPauseMillis=200",
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+DisableExplicitGC",
+                "-XX:+AlwaysPreTouch",
+                "-XX:G1NewSizePercent=30",
+                "-XX:G1MaxNewSizePercent=40",
+                "-XX:G1HeapRegionSize=8M",
+                "-XX:G1ReservePercent=20",
+                "-XX:G1HeapWastePercent=5",
+                "-XX:G1MixedGCCountTarget=4",
+                "-XX:InitiatingHeapOccupancyPercent=15",
+                "-XX:G1MixedGCLiveThresholdPercent=90",
+                "-XX:SurvivorRatio=32",
+                "-XX:+PerfDisableSharedMem",
+                "-XX:MaxTenuringThreshold=1",
+            ],
+            JvmProfile::ZgcGenerational => &[
+                "-XX:+UseZGC",
+                "-XX:+DisableExplicitGC",
+                "-XX:+AlwaysPreTouch",
+            ],
+            JvmProfile::Shenandoah => &[
+                "-XX:+UseShenandoahGC",
+                "-XX:+DisableExplicitGC",
+                "-XX:+AlwaysPreTouch",
+            ],
+        };
+        let mut flags: Vec<String> = flags.iter().map(|f| f.to_string()).collect();
+        // Java 21/22 需显式开启分代模式；23 起默认分代，24 起该选项已移除
+        if self == JvmProfile::ZgcGenerational && java_major.unwrap_or(self.min_java_major()) < 23 {
+            flags.insert(1, "-XX:+ZGenerational".to_string());
+        }
+        flags
+    }
+}
+
+/// 自定义 JVM 参数是否已指定垃圾回收器（与调优配置同时指定会导致 JVM 拒绝启动）
+pub fn selects_gc(jvm_args: &[String]) -> bool {
+    jvm_args.iter().any(|a| GC_SELECTOR.is_match(a))
+}
+
+/// 加载器的基础堆大小（MB）：Forge / NeoForge 系启动开销最大
+fn base_heap_mb(loader: Option<&str>) -> u32 {
+    match loader {
+        Some("Forge" | "NeoForge") => 3072,
+        Some(_) => 2560,
+        None => 2048,
+    }
+}
+
+/// 自动内存：基础堆 + 每个模组的增量，不超过当前可用内存减去系统保留、
+/// 物理内存的 3/4 与上限，最低 1 GB
+pub fn auto_heap_mb(total_mb: u64, used_mb: u64, loader: Option<&str>, mod_count: usize) -> u32 {
+    let wanted = base_heap_mb(loader).saturating_add((mod_count as u32).saturating_mul(PER_MOD_MB));
+    if total_mb == 0 {
+        return wanted.clamp(AUTO_MIN_HEAP_MB, AUTO_MAX_HEAP_MB);
+    }
+    let available = total_mb
+        .saturating_sub(used_mb)
+        .saturating_sub(SYSTEM_RESERVE_MB);
+    let limit = available.min(total_mb * 3 / 4).min(AUTO_MAX_HEAP_MB as u64) as u32;
+    wanted.min(limit).max(AUTO_MIN_HEAP_MB)
+}
+
+/// 游戏目录 mods 下启用的模组数量（.jar，不含 .disabled）
+pub fn count_mods(game_dir: &Path) -> usize {
+    std::fs::read_dir(game_dir.join("mods"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| {
+                    e.path()
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+                })
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn java(major: u32, vendor: &str) -> JavaInstallation {
+        JavaInstallation {
+            path: PathBuf::from("/usr/bin/java"),
+            version: major.to_string(),
+            vendor: vendor.to_string(),
+            is_jdk: false,
+            major_version: major,
+            is_64bit: true,
+        }
+    }
+
+    #[test]
+    fn auto_heap_scales_with_loader_and_mods() {
+        assert_eq!(auto_heap_mb(32768, 4096, None, 0), 2048);
+        assert_eq!(auto_heap_mb(32768, 4096, Some("Fabric"), 0), 2560);
+        assert_eq!(auto_heap_mb(32768, 4096, Some("Forge"), 100), 3072 + 2400);
+        // 上限
+        assert_eq!(
+            auto_heap_mb(131072, 0, Some("Forge"), 1000),
+            AUTO_MAX_HEAP_MB
+        );
+    }
+
+    #[test]
+    fn auto_heap_respects_available_memory() {
+        // 8 GB 物理内存、已用 5 GB：可用 3 GB - 保留 1.5 GB
+        assert_eq!(auto_heap_mb(8192, 5120, Some("Forge"), 50), 1536);
+        // 可用内存不足时保底 1 GB
+        assert_eq!(auto_heap_mb(4096, 3900, None, 0), AUTO_MIN_HEAP_MB);
+        // 未知物理内存时按估算值
+        assert_eq!(auto_heap_mb(0, 0, None, 10), 2048 + 240);
+    }
+
+    #[test]
+    fn profiles_validate_java_version_and_vendor() {
+        assert!(
+            JvmProfile::G1Aikar
+                .unsupported_reason(&java(8, "Oracle Corporation"))
+                .is_none()
+        );
+        assert!(
+            JvmProfile::ZgcGenerational
+                .unsupported_reason(&java(17, "Eclipse Adoptium"))
+                .is_some()
+        );
+        assert!(
+            JvmProfile::ZgcGenerational
+                .unsupported_reason(&java(21, "Eclipse Adoptium"))
+                .is_none()
+        );
+        assert!(
+            JvmProfile::Shenandoah
+                .unsupported_reason(&java(17, "Oracle Corporation"))
+                .is_some()
+        );
+        assert!(
+            JvmProfile::Shenandoah
+                .unsupported_reason(&java(17, "Eclipse Adoptium"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn zgc_generational_flag_only_where_needed() {
+        assert!(
+            JvmProfile::ZgcGenerational
+                .flags(Some(21))
+                .contains(&"-XX:+ZGenerational".to_string())
+        );
+        assert!(
+            !JvmProfile::ZgcGenerational
+                .flags(Some(24))
+                .contains(&"-XX:+ZGenerational".to_string())
+        );
+        assert!(JvmProfile::None.flags(Some(21)).is_empty());
+    }
+
+    #[test]
+    fn detects_user_selected_gc() {
+        assert!(selects_gc(&["-Xss2M".into(), "-XX:+UseG1GC".into()]));
+        assert!(selects_gc(&["-XX:+UseShenandoahGC".into()]));
+        assert!(!selects_gc(&[
+            "-XX:-UseAdaptiveSizePolicy".into(),
+            "-XX:+UseStringDeduplication".into()
+        ]));
+    }
+}
@@ -8,27 +8,57 @@ use once_cell::sync::OnceCell;
 use core::convert::{From, Into};
 use core::prelude::v1::derive;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
 use crate::app_context::AppContext;
 use crate::download::models::FileDownload;
-use crate::download::{DownloadManager, extract_jar, parse_version_json};
+use crate::download::{DownloadManager, parse_version_json};
 use crate::game::GameManager;
-use crate::launch::args::build_launch_args;
-use crate::{log_error, log_info};
+use crate::java::JavaInstallation;
+use crate::launch::args::{build_launch_args, load_version_json};
+use crate::launch::stop::OwnProcessGroup;
+use crate::{log_error, log_info, log_warn};
 use tauri::AppHandle;
 use tauri::Manager;
+use tokio::sync::oneshot;
 
+mod argfile;
 mod args;
+mod cgroup;
 pub mod command;
+mod crash;
+mod doctor;
+mod events;
+mod history;
+mod hooks;
 mod log;
+mod log_parse;
+mod log_search;
+mod memory;
+mod monitor;
+mod natives;
+mod report;
+mod script;
+mod stop;
 mod window;
 pub use command::*;
+pub use cgroup::ResourceUsage;
+pub use crash::CrashReport;
+pub use doctor::DoctorReport;
+pub use history::{LaunchHistory, LaunchSession};
+pub use report::GameCrashDetails;
+pub use script::{LaunchPreview, ScriptKind};
 pub use log::{GameLogResult, LogLevel, LogLine};
+pub use log_search::{LogSearchQuery, LogSearchResult};
+pub use memory::JvmProfile;
+pub use monitor::ResourceSeries;
+pub use stop::StopOutcome;
+pub(crate) use args::maven_path_from_name;
 
 /// 避免子进程（如 java.exe 控制台程序）在 Windows 上弹出黑窗口。
 /// 无窗口创建标志 `CREATE_NO_WINDOW` (0x08000000)，非 Windows 平台为空操作。
@@ -74,13 +104,28 @@ pub enum LaunchStatus {
     Stopped,
 }
 
+/// java_path 取该值时按版本 JSON 的 Java 要求自动选择已安装的 Java
+pub const AUTO_JAVA_PATH: &str = "auto";
+
+/// 版本 JSON 未声明 javaVersion（1.17 之前的旧版本）时要求的 Java 主版本
+const LEGACY_JAVA_MAJOR: u32 = 8;
+
 /// 启动配置结构体
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LaunchConfig {
-    /// Java 可执行文件路径
+    /// Java 可执行文件路径（"auto" 为自动选择）
     pub java_path: String,
-    /// 内存大小（MB）
+    /// 内存大小（MB，-Xmx；auto_memory 时由启动器计算）
     pub memory_mb: u32,
+    /// 初始堆大小（MB，-Xms，不超过 memory_mb；为空时交由 JVM 默认策略）
+    #[serde(default)]
+    pub min_memory_mb: Option<u32>,
+    /// 自动内存：按物理内存、当前占用、加载器与模组数量计算 memory_mb
+    #[serde(default)]
+    pub auto_memory: bool,
+    /// JVM 调优配置（GC 参数预设）
+    #[serde(default)]
+    pub jvm_profile: JvmProfile,
     /// Minecraft 版本
     pub version: String,
     /// 游戏目录（.minecraft 根目录，兼作游戏标识）
@@ -100,7 +145,7 @@ pub struct LaunchConfig {
     /// 用户属性（--userProperties）
     #[serde(default)]
     pub user_properties: Option<String>,
-    /// natives 解压目录（默认 {game_dir}/natives）
+    /// natives 解压目录（不指定时按版本原生库集合使用托管目录 {root}/natives/{hash}）
     #[serde(default)]
     pub natives_dir: Option<String>,
     /// 账户类型（microsoft/offline/thirdparty）
@@ -124,13 +169,69 @@ pub struct LaunchConfig {
     /// 启动游戏后启动器窗口是否保持可见（false 时启动后隐藏，游戏退出后恢复）
     #[serde(default = "default_true")]
     pub launcher_visible: bool,
+    /// 使用版本 JSON 的 log4j 配置（XMLLayout）启动，按结构化事件解析游戏日志；
+    /// false 时不传该配置，游戏输出纯文本日志
+    #[serde(default = "default_true")]
+    pub xml_log_layout: bool,
+    /// 自动选择 Java 时优先 JDK（默认优先 JRE）
+    #[serde(default)]
+    pub prefer_jdk: bool,
+    /// 停止游戏时等待正常关闭（保存世界）的宽限期（秒），超时后强制结束
+    #[serde(default = "default_stop_grace_secs")]
+    pub stop_grace_secs: u32,
+    /// 快速游戏：启动后直接进入服务器 / 单人世界 / Realms
+    #[serde(default)]
+    pub quick_play: Option<QuickPlay>,
+    /// 启动包装命令（如 gamemoderun / mangohud / prime-run / taskset -c 0-3，置于 java 之前）
+    #[serde(default)]
+    pub wrapper_command: Vec<String>,
+    /// 附加环境变量（在继承的环境之上设置）
+    #[serde(default)]
+    pub env_vars: BTreeMap<String, String>,
+    /// 启动前命令（游戏目录下经 shell 执行，失败则终止启动）
+    #[serde(default)]
+    pub pre_launch_command: Option<String>,
+    /// 退出后命令（游戏退出后执行，输出追加到游戏日志）
+    #[serde(default)]
+    pub post_exit_command: Option<String>,
+    /// 启动前 / 退出后命令的超时（秒），超时后结束命令的整个进程组
+    #[serde(default = "default_hook_timeout_secs")]
+    pub hook_timeout_secs: u32,
+    /// 资源限制（仅 Linux cgroups v2）：游戏进程经 systemd-run 放入独立 scope，限制内存 / CPU 权重 / 任务数
+    #[serde(default)]
+    pub resource_limits: bool,
+    /// CPU 权重（cpu.weight，1-10000，默认 100；并行实例按权重分配 CPU）
+    #[serde(default)]
+    pub cpu_weight: Option<u32>,
+    /// 最大任务（线程）数（pids.max，默认 4096）
+    #[serde(default)]
+    pub pids_max: Option<u32>,
+}
+
+/// 快速游戏目标（1.20+ 通过 --quickPlay* 参数，旧版本多人服务器回退为 --server/--port）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuickPlay {
+    /// 多人服务器
+    Multiplayer {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+    },
+    /// 单人世界（saves 下的存档目录名）
+    Singleplayer { world: String },
+    /// Realms（Realm ID）
+    Realms { realm_id: String },
 }
 
 impl Default for LaunchConfig {
     fn default() -> Self {
         Self {
-            java_path: "java".to_string(),
+            java_path: AUTO_JAVA_PATH.to_string(),
             memory_mb: 2048,
+            min_memory_mb: None,
+            auto_memory: false,
+            jvm_profile: JvmProfile::None,
             version: "1.20.4".to_string(),
             game_dir: "./.minecraft".to_string(),
             assets_dir: "./.minecraft/assets".to_string(),
@@ -147,6 +248,18 @@ impl Default for LaunchConfig {
             resolution_height: None,
             fullscreen: false,
             launcher_visible: true,
+            xml_log_layout: true,
+            prefer_jdk: false,
+            stop_grace_secs: default_stop_grace_secs(),
+            quick_play: None,
+            wrapper_command: Vec::new(),
+            env_vars: BTreeMap::new(),
+            pre_launch_command: None,
+            post_exit_command: None,
+            hook_timeout_secs: default_hook_timeout_secs(),
+            resource_limits: false,
+            cpu_weight: None,
+            pids_max: None,
         }
     }
 }
@@ -156,6 +269,16 @@ fn default_true() -> bool {
     true
 }
 
+/// 默认停止宽限期（serde default）
+fn default_stop_grace_secs() -> u32 {
+    15
+}
+
+/// 默认钩子命令超时（serde default）
+fn default_hook_timeout_secs() -> u32 {
+    300
+}
+
 /// 关闭主启动器窗口（销毁 webview，真正释放 React/CPU/内存）。
 /// 同时置位 LAUNCHER_KEEP_ALIVE，阻止进程随窗口关闭而退出。
 fn close_launcher_window(app: &AppHandle) {
@@ -218,6 +341,8 @@ pub struct LaunchGameInfo {
     pub progress: u32,
     /// 当前阶段文案
     pub stage: String,
+    /// 实时资源占用（仅启用资源限制且运行中）
+    pub resources: Option<ResourceUsage>,
 }
 
 /// 增量拉取指定游戏的日志（游标式，offset=0 时返回全部现存日志）
@@ -237,9 +362,30 @@ pub struct LaunchStatusInfo {
     /// 最近一次错误信息
     #[serde(default)]
     pub last_error: Option<String>,
-    /// 崩溃原因摘要（崩溃时生成）
+    /// 崩溃原因摘要（崩溃时生成，等同 crash_report.summary）
     #[serde(default)]
     pub crash_summary: Option<String>,
+    /// 结构化崩溃诊断（崩溃时生成）
+    #[serde(default)]
+    pub crash_report: Option<CrashReport>,
+    /// 启动前检查结果（检查阶段完成后生成）
+    #[serde(default)]
+    pub preflight: Option<DoctorReport>,
+    /// 自动选择的 Java（仅 auto 模式）
+    #[serde(default)]
+    pub java_selection: Option<JavaSelection>,
+    /// 停止方式（用户停止后生成）
+    #[serde(default)]
+    pub stop_outcome: Option<StopOutcome>,
+}
+
+/// 自动选择 Java 的结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JavaSelection {
+    /// 选中的 Java
+    pub java: JavaInstallation,
+    /// 版本 JSON 要求的 Java 主版本
+    pub required_major: u32,
 }
 
 /// 单个游戏的运行时状态
@@ -252,10 +398,28 @@ struct RunningGame {
     stage: String,
     /// 进程退出码（Running 态退出时记录）
     exit_code: Option<i32>,
-    /// 崩溃原因摘要（分析日志生成）
-    crash_summary: Option<String>,
+    /// 崩溃诊断报告（分析日志与崩溃报告生成）
+    crash_report: Option<CrashReport>,
     /// 日志缓冲键（与 start_capture 使用的 game_id 一致）
     log_key: String,
+    /// 启动时间（仅分析此后生成的崩溃报告文件）
+    started_at: SystemTime,
+    /// 用户崩溃规则文件路径
+    crash_rules_path: PathBuf,
+    /// 启动会话记录（退出时补全并追加写入历史）
+    session: LaunchSession,
+    /// 启动前检查结果
+    preflight: Option<DoctorReport>,
+    /// 自动选择的 Java
+    java_selection: Option<JavaSelection>,
+    /// 用户已请求停止（此后进程退出按已停止处理，不做崩溃分析）
+    stopping: bool,
+    /// 停止方式
+    stop_outcome: Option<StopOutcome>,
+    /// 托管的原生库目录（游戏结束且无其他游戏引用时清理）
+    natives_dir: Option<PathBuf>,
+    /// 资源限制 scope 的 cgroup（读取资源占用，游戏结束后清空）
+    cgroup: Option<cgroup::GameCgroup>,
 }
 
 /// 启动管理器状态（多子进程，以每次启动生成的游戏 ID 为 key）
@@ -283,6 +447,7 @@ pub fn init_launch_manager() {
     LAUNCH_MANAGER
         .set(Mutex::new(LaunchManager::default()))
         .unwrap_or_else(|_| panic!("启动管理器已初始化，不可重复调用"));
+    cgroup::remove_stale();
 }
 
 /// 获取启动管理器全局锁
@@ -295,38 +460,134 @@ fn lock_manager() -> Result<MutexGuard<'static, LaunchManager>, String> {
 
 // ======================== 状态机辅助 ========================
 
-/// 轮询单个游戏的子进程退出状态，同步更新状态机
-fn update_process_status(game: &mut RunningGame) -> Result<(), String> {
+/// 崩溃分析所需的输入快照（在释放启动锁后分析，避免读取日志与报告时阻塞其他调用）
+struct PendingCrash {
+    game_id: String,
+    log_key: String,
+    game_dir: PathBuf,
+    started_at: SystemTime,
+    crash_rules_path: PathBuf,
+}
+
+impl PendingCrash {
+    fn of(game_id: &str, game: &RunningGame) -> Self {
+        Self {
+            game_id: game_id.to_string(),
+            log_key: game.log_key.clone(),
+            game_dir: PathBuf::from(&game.config.game_dir),
+            started_at: game.started_at,
+            crash_rules_path: game.crash_rules_path.clone(),
+        }
+    }
+}
+
+/// 轮询单个游戏的子进程退出状态，同步更新状态机（状态变化时推送事件）。
+/// 进程崩溃时返回待分析的输入，调用方释放启动锁后交给 `store_crash_report`。
+fn update_process_status(
+    game_id: &str,
+    game: &mut RunningGame,
+) -> Result<Option<PendingCrash>, String> {
+    let mut pending = None;
     if game.status == LaunchStatus::Running {
         if let Some(child) = &mut game.child_process {
-            match child.try_wait() {
+            let exited = match child.try_wait() {
                 Ok(Some(status)) => {
                     game.exit_code = status.code();
-                    game.status = if status.success() {
+                    game.status = if status.success() || game.stopping {
                         LaunchStatus::Stopped
                     } else {
                         LaunchStatus::Crashed
                     };
+                    game.crash_report = None;
                     if game.status == LaunchStatus::Crashed {
-                        let summary = analyze_crash(&game);
-                        game.crash_summary = summary;
-                    } else {
-                        game.crash_summary = None;
+                        pending = Some(PendingCrash::of(game_id, game));
                     }
                     game.child_process = None;
+                    true
                 }
-                Ok(None) => {}
+                Ok(None) => false,
                 Err(e) => {
                     game.last_error = Some(format!("检查进程状态失败: {}", e));
                     game.status = LaunchStatus::Crashed;
                     game.child_process = None;
+                    true
                 }
+            };
+            if exited {
+                publish_status(game_id, game);
+            }
+        }
+    }
+    Ok(pending)
+}
+
+/// 轮询全部游戏（或指定游戏）的退出状态；崩溃分析在释放启动锁后执行
+fn refresh_status(game_id: Option<&str>) -> Result<(), String> {
+    let pending = {
+        let mut manager = lock_manager()?;
+        let mut pending = Vec::new();
+        for (id, game) in manager.processes.iter_mut() {
+            if game_id.is_some_and(|g| g != id) {
+                continue;
             }
+            pending.extend(update_process_status(id, game)?);
         }
+        pending
+    };
+    for crash in pending {
+        store_crash_report(crash);
     }
     Ok(())
 }
 
+/// 在锁外完成崩溃分析，再回填到游戏记录并推送状态事件
+fn store_crash_report(crash: PendingCrash) {
+    let report = analyze_crash(&crash);
+    let Ok(mut manager) = lock_manager() else {
+        return;
+    };
+    let game = manager
+        .processes
+        .get_mut(&crash.game_id)
+        .filter(|g| g.status == LaunchStatus::Crashed && g.crash_report.is_none());
+    if let Some(game) = game {
+        game.crash_report = report;
+        publish_status(&crash.game_id, game);
+    }
+}
+
+/// 指定游戏是否有实例正在启动或运行（更换加载器等改写游戏文件的操作前检查）
+pub fn is_game_active(game_name: &str) -> bool {
+    let _ = refresh_status(None);
+    let Ok(manager) = lock_manager() else {
+        return false;
+    };
+    manager.processes.values().any(|g| {
+        g.session.game_name == game_name
+            && matches!(g.status, LaunchStatus::Launching | LaunchStatus::Running)
+    })
+}
+
+/// 单个游戏的状态快照
+fn status_info(game: &RunningGame) -> LaunchStatusInfo {
+    LaunchStatusInfo {
+        status: game.status.clone(),
+        progress: game.progress,
+        stage: game.stage.clone(),
+        last_error: game.last_error.clone(),
+        crash_summary: game.crash_report.as_ref().map(|r| r.summary.clone()),
+        crash_report: game.crash_report.clone(),
+        preflight: game.preflight.clone(),
+        java_selection: game.java_selection.clone(),
+        stop_outcome: game.stop_outcome,
+    }
+}
+
+/// 推送状态变化事件（附完整快照）
+fn publish_status(game_id: &str, game: &RunningGame) {
+    events::emit_status(game_id, status_info(game));
+}
+
 /// 聚合所有游戏状态：任一启动中 > 任一运行中 > 任一崩溃 > 全部停止 > 空闲
 fn aggregate_status(manager: &LaunchManager) -> LaunchStatus {
     if manager.processes.is_empty() {
@@ -363,77 +624,85 @@ fn set_game_progress(game_id: &str, progress: u32, stage: &str) {
         if let Some(game) = manager.processes.get_mut(game_id) {
             game.progress = progress.min(100);
             game.stage = stage.to_string();
+            events::emit_progress(game_id, game.progress, stage);
         }
     }
 }
 
+/// 记录游戏的资源限制 cgroup（后台管线专用）
+fn set_game_cgroup(game_id: &str, cgroup: Option<cgroup::GameCgroup>) {
+    let mut manager = lock_manager().ok();
+    if let Some(game) = manager.as_mut().and_then(|m| m.processes.get_mut(game_id)) {
+        game.cgroup = cgroup;
+    }
+}
+
 /// 标记游戏启动失败（后台管线专用）
 fn set_game_failed(game_id: &str, error: &str) {
+    let mut pending = None;
     if let Ok(mut manager) = lock_manager() {
         if let Some(game) = manager.processes.get_mut(game_id) {
             game.status = LaunchStatus::Crashed;
             game.last_error = Some(error.to_string());
-            if game.crash_summary.is_none() {
-                game.crash_summary = analyze_crash(game);
+            if game.crash_report.is_none() {
+                pending = Some(PendingCrash::of(game_id, game));
             }
             game.child_process = None;
+            publish_status(game_id, game);
         }
     }
+    if let Some(crash) = pending {
+        store_crash_report(crash);
+    }
     log_info!("❌ {}: {}", game_id, error);
 }
 
-/// 崩溃原因分析：扫描捕获日志做关键词启发式匹配，并检索 JVM 崩溃报告文件。
-/// 返回可读摘要；无命中时返回 None（前端展示 last_error）。
-fn analyze_crash(game: &RunningGame) -> Option<String> {
-    let logs = log::scan_logs(&game.log_key, MAX_CRASH_SCAN_LINES);
-    let joined = logs
-        .iter()
-        .map(|l| l.text.as_str())
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    if joined.contains("OutOfMemoryError") || joined.contains("Out of memory") {
-        return Some("游戏内存不足 (OutOfMemoryError)，建议在设置中增大分配内存".to_string());
-    }
-    if joined.contains("UnsupportedClassVersionError") {
-        return Some(
-            "Java 版本不匹配 (UnsupportedClassVersionError)，请安装更新的 Java".to_string(),
-        );
-    }
-    if joined.contains("NoClassDefFoundError") {
-        return Some("缺失类或依赖 (NoClassDefFoundError)，可能是模组或游戏文件损坏".to_string());
-    }
-    if joined.contains("A fatal error has been detected by the Java Runtime Environment") {
-        return Some("JVM 发生致命错误，已检索崩溃报告".to_string());
-    }
-
-    let game_dir = PathBuf::from(&game.config.game_dir);
-    if find_by_prefix(&game_dir, "hs_err_pid").is_some() {
-        return Some("检测到 JVM 崩溃报告 (hs_err_pid*.log)，可在游戏目录中查看".to_string());
-    }
-    if game_dir
-        .join("crash-reports")
-        .join("latest-crash.txt")
-        .exists()
-    {
-        return Some("检测到游戏崩溃报告 (crash-reports/latest-crash.txt)".to_string());
+/// 崩溃原因分析：以规则集匹配捕获日志尾部、本次启动后生成的
+/// crash-reports 与 hs_err_pid 报告。无命中时返回 None（前端展示 last_error）。
+fn analyze_crash(crash: &PendingCrash) -> Option<CrashReport> {
+    let logs = log::scan_logs(&crash.log_key, MAX_CRASH_SCAN_LINES)
+        .into_iter()
+        .flat_map(|l| {
+            let mut lines = vec![l.text];
+            lines.extend(l.throwable.iter().flat_map(|t| t.lines().map(str::to_string)));
+            lines
+        })
+        .collect();
+    let rules = crash::load_rules(&crash.crash_rules_path);
+    let inputs = crash::collect_inputs(&crash.game_dir, logs, crash.started_at);
+    let report = crash::with_resource_note(
+        crash::analyze(&rules, &inputs),
+        monitor::pressure_note(&crash.log_key),
+    );
+    if let Some(r) = &report {
+        log_info!("🩺 崩溃诊断 [{}] {}", r.rule_id, r.summary);
     }
-    None
+    report
 }
 
 /// 崩溃扫描的最大日志行数（取尾部）
 const MAX_CRASH_SCAN_LINES: usize = 200;
 
-/// 目录下是否有以指定前缀命名的文件
-fn find_by_prefix(dir: &Path, prefix: &str) -> Option<PathBuf> {
-    let entries = std::fs::read_dir(dir).ok()?;
-    for entry in entries.flatten() {
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.starts_with(prefix) {
-            return Some(entry.path());
-        }
-    }
-    None
+/// 解析指定游戏本次启动后生成的崩溃报告与 JVM 致命错误日志
+pub fn get_crash_details(game_id: &str) -> Result<GameCrashDetails, String> {
+    let (game_dir, started_at) = {
+        let manager = lock_manager()?;
+        let game = manager
+            .processes
+            .get(game_id)
+            .ok_or_else(|| format!("游戏 {} 不存在", game_id))?;
+        (PathBuf::from(&game.config.game_dir), game.started_at)
+    };
+
+    let inputs = crash::collect_inputs(&game_dir, Vec::new(), started_at);
+    Ok(GameCrashDetails {
+        crash_report: inputs
+            .crash_report
+            .map(|(path, text)| report::parse_crash_report(&path.to_string_lossy(), &text)),
+        jvm_fatal: inputs
+            .hs_err
+            .map(|(path, text)| report::parse_hs_err(&path.to_string_lossy(), &text)),
+    })
 }
 
 /// 启动 Minecraft 游戏：立即返回游戏唯一 ID（同一游戏目录可并行启动多次），
@@ -445,6 +714,7 @@ pub fn launch_game(
     dm: DownloadManager,
     gm: GameManager,
 ) -> Result<String, String> {
+    events::attach(ctx.app_handle());
     let mut manager = lock_manager()?;
 
     let config = match config {
@@ -452,6 +722,10 @@ pub fn launch_game(
         None => manager.default_config.clone(),
     };
     let game_id = Uuid::new_v4().to_string();
+    let session = new_session(&game_id, &config, &gm);
+    if let Err(e) = history::append_session(&ctx.launch_history_path(), &session) {
+        log_warn!("写入启动记录失败: {}", e);
+    }
 
     manager.processes.insert(
         game_id.clone(),
@@ -463,10 +737,22 @@ pub fn launch_game(
             progress: 0,
             stage: "正在初始化".to_string(),
             exit_code: None,
-            crash_summary: None,
+            crash_report: None,
             log_key: game_id.clone(),
+            started_at: SystemTime::now(),
+            crash_rules_path: ctx.crash_rules_path(),
+            session,
+            preflight: None,
+            java_selection: None,
+            stopping: false,
+            stop_outcome: None,
+            natives_dir: None,
+            cgroup: None,
         },
     );
+    if let Some(game) = manager.processes.get(&game_id) {
+        publish_status(&game_id, game);
+    }
     drop(manager);
 
     let task_id = game_id.clone();
@@ -474,16 +760,248 @@ pub fn launch_game(
     let task_dm = dm.clone();
     let task_gm = gm.clone();
     tauri::async_runtime::spawn(async move {
-        run_launch_pipeline(&task_id, config, task_ctx, task_dm, task_gm).await;
+        run_launch_pipeline(&task_id, config, task_ctx.clone(), task_dm, task_gm.clone()).await;
+        // 管线任一路径结束（启动失败 / 取消 / 游戏退出）后删除参数文件
+        argfile::remove(&argfile::argfile_dir(&task_ctx), &task_id);
+        finish_session(&task_id, &task_ctx, &task_gm);
+        release_natives(&task_id);
     });
 
     Ok(game_id)
 }
 
-/// 后台启动管线：校验 → 缺失文件下载 → 构建参数 → 启动进程
+/// 生成本次启动的会话记录（加载器信息取自游戏记录）
+fn new_session(game_id: &str, config: &LaunchConfig, gm: &GameManager) -> LaunchSession {
+    let game_name = Path::new(&config.game_dir)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let record = gm.load_record(&game_name);
+    LaunchSession {
+        session_id: game_id.to_string(),
+        game_name,
+        version: config.version.clone(),
+        loader_type: record.as_ref().map(|g| g.loader_type.as_str().to_string()),
+        loader_version: record.and_then(|g| g.loader_version),
+        java_path: config.java_path.clone(),
+        memory_mb: config.memory_mb,
+        username: config.username.clone(),
+        account_type: config.account_type.clone(),
+        started_at: chrono::Utc::now().timestamp(),
+        ended_at: None,
+        status: LaunchStatus::Launching,
+        exit_code: None,
+        crash_summary: None,
+        log_file: None,
+    }
+}
+
+/// 启动管线结束（游戏退出 / 启动失败 / 取消）后补全会话记录，
+/// 追加写入历史并更新游戏的最后游玩时间
+fn finish_session(game_id: &str, ctx: &AppContext, gm: &GameManager) {
+    let _ = refresh_status(Some(game_id));
+    let session = {
+        let Ok(mut manager) = lock_manager() else {
+            return;
+        };
+        let Some(game) = manager.processes.get_mut(game_id) else {
+            return;
+        };
+        let mut session = game.session.clone();
+        session.ended_at = Some(chrono::Utc::now().timestamp());
+        session.status = game.status.clone();
+        session.exit_code = game.exit_code;
+        session.crash_summary = game.crash_report.as_ref().map(|r| r.summary.clone());
+        session
+    };
+
+    if let Err(e) = history::append_session(&ctx.launch_history_path(), &session) {
+        log_warn!("写入启动记录失败: {}", e);
+    }
+    if let Some(mut record) = gm.load_record(&session.game_name) {
+        record.last_played = session.ended_at;
+        if let Err(e) = gm.save_record(&record) {
+            log_warn!("更新最后游玩时间失败: {}", e);
+        }
+    }
+    log_info!(
+        "📝 启动会话结束: {} ({}s, 状态 {:?})",
+        game_id,
+        session.play_seconds(),
+        session.status
+    );
+}
+
+/// 查询启动历史（可按游戏名过滤）
+pub fn get_launch_history(ctx: &AppContext, game_name: Option<&str>) -> LaunchHistory {
+    history::query(&ctx.launch_history_path(), game_name)
+}
+
+/// 检索指定游戏的启动日志（会话按开始时间从新到旧，latest_only 时仅最近一次）
+pub fn search_game_log(
+    ctx: &AppContext,
+    query: &LogSearchQuery,
+) -> Result<LogSearchResult, String> {
+    let mut sessions = history::query(&ctx.launch_history_path(), Some(&query.game_name)).sessions;
+    if query.latest_only {
+        sessions.truncate(1);
+    }
+    let sources = sessions
+        .into_iter()
+        .map(|session| log_search::SearchSource {
+            session_id: session.session_id.clone(),
+            started_at: session.started_at,
+            load: Box::new(move || {
+                log::captured_records(&session.session_id).unwrap_or_else(|| {
+                    session
+                        .log_file
+                        .as_deref()
+                        .map(|f| log::read_log_file(Path::new(f)))
+                        .unwrap_or_default()
+                })
+            }),
+        })
+        .collect();
+    log_search::search(query, sources)
+}
+
+/// 启动前环境检查（不启动游戏，供前端提前展示问题）
+pub fn check_launch(config: Option<LaunchConfig>, ctx: &AppContext) -> Result<DoctorReport, String> {
+    let mut config = match config {
+        Some(c) => c,
+        None => lock_manager()?.default_config.clone(),
+    };
+    if config.java_path == AUTO_JAVA_PATH {
+        config.java_path = select_java(&config, ctx)?.java.path.to_string_lossy().to_string();
+    }
+    apply_auto_memory(&mut config, ctx);
+    Ok(doctor::run(&config, ctx))
+}
+
+/// 启动预览（dry-run）：执行到参数构建为止（选择 Java、定位原生库、构建参数），不启动游戏。
+/// 访问令牌以占位符参与构建，返回时隐藏；指定 script 时写出可运行的启动脚本
+/// （默认 {game_dir}/launch.{ext}），此时原生库解压到游戏目录下的 natives，不受托管清理影响
+pub fn preview_launch(
+    config: Option<LaunchConfig>,
+    script: Option<ScriptKind>,
+    script_path: Option<String>,
+    ctx: &AppContext,
+) -> Result<LaunchPreview, String> {
+    let mut config = match config {
+        Some(c) => c,
+        None => lock_manager()?.default_config.clone(),
+    };
+    if config.java_path == AUTO_JAVA_PATH {
+        config.java_path = select_java(&config, ctx)?.java.path.to_string_lossy().to_string();
+    }
+    apply_auto_memory(&mut config, ctx);
+    let game_dir = PathBuf::from(&config.game_dir);
+
+    let (_, merged) = load_version_json(&config, ctx)?;
+    let jars = natives::collect_native_jars(&merged);
+    let natives_dir = match (config.natives_dir.as_ref().filter(|d| !d.is_empty()), script) {
+        (Some(dir), _) => PathBuf::from(dir),
+        (None, Some(_)) => game_dir.join("natives"),
+        (None, None) => natives::managed_dir(ctx, &jars),
+    };
+    // 纯预览只计算路径、不写磁盘；仅导出脚本时解压（脚本需可独立运行）
+    if script.is_some() {
+        natives::extract_all(&jars, &ctx.libraries_dir(), &natives_dir)?;
+    }
+
+    let has_token = crate::account::manager::get_current_account_token_internal()
+        .ok()
+        .flatten()
+        .is_some();
+    let access_token = has_token.then(|| script::ACCESS_TOKEN_PLACEHOLDER.to_string());
+    let java_major = doctor::detect_java(&config.java_path)
+        .map(|j| j.major_version)
+        .filter(|m| *m != 0);
+    let (main_class, launch_args) =
+        build_launch_args(&config, ctx, access_token, &natives_dir, java_major)?;
+
+    let cmd = game_command(&config, &[], &launch_args);
+    let argv: Vec<String> = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|s| s.to_string_lossy().to_string())
+        .collect();
+    let mut preview = LaunchPreview::new(
+        main_class,
+        config.java_path.clone(),
+        &argv,
+        config.game_dir.clone(),
+        config.env_vars.clone(),
+        natives_dir.to_string_lossy().to_string(),
+    );
+
+    if let Some(kind) = script {
+        let path = script_path
+            .map(PathBuf::from)
+            .unwrap_or_else(|| game_dir.join(format!("launch.{}", kind.extension())));
+        let content = script::render_script(kind, &argv, &config.game_dir, &config.env_vars);
+        std::fs::write(&path, content).map_err(|e| format!("写入启动脚本失败: {}", e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("设置启动脚本权限失败: {}", e))?;
+        }
+        log_info!("已导出启动脚本: {}", path.display());
+        preview.script_path = Some(path.to_string_lossy().to_string());
+    }
+
+    Ok(preview)
+}
+
+/// 自动内存模式：按物理内存、当前占用、加载器（取自版本 JSON）与 mods 下的模组数量计算 memory_mb
+fn apply_auto_memory(config: &mut LaunchConfig, ctx: &AppContext) {
+    if !config.auto_memory {
+        return;
+    }
+    let loader = load_version_json(config, ctx)
+        .ok()
+        .and_then(|(_, merged)| doctor::loader_mc_version(&merged).map(|(loader, _)| loader));
+    let mods = memory::count_mods(Path::new(&config.game_dir));
+    let (used_mb, total_mb) = crate::system::get_memory_usage();
+    config.memory_mb = memory::auto_heap_mb(total_mb, used_mb, loader, mods);
+    log_info!(
+        "🧮 自动内存: {}MB（加载器 {}，模组 {} 个，物理内存 {}MB，已用 {}MB）",
+        config.memory_mb,
+        loader.unwrap_or("无"),
+        mods,
+        total_mb,
+        used_mb
+    );
+}
+
+/// 按版本 JSON 的 javaVersion.majorVersion 从已安装的 Java 中自动选择
+fn select_java(config: &LaunchConfig, ctx: &AppContext) -> Result<JavaSelection, String> {
+    let required_major = load_version_json(config, ctx)
+        .ok()
+        .and_then(|(_, merged)| merged["javaVersion"]["majorVersion"].as_u64())
+        .map(|v| v as u32)
+        .unwrap_or(LEGACY_JAVA_MAJOR);
+    let installs = crate::java::scan_java_cached(ctx).unwrap_or_default();
+    let java = crate::java::select_java(&installs, required_major, config.prefer_jdk)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "未找到满足要求的 Java {}（已扫描到 {} 个 Java），请安装 Java {} 或手动指定 Java 路径",
+                required_major,
+                installs.len(),
+                required_major
+            )
+        })?;
+    Ok(JavaSelection {
+        java,
+        required_major,
+    })
+}
+
+/// 后台启动管线：选择 Java → 环境检查 → 校验 → 缺失文件下载 → 构建参数 → 启动进程
 async fn run_launch_pipeline(
     game_id: &str,
-    config: LaunchConfig,
+    mut config: LaunchConfig,
     ctx: AppContext,
     dm: DownloadManager,
     gm: GameManager,
@@ -503,6 +1021,81 @@ async fn run_launch_pipeline(
     log_info!("  内存: {}MB", config.memory_mb);
     log_info!("  版本: {}", config.version);
     log_info!("  用户名: {}", config.username);
+    if !config.wrapper_command.is_empty() {
+        log_info!("  包装命令: {}", config.wrapper_command.join(" "));
+    }
+    // 只记录变量名：值可能包含令牌等敏感信息
+    if !config.env_vars.is_empty() {
+        let keys: Vec<&str> = config.env_vars.keys().map(String::as_str).collect();
+        log_info!("  环境变量: {}", keys.join(", "));
+    }
+
+    // ====== 自动选择 Java（auto 模式），选择结果写回配置与会话记录 ======
+    if config.java_path == AUTO_JAVA_PATH {
+        set_game_progress(game_id, 2, "正在选择 Java");
+        let selection = match select_java(&config, &ctx) {
+            Ok(s) => s,
+            Err(e) => {
+                set_game_failed(game_id, &e);
+                return;
+            }
+        };
+        config.java_path = selection.java.path.to_string_lossy().to_string();
+        log_info!(
+            "☕ 自动选择 Java {}（{}，{}，要求 Java {}）: {}",
+            selection.java.version,
+            selection.java.vendor,
+            if selection.java.is_jdk { "JDK" } else { "JRE" },
+            selection.required_major,
+            config.java_path
+        );
+        if let Some(game) = lock_manager()
+            .ok()
+            .as_mut()
+            .and_then(|m| m.processes.get_mut(game_id))
+        {
+            game.config.java_path = config.java_path.clone();
+            game.session.java_path = config.java_path.clone();
+            game.java_selection = Some(selection);
+        }
+    }
+
+    // ====== 自动内存（按物理内存、当前占用、加载器与模组数量计算），结果写回配置与会话记录 ======
+    if config.auto_memory {
+        apply_auto_memory(&mut config, &ctx);
+        if let Some(game) = lock_manager()
+            .ok()
+            .as_mut()
+            .and_then(|m| m.processes.get_mut(game_id))
+        {
+            game.config.memory_mb = config.memory_mb;
+            game.session.memory_mb = config.memory_mb;
+        }
+    }
+
+    // ====== 阶段 0: 启动前环境检查（错误直接终止，警告随状态返回前端） ======
+    set_game_progress(game_id, 5, "正在检查运行环境");
+    let preflight = doctor::run(&config, &ctx);
+    for item in &preflight.issues {
+        log_warn!("启动前检查 [{:?}] {}", item.severity, item.message);
+    }
+    let blocking = preflight.blocking.then(|| preflight.error_summary());
+    let java_major = preflight
+        .java
+        .as_ref()
+        .map(|j| j.major_version)
+        .filter(|m| *m != 0);
+    if let Some(game) = lock_manager()
+        .ok()
+        .as_mut()
+        .and_then(|m| m.processes.get_mut(game_id))
+    {
+        game.preflight = Some(preflight);
+    }
+    if let Some(summary) = blocking {
+        set_game_failed(game_id, &format!("启动前检查未通过: {}", summary));
+        return;
+    }
 
     // ====== 阶段 1: 校验游戏文件（真实 SHA1 校验，10% → 35%） ======
     set_game_progress(game_id, 10, "正在校验游戏文件");
@@ -567,33 +1160,26 @@ async fn run_launch_pipeline(
                 }
             }
 
-            // natives 下载完成后解压到 natives 目录
-            if to_download.iter().any(|(c, _)| c == "native") {
-                set_game_progress(game_id, 85, "正在解压原生库");
-                let natives_dir = ctx.natives_dir();
-                for (category, file) in to_download.iter().filter(|(c, _)| c == "native") {
-                    let _ = category;
-                    let jar_path = ctx.libraries_dir().join(&file.path);
-                    if let Err(e) = extract_jar(
-                        &jar_path,
-                        &natives_dir,
-                        file.extract.as_ref().and_then(|x| x.exclude.as_deref()),
-                    ) {
-                        set_game_failed(game_id, &format!("解压原生库失败: {}", e));
-                        return;
-                    }
-                }
-            }
             set_game_progress(game_id, 85, "文件下载完成");
         }
     }
 
-    // ====== 阶段 3: 构建启动参数（85% → 90%） ======
+    // ====== 阶段 3: 准备原生库目录（按原生库集合哈希隔离，未变化时复用） ======
+    set_game_progress(game_id, 86, "正在准备原生库");
+    let natives_dir = match prepare_natives(game_id, &config, &ctx) {
+        Ok(dir) => dir,
+        Err(e) => {
+            set_game_failed(game_id, &format!("准备原生库失败: {}", e));
+            return;
+        }
+    };
+
+    // ====== 阶段 4: 构建启动参数（85% → 90%） ======
     set_game_progress(game_id, 88, "正在构建启动参数");
     let access_token = crate::account::manager::get_current_account_token_internal()
         .ok()
         .flatten();
-    let (main_class, launch_args) = match build_launch_args(&config, &ctx, access_token) {
+    let (main_class, launch_args) = match build_launch_args(&config, &ctx, access_token, &natives_dir, java_major) {
         Ok(v) => v,
         Err(e) => {
             set_game_failed(game_id, &e);
@@ -601,46 +1187,114 @@ async fn run_launch_pipeline(
         }
     };
 
-    // ====== 阶段 4: 启动 Java 进程并等待游戏窗口出现（90% → 100%） ======
+    // ====== 启动前命令（失败则终止启动） ======
+    if let Some(command) = hook_command(&config.pre_launch_command) {
+        set_game_progress(game_id, 92, "正在执行启动前命令");
+        log_info!("执行启动前命令: {}", command);
+        let env = hooks::hook_env(game_id, &game_name, &config, None);
+        let timeout = Duration::from_secs(config.hook_timeout_secs as u64);
+        match hooks::run_hook(command, &game_dir, &env, timeout, || launch_cancelled(game_id))
+            .await
+        {
+            Ok(output) if output.success => {
+                for line in &output.lines {
+                    log_info!("  [启动前命令] {}", line);
+                }
+            }
+            Ok(output) => {
+                set_game_failed(
+                    game_id,
+                    &format!(
+                        "启动前命令执行失败 (代码 {}):\n{}",
+                        output.code_text(),
+                        output.tail()
+                    ),
+                );
+                return;
+            }
+            Err(e) => {
+                set_game_failed(game_id, &format!("启动前命令执行失败: {}", e));
+                return;
+            }
+        }
+    }
+
+    // ====== 阶段 5: 启动 Java 进程并等待游戏窗口出现（90% → 100%） ======
     set_game_progress(game_id, 95, "正在启动游戏窗口");
 
     // 启动前检查：用户可能已在后台阶段停止该游戏
-    let cancelled = lock_manager()
-        .map(|m| {
-            m.processes
-                .get(game_id)
-                .map(|i| i.status != LaunchStatus::Launching)
-                .unwrap_or(true)
-        })
-        .unwrap_or(true);
-    if cancelled {
+    if launch_cancelled(game_id) {
         log_info!("游戏 {} 已被取消启动", game_id);
         return;
     }
 
-    match Command::new(&config.java_path)
-        .args(&launch_args)
+    // Java 9+ 将 JVM 参数与 classpath 写入参数文件（@argfile），避免命令行过长；写入失败时沿用内联参数
+    let argfile_dir = argfile::argfile_dir(&ctx);
+    let argfile = argfile::apply(&argfile_dir, game_id, java_major, &main_class, &launch_args);
+    let launch_args = match argfile {
+        Ok(Some(args)) => {
+            log_info!("JVM 参数已写入参数文件: {}", args[0]);
+            args
+        }
+        Ok(None) => launch_args,
+        Err(e) => {
+            log_warn!("{}，改用内联启动参数", e);
+            launch_args
+        }
+    };
+
+    // 资源限制：经 systemd-run 在独立 scope 中启动；systemd 用户会话不可用时不限制
+    let scope = config
+        .resource_limits
+        .then(|| cgroup::scope_prefix(game_id, &cgroup::CgroupLimits::from_config(&config)))
+        .and_then(|r| r.map_err(|e| log_warn!("{}，不限制资源占用", e)).ok())
+        .unwrap_or_default();
+    let mut command = game_command(&config, &scope, &launch_args);
+
+    match command
         .current_dir(&game_dir)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .no_console_window()
+        .own_process_group()
         .spawn()
     {
         Ok(mut child) => {
-            log::start_capture(game_id, &mut child);
+            let log_path = log::new_log_path(&ctx.game_logs_dir(&game_name));
+            let capture = log::start_capture(game_id, &mut child, Some(&log_path));
+            let pid = child.id();
+            monitor::start(game_id, pid, config.memory_mb);
             if let Ok(mut manager) = lock_manager() {
                 if let Some(game) = manager.processes.get_mut(game_id) {
                     game.child_process = Some(child);
+                    game.session.log_file = capture.file.map(|p| p.to_string_lossy().to_string());
                 }
             }
             log_info!("✅ Minecraft Java 进程已启动: {}", game_id);
             log_info!("  主类: {}", main_class);
 
+            if !scope.is_empty() {
+                match cgroup::GameCgroup::attach(game_id, pid).await {
+                    Some(cg) => {
+                        log_info!("游戏进程已加入 scope: {}", cg.path().display());
+                        set_game_cgroup(game_id, Some(cg));
+                    }
+                    None => log_warn!("未找到游戏进程的 scope，无法读取资源占用"),
+                }
+            }
+
             // 启动器可见性：游戏窗口真正出现后再隐藏，游戏结束后恢复
             wait_for_game_window(game_id, config.launcher_visible, &app).await;
 
-            // 等待游戏进程结束，结束后重建并恢复启动器窗口
-            wait_until_game_exits(game_id).await;
+            // 等待游戏进程结束（立即完成状态迁移与崩溃分析），结束后重建并恢复启动器窗口
+            wait_until_game_exits(game_id, capture.closed).await;
+            monitor::stop(game_id);
+            // scope 由 systemd 在进程退出后回收（--collect）
+            set_game_cgroup(game_id, None);
+            if let Some(command) = hook_command(&config.post_exit_command) {
+                run_post_exit_hook(game_id, &game_name, &config, command).await;
+            }
+            log::close_capture(game_id);
             if !config.launcher_visible {
                 if let Some(app) = app.clone() {
                     reopen_launcher_window(app);
@@ -648,12 +1302,129 @@ async fn run_launch_pipeline(
             }
         }
         Err(e) => {
-            let error_msg = format!("启动失败: {}", e);
+            let error_msg = match config.wrapper_command.first() {
+                Some(wrapper) => format!("启动失败（包装命令 {}）: {}", wrapper, e),
+                None => format!("启动失败: {}", e),
+            };
             set_game_failed(game_id, &error_msg);
         }
     }
 }
 
+/// 启动是否已被取消（用户在进程启动前停止，或记录已移除）
+fn launch_cancelled(game_id: &str) -> bool {
+    lock_manager()
+        .map(|m| {
+            m.processes
+                .get(game_id)
+                .map(|i| i.status != LaunchStatus::Launching)
+                .unwrap_or(true)
+        })
+        .unwrap_or(true)
+}
+
+/// 非空的钩子命令
+fn hook_command(command: &Option<String>) -> Option<&str> {
+    command.as_deref().filter(|c| !c.trim().is_empty())
+}
+
+/// 游戏退出后执行退出后命令，输出追加到游戏日志；失败只记录警告，不影响游戏状态
+async fn run_post_exit_hook(game_id: &str, game_name: &str, config: &LaunchConfig, command: &str) {
+    let _ = refresh_status(Some(game_id));
+    let exit = {
+        let Ok(manager) = lock_manager() else {
+            return;
+        };
+        let Some(game) = manager.processes.get(game_id) else {
+            return;
+        };
+        hooks::ExitInfo {
+            code: game.exit_code,
+            status: game.status.clone(),
+        }
+    };
+
+    log_info!("执行退出后命令: {}", command);
+    let env = hooks::hook_env(game_id, game_name, config, Some(&exit));
+    let mut lines = vec![format!("[WeCraft] 执行退出后命令: {}", command)];
+    let timeout = Duration::from_secs(config.hook_timeout_secs as u64);
+    match hooks::run_hook(command, Path::new(&config.game_dir), &env, timeout, || false).await {
+        Ok(output) => {
+            let code = output.code_text();
+            if !output.success {
+                log_warn!("退出后命令执行失败 (代码 {}): {}", code, output.tail());
+            }
+            lines.extend(output.lines);
+            lines.push(format!("[WeCraft] 退出后命令结束 (代码 {})", code));
+        }
+        Err(e) => {
+            log_warn!("退出后命令执行失败: {}", e);
+            lines.push(format!("[WeCraft] 退出后命令执行失败: {}", e));
+        }
+    }
+    log::append_lines(game_id, &lines);
+}
+
+/// 构建游戏进程命令：依次为资源限制前缀（systemd-run）、包装命令、java 及参数，
+/// 并在继承的环境之上设置附加环境变量
+fn game_command(config: &LaunchConfig, scope: &[String], launch_args: &[String]) -> Command {
+    let mut argv = scope
+        .iter()
+        .chain(&config.wrapper_command)
+        .chain(std::iter::once(&config.java_path));
+    let program = argv.next().unwrap_or(&config.java_path);
+    let mut cmd = Command::new(program);
+    cmd.args(argv).args(launch_args).envs(&config.env_vars);
+    cmd
+}
+
+/// 准备本次启动的原生库目录：指定 natives_dir 时直接解压到该目录（不托管）；
+/// 否则使用按原生库集合哈希隔离的托管目录，先登记到游戏记录再解压，
+/// 保证其他游戏退出清理时不会删除正在使用的目录
+fn prepare_natives(
+    game_id: &str,
+    config: &LaunchConfig,
+    ctx: &AppContext,
+) -> Result<PathBuf, String> {
+    let (_, merged) = load_version_json(config, ctx)?;
+    let jars = natives::collect_native_jars(&merged);
+
+    if let Some(dir) = config.natives_dir.as_ref().filter(|d| !d.is_empty()) {
+        let dir = PathBuf::from(dir);
+        natives::extract_all(&jars, &ctx.libraries_dir(), &dir)?;
+        return Ok(dir);
+    }
+
+    let dir = natives::managed_dir(ctx, &jars);
+    if let Some(game) = lock_manager()?.processes.get_mut(game_id) {
+        game.natives_dir = Some(dir.clone());
+    }
+    natives::prepare(&jars, &ctx.libraries_dir(), &dir)?;
+    Ok(dir)
+}
+
+/// 游戏结束后清理托管的原生库目录（仍有其他启动中 / 运行中的游戏引用时保留）
+fn release_natives(game_id: &str) {
+    let Ok(mut manager) = lock_manager() else {
+        return;
+    };
+    let Some(dir) = manager
+        .processes
+        .get_mut(game_id)
+        .and_then(|g| g.natives_dir.take())
+    else {
+        return;
+    };
+    let in_use = manager.processes.values().any(|g| {
+        g.natives_dir.as_ref() == Some(&dir)
+            && matches!(g.status, LaunchStatus::Launching | LaunchStatus::Running)
+    });
+    // 持锁删除：并行启动的游戏需先登记才会解压，避免删除刚准备好的目录
+    if !in_use {
+        natives::remove(&dir);
+    }
+}
+
 /// 等待游戏窗口出现后置为 Running（每 500ms 检测一次，最长 90 秒兜底）。
 /// 期间用户可停止游戏；进程提前退出视为启动失败。
 async fn wait_for_game_window(game_id: &str, launcher_visible: bool, app: &Option<AppHandle>) {
@@ -678,6 +1449,10 @@ async fn wait_for_game_window(game_id: &str, launcher_visible: bool, app: &Optio
         if let Ok(mut manager) = lock_manager() {
             if let Some(game) = manager.processes.get_mut(game_id) {
                 match game.child_process.as_mut().map(|c| c.try_wait()) {
+                    Some(Ok(Some(_))) if game.stopping => {
+                        log_info!("等待窗口期间游戏 {} 已被停止", game_id);
+                        return;
+                    }
                     Some(Ok(Some(status))) => {
                         game.exit_code = status.code();
                         let msg = format!(
@@ -711,6 +1486,7 @@ async fn wait_for_game_window(game_id: &str, launcher_visible: bool, app: &Optio
                     game.status = LaunchStatus::Running;
                     game.progress = 100;
                     game.stage = "游戏运行中".to_string();
+                    publish_status(game_id, game);
                 }
             }
             log_info!("🎮 Minecraft 窗口已出现，进入运行状态: {}", game_id);
@@ -741,6 +1517,7 @@ async fn wait_for_game_window(game_id: &str, launcher_visible: bool, app: &Optio
                         game.status = LaunchStatus::Running;
                         game.progress = 100;
                         game.stage = "游戏运行中".to_string();
+                        publish_status(game_id, game);
                     }
                 }
                 log_info!("⏱ 等待窗口超时，进程存活，按运行中处理: {}", game_id);
@@ -754,34 +1531,50 @@ async fn wait_for_game_window(game_id: &str, launcher_visible: bool, app: &Optio
     }
 }
 
-/// 等待游戏进程结束（最多 24 小时兜底）。用于"启动器可见性"设置：
-/// 游戏运行期间隐藏启动器窗口，进程退出后由调用方恢复。
-async fn wait_until_game_exits(game_id: &str) {
+/// 等待游戏进程结束（最多 24 小时兜底）。日志管道关闭通常意味着进程已退出，
+/// 因此先等待 `closed`，再以递增间隔确认退出；确认后立即更新状态机
+/// （推送状态事件、崩溃分析），不依赖前端轮询。
+async fn wait_until_game_exits(game_id: &str, closed: oneshot::Receiver<()>) {
     use std::time::{Duration, Instant};
 
     let deadline = Instant::now() + Duration::from_secs(24 * 3600);
+    let _ = tokio::time::timeout(Duration::from_secs(24 * 3600), closed).await;
+    let mut interval = Duration::from_millis(50);
     loop {
-        let ended = if let Ok(mut manager) = lock_manager() {
+        let (ended, crash) = if let Ok(mut manager) = lock_manager() {
             match manager.processes.get_mut(game_id) {
-                // 进程已被取走（用户停止）或记录不存在 → 视为已结束
-                None => true,
-                Some(game) => match game.child_process.as_mut().map(|c| c.try_wait()) {
-                    Some(Ok(Some(_))) => true,
-                    Some(Ok(None)) => false,
-                    Some(Err(_)) => true,
-                    None => true,
-                },
+                // 记录不存在 → 视为已结束
+                None => (true, None),
+                Some(game) => {
+                    let ended = match game.child_process.as_mut().map(|c| c.try_wait()) {
+                        Some(Ok(Some(_))) => true,
+                        Some(Ok(None)) => false,
+                        Some(Err(_)) => true,
+                        // 进程已被取走（用户停止）
+                        None => true,
+                    };
+                    let crash = if ended {
+                        update_process_status(game_id, game).ok().flatten()
+                    } else {
+                        None
+                    };
+                    (ended, crash)
+                }
             }
         } else {
-            true
+            (true, None)
         };
+        if let Some(crash) = crash {
+            store_crash_report(crash);
+        }
         if ended {
             return;
         }
         if Instant::now() >= deadline {
             return;
         }
-        tokio::time::sleep(Duration::from_millis(1000)).await;
+        tokio::time::sleep(interval).await;
+        interval = (interval * 2).min(Duration::from_secs(1));
     }
 }
 
@@ -871,101 +1664,167 @@ async fn build_download_list(
     Ok(result)
 }
 
-/// 停止一个游戏的子进程
-fn stop_one(manager: &mut LaunchManager, game_id: &str) -> Result<String, String> {
-    let Some(game) = manager.processes.get_mut(game_id) else {
-        return Ok(format!("游戏 {} 未在运行", game_id));
-    };
+/// 停止单个游戏：进程未启动则取消启动；否则先请求正常关闭，
+/// 宽限期内未退出（或 force）再强制结束整个进程组
+async fn stop_one(game_id: String, force: bool) -> Result<String, String> {
+    use std::time::{Duration, Instant};
 
-    match game.child_process.take() {
-        Some(mut child) => {
-            if let Err(e) = child.kill() {
-                let error_msg = format!("终止进程失败: {}", e);
-                game.last_error = Some(error_msg.clone());
-                return Err(error_msg);
+    let (pid, grace) = {
+        let mut manager = lock_manager()?;
+        let Some(game) = manager.processes.get_mut(&game_id) else {
+            return Ok(format!("游戏 {} 未在运行", game_id));
+        };
+        let Some(child) = game.child_process.as_ref() else {
+            if game.status != LaunchStatus::Launching {
+                return Ok(format!("游戏 {} 未在运行", game_id));
             }
+            game.status = LaunchStatus::Stopped;
+            game.last_error = Some("启动已取消".to_string());
+            game.stop_outcome = Some(StopOutcome::Cancelled);
+            publish_status(&game_id, game);
+            return Ok(format!("游戏 {} 已取消启动", game_id));
+        };
+        game.stopping = true;
+        game.stage = "正在停止".to_string();
+        (child.id(), game.config.stop_grace_secs)
+    };
 
-            if let Err(e) = child.wait() {
-                let error_msg = format!("等待进程结束失败: {}", e);
-                game.last_error = Some(error_msg.clone());
-                return Err(error_msg);
+    if !force {
+        match stop::request_close(pid) {
+            Ok(()) => {
+                log_info!("请求游戏正常关闭: {} (pid {}，宽限 {}s)", game_id, pid, grace);
+                let deadline = Instant::now() + Duration::from_secs(grace as u64);
+                loop {
+                    if reap_stopped(&game_id)? {
+                        log_info!("✅ Minecraft 已正常关闭: {}", game_id);
+                        return Ok(format!("Minecraft 已正常关闭: {}", game_id));
+                    }
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                log_warn!("游戏 {} 未在 {}s 内退出，强制结束", game_id, grace);
             }
-
-            game.status = LaunchStatus::Stopped;
-            log_info!("✅ Minecraft 已停止: {}", game_id);
-            Ok(format!("Minecraft 已停止: {}", game_id))
+            Err(e) => log_warn!("请求正常关闭失败，改为强制结束: {}", e),
         }
-        None => {
-            game.status = LaunchStatus::Stopped;
-            game.last_error = Some("启动已取消".to_string());
-            Ok(format!("游戏 {} 已取消启动", game_id))
+    }
+
+    let mut manager = lock_manager()?;
+    let Some(game) = manager.processes.get_mut(&game_id) else {
+        return Ok(format!("游戏 {} 未在运行", game_id));
+    };
+    let Some(mut child) = game.child_process.take() else {
+        game.status = LaunchStatus::Stopped;
+        game.stop_outcome.get_or_insert(StopOutcome::Graceful);
+        publish_status(&game_id, game);
+        return Ok(format!("Minecraft 已停止: {}", game_id));
+    };
+    if let Err(e) = stop::kill_tree(pid) {
+        log_warn!("结束进程组失败，仅结束主进程: {}", e);
+    }
+    let _ = child.kill();
+    match child.wait() {
+        Ok(status) => game.exit_code = status.code(),
+        Err(e) => {
+            let error_msg = format!("等待进程结束失败: {}", e);
+            game.last_error = Some(error_msg.clone());
+            return Err(error_msg);
         }
     }
+    game.status = LaunchStatus::Stopped;
+    game.crash_report = None;
+    game.stop_outcome = Some(StopOutcome::Forced);
+    publish_status(&game_id, game);
+    log_info!("✅ Minecraft 已强制停止: {}", game_id);
+    Ok(format!("Minecraft 已强制停止: {}", game_id))
 }
 
-/// 停止 Minecraft 游戏；未指定游戏 ID 时停止全部
-pub fn stop_game(game_id: Option<String>) -> Result<String, String> {
+/// 检查停止中的游戏是否已退出；已退出则回收进程并记为正常关闭
+fn reap_stopped(game_id: &str) -> Result<bool, String> {
     let mut manager = lock_manager()?;
-
-    match game_id {
-        Some(id) => stop_one(&mut manager, &id),
-        None => {
-            let keys: Vec<String> = manager.processes.keys().cloned().collect();
-            if keys.is_empty() {
-                return Ok("Minecraft 未在运行".to_string());
-            }
-            let mut results = Vec::with_capacity(keys.len());
-            for key in keys {
-                results.push(stop_one(&mut manager, &key)?);
-            }
-            Ok(results.join("\n"))
+    let Some(game) = manager.processes.get_mut(game_id) else {
+        return Ok(true);
+    };
+    let exited = match game.child_process.as_mut().map(|c| c.try_wait()) {
+        Some(Ok(Some(status))) => {
+            game.exit_code = status.code();
+            true
         }
+        Some(Ok(None)) => false,
+        // 已被状态轮询回收
+        Some(Err(_)) | None => true,
+    };
+    if exited {
+        game.child_process = None;
+        game.status = LaunchStatus::Stopped;
+        game.crash_report = None;
+        game.stop_outcome = Some(StopOutcome::Graceful);
+        publish_status(game_id, game);
     }
+    Ok(exited)
 }
 
-/// 获取聚合启动状态（全部游戏）
-pub fn get_launch_status() -> Result<LaunchStatus, String> {
-    let mut manager = lock_manager()?;
+/// 停止 Minecraft 游戏；未指定游戏 ID 时并行停止全部。
+/// force 为 true 时跳过正常关闭，直接强制结束
+pub async fn stop_game(game_id: Option<String>, force: bool) -> Result<String, String> {
+    let ids: Vec<String> = match game_id {
+        Some(id) => vec![id],
+        None => lock_manager()?.processes.keys().cloned().collect(),
+    };
+    if ids.is_empty() {
+        return Ok("Minecraft 未在运行".to_string());
+    }
 
-    for game in manager.processes.values_mut() {
-        update_process_status(game)?;
+    let mut set = tokio::task::JoinSet::new();
+    for (idx, id) in ids.into_iter().enumerate() {
+        set.spawn(async move { (idx, stop_one(id, force).await) });
+    }
+    let mut results = Vec::new();
+    while let Some(res) = set.join_next().await {
+        results.push(res.map_err(|e| format!("停止任务失败: {}", e))?);
     }
+    results.sort_by_key(|(idx, _)| *idx);
+    Ok(results
+        .into_iter()
+        .map(|(_, r)| r)
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n"))
+}
+
+/// 获取聚合启动状态（全部游戏）
+pub fn get_launch_status() -> Result<LaunchStatus, String> {
+    refresh_status(None)?;
+    let manager = lock_manager()?;
 
     Ok(aggregate_status(&manager))
 }
 
 /// 获取指定游戏的启动状态与进度（以游戏 ID 标识）
 pub fn get_launch_status_by_key(game_id: &str) -> Result<LaunchStatusInfo, String> {
-    let mut manager = lock_manager()?;
+    refresh_status(Some(game_id))?;
+    let manager = lock_manager()?;
 
-    match manager.processes.get_mut(game_id) {
-        Some(game) => {
-            update_process_status(game)?;
-            Ok(LaunchStatusInfo {
-                status: game.status.clone(),
-                progress: game.progress,
-                stage: game.stage.clone(),
-                last_error: game.last_error.clone(),
-                crash_summary: game.crash_summary.clone(),
-            })
-        }
+    match manager.processes.get(game_id) {
+        Some(game) => Ok(status_info(game)),
         None => Ok(LaunchStatusInfo {
             status: LaunchStatus::Idle,
             progress: 0,
             stage: String::new(),
             last_error: None,
             crash_summary: None,
+            crash_report: None,
+            preflight: None,
+            java_selection: None,
+            stop_outcome: None,
         }),
     }
 }
 
 /// 获取全部运行游戏的快照列表
 pub fn get_launch_games() -> Result<Vec<LaunchGameInfo>, String> {
-    let mut manager = lock_manager()?;
-
-    for game in manager.processes.values_mut() {
-        let _ = update_process_status(game);
-    }
+    let _ = refresh_status(None);
+    let manager = lock_manager()?;
 
     Ok(manager
         .processes
@@ -980,10 +1839,20 @@ pub fn get_launch_games() -> Result<Vec<LaunchGameInfo>, String> {
             last_error: game.last_error.clone(),
             progress: game.progress,
             stage: game.stage.clone(),
+            resources: game
+                .cgroup
+                .as_ref()
+                .filter(|_| game.status == LaunchStatus::Running)
+                .map(|cg| cg.usage()),
         })
         .collect())
 }
 
+/// 获取指定游戏的资源采样序列（RSS / CPU / 线程数 / 运行时长）
+pub fn get_game_resources(game_id: &str) -> ResourceSeries {
+    monitor::get_series(game_id)
+}
+
 /// 获取默认启动配置
 pub fn get_launch_config() -> Result<LaunchConfig, String> {
     let manager = lock_manager()?;
@@ -1011,8 +1880,33 @@ mod tests {
             progress: 0,
             stage: String::new(),
             exit_code: None,
-            crash_summary: None,
+            crash_report: None,
             log_key: String::new(),
+            started_at: SystemTime::now(),
+            crash_rules_path: PathBuf::new(),
+            session: LaunchSession {
+                session_id: String::new(),
+                game_name: String::new(),
+                version: String::new(),
+                loader_type: None,
+                loader_version: None,
+                java_path: String::new(),
+                memory_mb: 0,
+                username: String::new(),
+                account_type: None,
+                started_at: 0,
+                ended_at: None,
+                status: LaunchStatus::Launching,
+                exit_code: None,
+                crash_summary: None,
+                log_file: None,
+            },
+            preflight: None,
+            java_selection: None,
+            stopping: false,
+            stop_outcome: None,
+            natives_dir: None,
+            cgroup: None,
         }
     }
 
@@ -1097,6 +1991,116 @@ mod tests {
         assert_eq!(info.progress, 0);
     }
 
+    #[test]
+    fn game_command_prefixes_wrapper_and_sets_env() {
+        let mut config = LaunchConfig {
+            java_path: "/usr/bin/java".to_string(),
+            ..Default::default()
+        };
+        let args = vec![
+            "-Xmx2048M".to_string(),
+            "net.minecraft.client.main.Main".to_string(),
+        ];
+
+        let cmd = game_command(&config, &[], &args);
+        assert_eq!(cmd.get_program(), "/usr/bin/java");
+        assert_eq!(cmd.get_args().count(), 2);
+
+        config.wrapper_command = vec!["taskset".into(), "-c".into(), "0-3".into()];
+        config
+            .env_vars
+            .insert("MESA_GL_VERSION_OVERRIDE".into(), "4.5".into());
+        let cmd = game_command(&config, &[], &args);
+        assert_eq!(cmd.get_program(), "taskset");
+        let argv: Vec<_> = cmd.get_args().collect();
+        assert_eq!(
+            argv,
+            [
+                "-c",
+                "0-3",
+                "/usr/bin/java",
+                "-Xmx2048M",
+                "net.minecraft.client.main.Main"
+            ]
+        );
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert_eq!(
+            envs,
+            [(
+                std::ffi::OsStr::new("MESA_GL_VERSION_OVERRIDE"),
+                Some(std::ffi::OsStr::new("4.5"))
+            )]
+        );
+
+        // 资源限制前缀位于包装命令之前
+        let scope = vec!["systemd-run".to_string(), "--user".to_string(), "--".to_string()];
+        let cmd = game_command(&config, &scope, &args);
+        assert_eq!(cmd.get_program(), "systemd-run");
+        assert_eq!(cmd.get_args().nth(2).unwrap(), "taskset");
+    }
+
+    #[test]
+    fn release_natives_keeps_dir_shared_with_running_game() {
+        let _ = LAUNCH_MANAGER.set(Mutex::new(LaunchManager::default()));
+        let dir = std::env::temp_dir().join(format!("wecraft-natives-shared-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for id in ["natives-a", "natives-b"] {
+            let mut g = game(LaunchStatus::Running);
+            g.natives_dir = Some(dir.clone());
+            lock_manager().unwrap().processes.insert(id.to_string(), g);
+        }
+
+        lock_manager().unwrap().processes.get_mut("natives-a").unwrap().status =
+            LaunchStatus::Stopped;
+        release_natives("natives-a");
+        assert!(dir.exists(), "仍被运行中的游戏引用");
+
+        lock_manager().unwrap().processes.get_mut("natives-b").unwrap().status =
+            LaunchStatus::Crashed;
+        release_natives("natives-b");
+        assert!(!dir.exists());
+    }
+
+    /// 以独立进程组启动 sh 脚本并登记为运行中的游戏
+    #[cfg(unix)]
+    fn insert_running(id: &str, script: &str, grace: u32) {
+        let _ = LAUNCH_MANAGER.set(Mutex::new(LaunchManager::default()));
+        let child = Command::new("sh")
+            .args(["-c", script])
+            .own_process_group()
+            .spawn()
+            .unwrap();
+        // 等待脚本设置好 trap
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        let mut g = game(LaunchStatus::Running);
+        g.config.stop_grace_secs = grace;
+        g.child_process = Some(child);
+        lock_manager().unwrap().processes.insert(id.to_string(), g);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stop_game_graceful_then_forced() {
+        insert_running("stop-graceful", "sleep 30", 5);
+        stop_game(Some("stop-graceful".to_string()), false).await.unwrap();
+        let info = get_launch_status_by_key("stop-graceful").unwrap();
+        assert_eq!(info.status, LaunchStatus::Stopped);
+        assert_eq!(info.stop_outcome, Some(StopOutcome::Graceful));
+        assert!(info.crash_report.is_none());
+
+        // 忽略 SIGTERM 的进程在宽限期后被强制结束
+        insert_running("stop-forced", "trap '' TERM; sleep 30 & wait", 1);
+        stop_game(Some("stop-forced".to_string()), false).await.unwrap();
+        let info = get_launch_status_by_key("stop-forced").unwrap();
+        assert_eq!(info.status, LaunchStatus::Stopped);
+        assert_eq!(info.stop_outcome, Some(StopOutcome::Forced));
+
+        insert_running("stop-force-flag", "trap '' TERM; sleep 30 & wait", 30);
+        stop_game(Some("stop-force-flag".to_string()), true).await.unwrap();
+        let info = get_launch_status_by_key("stop-force-flag").unwrap();
+        assert_eq!(info.stop_outcome, Some(StopOutcome::Forced));
+    }
+
     #[test]
     fn launch_returns_game_id_immediately() {
         let _ = LAUNCH_MANAGER.set(Mutex::new(LaunchManager::default()));
@@ -0,0 +1,440 @@
+//! 游戏进程资源监控：按游戏 ID 定时采样常驻内存（RSS）、CPU 占用、线程数与运行时长，
+//! 保留最近一段时间序列，供前端绘制图表，并在崩溃诊断中标注退出前的内存压力。
+//!
+//! - Linux: /proc/<pid>/stat，统计整个进程组（包含包装命令派生的 java 进程）
+//! - Windows: GetProcessMemoryInfo + GetProcessTimes，线程数取自 Toolhelp 快照
+//! - macOS / 其它: 暂不支持，不产生采样
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::events;
+
+/// 采样间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 每个游戏保留的采样数（按采样间隔约 10 分钟）
+const MAX_SAMPLES: usize = 300;
+
+/// 崩溃诊断标注内存压力的阈值（RSS / -Xmx）
+const PRESSURE_THRESHOLD: f64 = 0.9;
+
+/// 判断内存压力时参考的最近采样数
+const PRESSURE_WINDOW: usize = 5;
+
+/// 单次资源采样
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ResourceSample {
+    /// 采样时间（Unix 毫秒）
+    pub timestamp: i64,
+    /// 进程已运行时长（秒）
+    pub uptime_secs: u64,
+    /// 常驻内存（字节）
+    pub rss_bytes: u64,
+    /// CPU 占用（百分比，单核满载为 100，多核可超过 100）
+    pub cpu_percent: f64,
+    /// 线程数
+    pub threads: u32,
+}
+
+/// 指定游戏的资源采样序列（暴露给前端）
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ResourceSeries {
+    pub game_id: String,
+    /// 采样的进程 PID（未开始采样为 None）
+    pub pid: Option<u32>,
+    /// 最大堆内存（字节，-Xmx）
+    pub heap_max_bytes: u64,
+    /// 采样间隔（毫秒）
+    pub interval_ms: u64,
+    /// 是否仍在采样（进程退出后为 false）
+    pub active: bool,
+    /// 采样序列（按时间升序，仅保留最近一段）
+    pub samples: Vec<ResourceSample>,
+}
+
+/// 平台采样的原始值
+struct RawSample {
+    rss_bytes: u64,
+    /// 累计 CPU 时间（用户态 + 内核态）
+    cpu_time: Duration,
+    threads: u32,
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::RawSample;
+    use std::time::Duration;
+
+    /// /proc/<pid>/stat 中需要的字段
+    #[derive(Debug, PartialEq, Eq)]
+    pub(super) struct ProcStat {
+        pub state: char,
+        pub pgrp: u32,
+        /// utime + stime（时钟周期）
+        pub ticks: u64,
+        pub threads: u32,
+        /// 常驻内存页数
+        pub rss_pages: u64,
+    }
+
+    /// 解析 stat 内容；进程名（comm）可能含空格与括号，从最后一个 `)` 之后按字段切分
+    pub(super) fn parse_stat(content: &str) -> Option<ProcStat> {
+        let rest = content.rsplit_once(')')?.1;
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        let num = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok());
+        Some(ProcStat {
+            state: fields.first()?.chars().next()?,
+            pgrp: num(2)? as u32,
+            ticks: num(11)? + num(12)?,
+            threads: num(17)? as u32,
+            rss_pages: num(21)?,
+        })
+    }
+
+    fn read_stat(pid: u32) -> Option<ProcStat> {
+        parse_stat(&std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)
+    }
+
+    /// 采样以 pid 为组长的整个进程组（游戏以独立进程组启动）；组长已退出时返回 None
+    pub fn sample(pid: u32) -> Option<RawSample> {
+        let leader = read_stat(pid).filter(|s| s.state != 'Z')?;
+        let mut ticks = leader.ticks;
+        let mut threads = leader.threads;
+        let mut rss_pages = leader.rss_pages;
+        for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+            let Some(other) = entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            if other == pid {
+                continue;
+            }
+            if let Some(stat) = read_stat(other).filter(|s| s.pgrp == pid && s.state != 'Z') {
+                ticks += stat.ticks;
+                threads += stat.threads;
+                rss_pages += stat.rss_pages;
+            }
+        }
+        let clock_ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64;
+        Some(RawSample {
+            rss_bytes: rss_pages * page_size,
+            cpu_time: Duration::from_micros(ticks * 1_000_000 / clock_ticks),
+            threads,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+use linux::sample as sample_process;
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::RawSample;
+    use std::time::Duration;
+    use windows::Win32::{
+        Foundation::{CloseHandle, FILETIME},
+        System::{
+            Diagnostics::ToolHelp::{
+                CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+                TH32CS_SNAPPROCESS,
+            },
+            ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+            Threading::{GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
+        },
+    };
+
+    /// FILETIME（100 纳秒为单位）转为时长
+    fn filetime_duration(ft: &FILETIME) -> Duration {
+        let ticks = ((ft.dwHighDateTime as u64) << 32) | ft.dwLowDateTime as u64;
+        Duration::from_nanos(ticks * 100)
+    }
+
+    /// 进程快照中的线程数（未找到时为 0）
+    fn thread_count(pid: u32) -> u32 {
+        let Ok(snapshot) = (unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }) else {
+            return 0;
+        };
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut threads = 0;
+        let mut found = unsafe { Process32FirstW(snapshot, &mut entry) }.is_ok();
+        while found {
+            if entry.th32ProcessID == pid {
+                threads = entry.cntThreads;
+                break;
+            }
+            found = unsafe { Process32NextW(snapshot, &mut entry) }.is_ok();
+        }
+        let _ = unsafe { CloseHandle(snapshot) };
+        threads
+    }
+
+    pub fn sample(pid: u32) -> Option<RawSample> {
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+        let mut counters = PROCESS_MEMORY_COUNTERS::default();
+        let memory = unsafe {
+            GetProcessMemoryInfo(
+                handle,
+                &mut counters,
+                std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+            )
+        };
+        let mut creation = FILETIME::default();
+        let mut exit = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let times =
+            unsafe { GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) };
+        let _ = unsafe { CloseHandle(handle) };
+        if memory.is_err() || times.is_err() {
+            return None;
+        }
+        Some(RawSample {
+            rss_bytes: counters.WorkingSetSize as u64,
+            cpu_time: filetime_duration(&kernel) + filetime_duration(&user),
+            threads: thread_count(pid),
+        })
+    }
+}
+
+#[cfg(windows)]
+use windows_impl::sample as sample_process;
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn sample_process(_pid: u32) -> Option<RawSample> {
+    None
+}
+
+/// 两次采样之间的 CPU 占用百分比
+fn cpu_percent(prev: Duration, now: Duration, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        return 0.0;
+    }
+    now.saturating_sub(prev).as_secs_f64() / elapsed.as_secs_f64() * 100.0
+}
+
+/// 单个游戏的采样状态
+struct Series {
+    pid: u32,
+    heap_max_bytes: u64,
+    active: bool,
+    samples: VecDeque<ResourceSample>,
+}
+
+/// 全局采样存储：game_id → 序列
+static MONITOR_STORE: OnceCell<Mutex<HashMap<String, Series>>> = OnceCell::new();
+
+fn store() -> &'static Mutex<HashMap<String, Series>> {
+    MONITOR_STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 追加一次采样；采样已停止时返回 false
+fn record(game_id: &str, sample: ResourceSample) -> bool {
+    let Ok(mut store) = store().lock() else {
+        return false;
+    };
+    match store.get_mut(game_id) {
+        Some(series) if series.active => {
+            if series.samples.len() >= MAX_SAMPLES {
+                series.samples.pop_front();
+            }
+            series.samples.push_back(sample);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 开始采样游戏进程（后台线程，进程退出或调用 stop 后结束）
+pub(super) fn start(game_id: &str, pid: u32, heap_max_mb: u32) {
+    if let Ok(mut store) = store().lock() {
+        store.insert(
+            game_id.to_string(),
+            Series {
+                pid,
+                heap_max_bytes: heap_max_mb as u64 * 1024 * 1024,
+                active: true,
+                samples: VecDeque::new(),
+            },
+        );
+    }
+    let game_id = game_id.to_string();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        let mut last: Option<(Instant, Duration)> = None;
+        while let Some(raw) = sample_process(pid) {
+            let now = Instant::now();
+            let sample = ResourceSample {
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                uptime_secs: now.duration_since(started).as_secs(),
+                rss_bytes: raw.rss_bytes,
+                cpu_percent: last
+                    .map(|(at, cpu)| cpu_percent(cpu, raw.cpu_time, now.duration_since(at)))
+                    .unwrap_or(0.0),
+                threads: raw.threads,
+            };
+            last = Some((now, raw.cpu_time));
+            if !record(&game_id, sample.clone()) {
+                return;
+            }
+            events::emit_resource_sample(&game_id, sample);
+            std::thread::sleep(SAMPLE_INTERVAL);
+        }
+        stop(&game_id);
+    });
+}
+
+/// 停止采样（保留已有序列）
+pub(super) fn stop(game_id: &str) {
+    if let Some(series) = store()
+        .lock()
+        .ok()
+        .as_mut()
+        .and_then(|s| s.get_mut(game_id))
+    {
+        series.active = false;
+    }
+}
+
+/// 获取指定游戏的采样序列（未采样过时返回空序列）
+pub fn get_series(game_id: &str) -> ResourceSeries {
+    let mut result = ResourceSeries {
+        game_id: game_id.to_string(),
+        interval_ms: SAMPLE_INTERVAL.as_millis() as u64,
+        ..Default::default()
+    };
+    if let Some(series) = store().lock().ok().as_ref().and_then(|s| s.get(game_id)) {
+        result.pid = Some(series.pid);
+        result.heap_max_bytes = series.heap_max_bytes;
+        result.active = series.active;
+        result.samples = series.samples.iter().cloned().collect();
+    }
+    result
+}
+
+/// 退出前的内存压力：最近几次采样的 RSS 峰值达到 -Xmx 的阈值比例时返回说明
+/// （仅作为已有崩溃报告的附加说明，不单独判定为内存不足）
+pub(super) fn pressure_note(game_id: &str) -> Option<String> {
+    let store = store().lock().ok()?;
+    let series = store.get(game_id)?;
+    if series.heap_max_bytes == 0 {
+        return None;
+    }
+    let peak = series
+        .samples
+        .iter()
+        .rev()
+        .take(PRESSURE_WINDOW)
+        .map(|s| s.rss_bytes)
+        .max()?;
+    let ratio = peak as f64 / series.heap_max_bytes as f64;
+    (ratio >= PRESSURE_THRESHOLD).then(|| {
+        format!(
+            "退出前常驻内存（RSS）达到 -Xmx 的 {:.0}%（{} MB / {} MB）",
+            ratio * 100.0,
+            peak / 1024 / 1024,
+            series.heap_max_bytes / 1024 / 1024
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(rss_mb: u64) -> ResourceSample {
+        ResourceSample {
+            timestamp: 0,
+            uptime_secs: 0,
+            rss_bytes: rss_mb * 1024 * 1024,
+            cpu_percent: 0.0,
+            threads: 1,
+        }
+    }
+
+    fn insert(game_id: &str, heap_max_mb: u64, rss_mb: &[u64]) {
+        store().lock().unwrap().insert(
+            game_id.to_string(),
+            Series {
+                pid: 1,
+                heap_max_bytes: heap_max_mb * 1024 * 1024,
+                active: true,
+                samples: rss_mb.iter().map(|&m| sample(m)).collect(),
+            },
+        );
+    }
+
+    #[test]
+    fn cpu_percent_from_time_deltas() {
+        let secs = Duration::from_secs;
+        assert_eq!(cpu_percent(secs(10), secs(11), secs(2)), 50.0);
+        assert_eq!(cpu_percent(secs(10), secs(14), secs(2)), 200.0);
+        assert_eq!(cpu_percent(secs(10), secs(11), Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn series_is_bounded_and_stops() {
+        insert("monitor-test-bounded", 2048, &[]);
+        for i in 0..MAX_SAMPLES as u64 + 10 {
+            assert!(record("monitor-test-bounded", sample(i)));
+        }
+        let series = get_series("monitor-test-bounded");
+        assert_eq!(series.samples.len(), MAX_SAMPLES);
+        assert_eq!(series.samples[0].rss_bytes, 10 * 1024 * 1024);
+
+        stop("monitor-test-bounded");
+        assert!(!record("monitor-test-bounded", sample(1)));
+        assert!(!get_series("monitor-test-bounded").active);
+        assert!(get_series("monitor-test-missing").samples.is_empty());
+    }
+
+    #[test]
+    fn pressure_note_uses_recent_peak() {
+        insert("monitor-test-high", 4096, &[1000, 3000, 4014, 3900]);
+        let note = pressure_note("monitor-test-high").unwrap();
+        assert!(note.contains("98%"), "{}", note);
+
+        // 早期峰值已移出参考窗口
+        insert(
+            "monitor-test-low",
+            4096,
+            &[4096, 1000, 1000, 1000, 1000, 1000],
+        );
+        assert!(pressure_note("monitor-test-low").is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parses_stat_with_parenthesized_comm() {
+        let stat = "4242 (java (Main) x) S 1 4242 4242 0 -1 4194560 100 0 0 0 \
+                    250 50 0 0 20 0 37 0 12345 5000000000 262144 18446744073709551615";
+        assert_eq!(
+            linux::parse_stat(stat),
+            Some(linux::ProcStat {
+                state: 'S',
+                pgrp: 4242,
+                ticks: 300,
+                threads: 37,
+                rss_pages: 262144,
+            })
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn samples_current_process() {
+        let raw = linux::sample(std::process::id()).unwrap();
+        assert!(raw.rss_bytes > 0);
+        assert!(raw.threads >= 1);
+    }
+}
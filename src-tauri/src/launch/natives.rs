@@ -0,0 +1,458 @@
+//! 原生库目录：按版本所需原生库集合的哈希隔离解压（{root}/natives/{hash}），
+//! 集合不变时复用已解压目录，游戏退出且无其他游戏引用时清理。
+//! 旧版本通过 natives 分类器构件提供原生库（按 extract.exclude 整包解压）；
+//! 1.19+ 的 LWJGL 原生库是 classpath 上的 natives-* 构件，只提取其中的动态库并平铺到目录根。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sha1::{Digest, Sha1};
+use uuid::Uuid;
+
+use crate::app_context::AppContext;
+use crate::download::extract_jar;
+use crate::download::models::Library;
+use crate::download::utils::{get_current_arch, get_native_classifier, rules_allow};
+use crate::launch::args::maven_path_from_name;
+use crate::{log_info, log_warn};
+
+/// 解压完成标记（内容为原生库集合哈希，存在且一致即目录可复用）
+const READY_MARKER: &str = ".wecraft-natives";
+
+/// classpath natives 构件中需要提取的动态库扩展名
+const NATIVE_EXTENSIONS: [&str; 4] = ["so", "dll", "dylib", "jnilib"];
+
+/// 需要解压的原生库 jar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct NativeJar {
+    /// 相对 libraries 目录的路径
+    pub path: String,
+    /// 构件 SHA1（参与目录哈希，缺失时仅按路径区分）
+    pub sha1: Option<String>,
+    /// 解压排除规则（旧版分类器构件，如 META-INF/）
+    pub exclude: Vec<String>,
+    /// classpath 上的 natives-* 构件（1.19+）：只提取动态库并平铺
+    pub classpath: bool,
+}
+
+/// classifier 中可出现的架构后缀（无后缀即 x64），如 natives-windows-arm64
+const NATIVE_ARCH_SUFFIXES: [&str; 3] = ["x86", "arm64", "arm32"];
+
+/// natives-* classifier 对应的 CPU 架构（与 `get_current_arch` 取值一致）
+fn natives_arch(classifier: &str) -> &str {
+    classifier
+        .rsplit_once('-')
+        .map(|(_, suffix)| suffix)
+        .filter(|suffix| NATIVE_ARCH_SUFFIXES.contains(suffix))
+        .unwrap_or("x64")
+}
+
+/// Maven 坐标的 classifier 是否为指定架构的 natives-*（1.19+ 以 classpath 构件提供的原生库）。
+/// 各架构构件只有系统规则、没有架构规则，且动态库同名，必须按后缀筛选，否则平铺时互相覆盖。
+fn is_classpath_natives(name: &str, arch: &str) -> bool {
+    name.split(':')
+        .nth(3)
+        .is_some_and(|c| c.starts_with("natives-") && natives_arch(c) == arch)
+}
+
+/// 从合并后的版本 JSON 收集当前平台需要解压的原生库 jar
+pub(super) fn collect_native_jars(merged: &serde_json::Value) -> Vec<NativeJar> {
+    let mut jars = Vec::new();
+    let Some(libraries) = merged["libraries"].as_array() else {
+        return jars;
+    };
+
+    for lib_json in libraries {
+        let Ok(library) = serde_json::from_value::<Library>(lib_json.clone()) else {
+            continue;
+        };
+        if !rules_allow(&library.rules) {
+            continue;
+        }
+        let downloads = library.downloads.as_ref();
+
+        // 旧版：natives 分类器构件（不在 classpath 上）
+        if let Some((classifier, _)) = get_native_classifier(&library) {
+            let artifact = downloads
+                .and_then(|d| d.classifiers.as_ref())
+                .and_then(|c| c.get(&classifier));
+            let path = artifact
+                .map(|a| a.path.clone())
+                .or_else(|| maven_path_from_name(&format!("{}:{}", library.name, classifier)));
+            if let Some(path) = path {
+                jars.push(NativeJar {
+                    path,
+                    sha1: artifact.and_then(|a| a.sha1.clone()),
+                    exclude: library
+                        .extract
+                        .as_ref()
+                        .and_then(|e| e.exclude.clone())
+                        .unwrap_or_default(),
+                    classpath: false,
+                });
+            }
+        }
+
+        // 1.19+：classifier 为 natives-* 的主构件
+        if is_classpath_natives(&library.name, get_current_arch()) {
+            let artifact = downloads.and_then(|d| d.artifact.as_ref());
+            let path = artifact
+                .map(|a| a.path.clone())
+                .or_else(|| maven_path_from_name(&library.name));
+            if let Some(path) = path {
+                jars.push(NativeJar {
+                    path,
+                    sha1: artifact.and_then(|a| a.sha1.clone()),
+                    exclude: Vec::new(),
+                    classpath: true,
+                });
+            }
+        }
+    }
+
+    jars
+}
+
+/// 原生库集合哈希（与顺序无关，取 SHA1 前 16 位）
+pub(super) fn natives_key(jars: &[NativeJar]) -> String {
+    let mut entries: Vec<String> = jars
+        .iter()
+        .map(|j| format!("{}:{}", j.path, j.sha1.as_deref().unwrap_or("")))
+        .collect();
+    entries.sort();
+    entries.dedup();
+
+    let mut hasher = Sha1::new();
+    for entry in &entries {
+        hasher.update(entry.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+/// 原生库集合对应的托管目录：{root}/natives/{hash}
+pub(super) fn managed_dir(ctx: &AppContext, jars: &[NativeJar]) -> PathBuf {
+    ctx.natives_dir().join(natives_key(jars))
+}
+
+/// 从 classpath natives 构件中提取动态库（平铺到目标目录根，跳过 META-INF）
+fn extract_native_libs(jar_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    let file = fs::File::open(jar_path).map_err(|e| format!("打开 jar 文件失败：{}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("解析 zip 失败：{}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("读取 zip 条目失败：{}", e))?;
+        if entry.is_dir() || entry.name().starts_with("META-INF/") {
+            continue;
+        }
+        let Some(path) = entry.enclosed_name() else {
+            continue;
+        };
+        let is_native = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| NATIVE_EXTENSIONS.contains(&e));
+        let Some(file_name) = path.file_name().filter(|_| is_native) else {
+            continue;
+        };
+
+        let mut outfile = fs::File::create(dest_dir.join(file_name))
+            .map_err(|e| format!("创建文件失败：{}", e))?;
+        std::io::copy(&mut entry, &mut outfile).map_err(|e| format!("复制文件失败：{}", e))?;
+    }
+
+    Ok(())
+}
+
+/// 将全部原生库解压到目标目录
+pub(super) fn extract_all(
+    jars: &[NativeJar],
+    libraries_dir: &Path,
+    dest_dir: &Path,
+) -> Result<(), String> {
+    fs::create_dir_all(dest_dir).map_err(|e| format!("创建 natives 目录失败: {}", e))?;
+    for jar in jars {
+        let jar_path = libraries_dir.join(&jar.path);
+        if !jar_path.is_file() {
+            return Err(format!("原生库缺失: {}", jar_path.display()));
+        }
+        if jar.classpath {
+            extract_native_libs(&jar_path, dest_dir)?;
+        } else {
+            extract_jar(&jar_path, dest_dir, Some(&jar.exclude))?;
+        }
+    }
+    Ok(())
+}
+
+/// 准备托管的原生库目录：已就绪（标记哈希一致）则直接复用，
+/// 否则解压到同级临时目录后重命名，避免并行启动读到半成品
+pub(super) fn prepare(jars: &[NativeJar], libraries_dir: &Path, dir: &Path) -> Result<(), String> {
+    let key = natives_key(jars);
+    let is_ready =
+        |dir: &Path| fs::read_to_string(dir.join(READY_MARKER)).is_ok_and(|k| k.trim() == key);
+    if is_ready(dir) {
+        log_info!("复用原生库目录: {}", dir.display());
+        return Ok(());
+    }
+
+    let parent = dir.parent().ok_or("natives 目录无效")?;
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let staging = parent.join(format!("{}.tmp-{}", name, Uuid::new_v4()));
+
+    let extracted = extract_all(jars, libraries_dir, &staging).and_then(|_| {
+        fs::write(staging.join(READY_MARKER), &key)
+            .map_err(|e| format!("写入 natives 标记失败: {}", e))
+    });
+    if let Err(e) = extracted {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    // 残留的不完整目录（上次解压中断）先移除
+    if dir.exists() && !is_ready(dir) {
+        let _ = fs::remove_dir_all(dir);
+    }
+    match fs::rename(&staging, dir) {
+        Ok(()) => {
+            log_info!("已解压 {} 个原生库到: {}", jars.len(), dir.display());
+            Ok(())
+        }
+        // 并行启动的同版本游戏已先完成解压
+        Err(_) if is_ready(dir) => {
+            let _ = fs::remove_dir_all(&staging);
+            Ok(())
+        }
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            Err(format!("移动 natives 目录失败: {}", e))
+        }
+    }
+}
+
+/// 删除托管的原生库目录（调用方保证已无游戏引用）
+pub(super) fn remove(dir: &Path) {
+    match fs::remove_dir_all(dir) {
+        Ok(()) => log_info!("已清理原生库目录: {}", dir.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log_warn!("清理原生库目录失败 {}: {}", dir.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "wecraft-natives-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_jar(path: &Path, entries: &[(&str, &[u8])]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, data) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn collects_legacy_and_classpath_natives() {
+        let os = crate::download::utils::get_current_os();
+        let modern = match get_current_arch() {
+            "x64" => format!("natives-{}", os),
+            arch => format!("natives-{}-{}", os, arch),
+        };
+        let merged = serde_json::json!({
+            "libraries": [
+                {
+                    "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4",
+                    "natives": { os: format!("natives-{}", os) },
+                    "extract": { "exclude": ["META-INF/"] },
+                    "downloads": {
+                        "classifiers": {
+                            format!("natives-{}", os): {
+                                "url": "https://example.com/platform.jar",
+                                "path": "legacy/platform-natives.jar",
+                                "sha1": "aaaa",
+                                "size": 1
+                            }
+                        }
+                    }
+                },
+                { "name": format!("org.lwjgl:lwjgl:3.3.1:{}", modern) },
+                { "name": "org.lwjgl:lwjgl:3.3.1" }
+            ]
+        });
+
+        let jars = collect_native_jars(&merged);
+        assert_eq!(jars.len(), 2);
+        assert_eq!(jars[0].path, "legacy/platform-natives.jar");
+        assert_eq!(jars[0].exclude, vec!["META-INF/".to_string()]);
+        assert!(!jars[0].classpath);
+        assert_eq!(
+            jars[1].path,
+            format!("org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-{}.jar", modern)
+        );
+        assert!(jars[1].classpath);
+    }
+
+    #[test]
+    fn classpath_natives_match_arch_suffix() {
+        assert!(is_classpath_natives(
+            "org.lwjgl:lwjgl:3.3.1:natives-windows",
+            "x64"
+        ));
+        assert!(!is_classpath_natives(
+            "org.lwjgl:lwjgl:3.3.1:natives-windows",
+            "arm64"
+        ));
+        assert!(is_classpath_natives(
+            "org.lwjgl:lwjgl:3.3.1:natives-windows-x86",
+            "x86"
+        ));
+        assert!(is_classpath_natives(
+            "org.lwjgl:lwjgl:3.3.1:natives-macos-arm64",
+            "arm64"
+        ));
+        assert!(is_classpath_natives(
+            "org.lwjgl:lwjgl:3.3.1:natives-macos-patch",
+            "x64"
+        ));
+        assert!(!is_classpath_natives("org.lwjgl:lwjgl:3.3.1", "x64"));
+    }
+
+    #[test]
+    fn extracts_only_current_arch_natives() {
+        let root = temp_dir("arch");
+        let libraries = root.join("libraries");
+        let arch = get_current_arch();
+        let other = if arch == "arm64" { "x86" } else { "arm64" };
+        let classifier = |arch: &str| match arch {
+            "x64" => "natives-linux".to_string(),
+            _ => format!("natives-linux-{}", arch),
+        };
+        let merged = serde_json::json!({
+            "libraries": [
+                { "name": format!("org.lwjgl:lwjgl:3.3.1:{}", classifier(other)) },
+                { "name": format!("org.lwjgl:lwjgl:3.3.1:{}", classifier(arch)) }
+            ]
+        });
+        for arch in [arch, other] {
+            write_jar(
+                &libraries.join(format!(
+                    "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-{}.jar",
+                    classifier(arch)
+                )),
+                &[(
+                    format!("linux/{}/org/lwjgl/liblwjgl.so", arch).as_str(),
+                    arch.as_bytes(),
+                )],
+            );
+        }
+
+        let jars = collect_native_jars(&merged);
+        assert_eq!(jars.len(), 1);
+        let dir = root.join("natives").join(natives_key(&jars));
+        prepare(&jars, &libraries, &dir).unwrap();
+        assert_eq!(fs::read(dir.join("liblwjgl.so")).unwrap(), arch.as_bytes());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn key_ignores_order_and_tracks_content() {
+        let jar = |path: &str, sha1: &str| NativeJar {
+            path: path.to_string(),
+            sha1: Some(sha1.to_string()),
+            exclude: Vec::new(),
+            classpath: false,
+        };
+        let a = [jar("a.jar", "1"), jar("b.jar", "2")];
+        let b = [jar("b.jar", "2"), jar("a.jar", "1")];
+        assert_eq!(natives_key(&a), natives_key(&b));
+        assert_ne!(
+            natives_key(&a),
+            natives_key(&[jar("a.jar", "1"), jar("b.jar", "3")])
+        );
+    }
+
+    #[test]
+    fn prepare_extracts_once_and_reuses() {
+        let root = temp_dir("prepare");
+        let libraries = root.join("libraries");
+        write_jar(
+            &libraries.join("legacy.jar"),
+            &[("liblegacy.so", b"legacy"), ("META-INF/MANIFEST.MF", b"x")],
+        );
+        write_jar(
+            &libraries.join("modern.jar"),
+            &[
+                ("linux/x64/org/lwjgl/liblwjgl.so", b"modern"),
+                ("linux/x64/org/lwjgl/liblwjgl.so.sha1", b"hash"),
+                ("META-INF/versions/x.dll", b"skip"),
+            ],
+        );
+        let jars = vec![
+            NativeJar {
+                path: "legacy.jar".into(),
+                sha1: None,
+                exclude: vec!["META-INF/".into()],
+                classpath: false,
+            },
+            NativeJar {
+                path: "modern.jar".into(),
+                sha1: None,
+                exclude: Vec::new(),
+                classpath: true,
+            },
+        ];
+        let dir = root.join("natives").join(natives_key(&jars));
+
+        prepare(&jars, &libraries, &dir).unwrap();
+        assert_eq!(fs::read(dir.join("liblegacy.so")).unwrap(), b"legacy");
+        assert_eq!(fs::read(dir.join("liblwjgl.so")).unwrap(), b"modern");
+        assert!(!dir.join("META-INF").exists());
+        assert!(!dir.join("liblwjgl.so.sha1").exists());
+        assert!(!dir.join("x.dll").exists());
+
+        // 已就绪：复用（不会重新解压覆盖）
+        fs::write(dir.join("liblwjgl.so"), b"in use").unwrap();
+        prepare(&jars, &libraries, &dir).unwrap();
+        assert_eq!(fs::read(dir.join("liblwjgl.so")).unwrap(), b"in use");
+        assert_eq!(fs::read_dir(root.join("natives")).unwrap().count(), 1);
+
+        remove(&dir);
+        assert!(!dir.exists());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn prepare_fails_on_missing_jar_without_leftovers() {
+        let root = temp_dir("missing");
+        let jars = vec![NativeJar {
+            path: "missing.jar".into(),
+            sha1: None,
+            exclude: Vec::new(),
+            classpath: true,
+        }];
+        let dir = root.join("natives").join("k");
+        assert!(prepare(&jars, &root.join("libraries"), &dir).is_err());
+        assert_eq!(fs::read_dir(root.join("natives")).unwrap().count(), 0);
+        fs::remove_dir_all(&root).ok();
+    }
+}
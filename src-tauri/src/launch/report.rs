@@ -0,0 +1,485 @@
+//! 崩溃报告解析：Minecraft `crash-reports/*.txt`（原版 / Forge / Fabric）
+//! 与 JVM 致命错误日志 `hs_err_pid*.log` 解析为结构化模型
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 崩溃报告节标题：`-- 标题 --`
+static SECTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-- (.+) --$").unwrap());
+
+/// 可疑模组条目：`名称 (id), Version: 版本`
+static SUSPECTED_MOD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+?) \(([\w\-]+)\)(?:, Version: (\S+))?$").unwrap());
+
+/// hs_err 信号行：`SIGSEGV (0xb) at pc=0x…, pid=…, tid=…`
+static HS_ERR_HEADER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\S+) \((0x[0-9a-fA-F]+)\) at pc=(0x[0-9a-fA-F]+), pid=(\d+), tid=(\d+)").unwrap()
+});
+
+/// 问题帧所在的原生库：`[libxyz.so+0x…]`
+static NATIVE_LIBRARY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[([^\]+]+)(?:\+0x[0-9a-fA-F]+)?\]").unwrap());
+
+/// 当前线程：`JavaThread "名称"`
+static JAVA_THREAD: Lazy<Regex> = Lazy::new(|| Regex::new(r#"JavaThread "([^"]+)""#).unwrap());
+
+/// 崩溃报告中的一节（`-- 标题 --` 至下一节）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CrashSection {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+/// System Details 中的一项（多行值以换行拼接）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CrashDetail {
+    pub key: String,
+    pub value: String,
+}
+
+/// 报告中列出的已加载模组（Forge `Mod List` / Fabric `Fabric Mods`）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CrashModEntry {
+    pub id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// 模组文件名（仅 Forge 提供）
+    pub file: Option<String>,
+}
+
+/// 报告中的疑似模组（`Suspected Mod(s):`）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SuspectedMod {
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Minecraft 崩溃报告
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MinecraftCrashReport {
+    /// 报告文件路径
+    pub file: String,
+    pub time: Option<String>,
+    pub description: Option<String>,
+    /// 异常首行（如 `java.lang.NullPointerException: ...`）
+    pub exception: Option<String>,
+    /// 顶部堆栈（含异常首行与 Caused by）
+    pub stack_trace: Vec<String>,
+    pub sections: Vec<CrashSection>,
+    pub suspected_mods: Vec<SuspectedMod>,
+    pub mod_list: Vec<CrashModEntry>,
+    pub system_details: Vec<CrashDetail>,
+}
+
+/// JVM 致命错误日志（hs_err_pid）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JvmFatalReport {
+    /// 日志文件路径
+    pub file: String,
+    /// 信号 / 异常名（SIGSEGV、EXCEPTION_ACCESS_VIOLATION 等）
+    pub signal: Option<String>,
+    pub signal_code: Option<String>,
+    pub pc: Option<String>,
+    pub pid: Option<u32>,
+    pub tid: Option<u32>,
+    /// 错误描述首行（无信号时如 "There is insufficient memory ..."）
+    pub error_message: Option<String>,
+    pub jre_version: Option<String>,
+    pub java_vm: Option<String>,
+    /// 出错栈帧原文（如 `C  [atio6axx.dll+0x1a2b3c]`）
+    pub problematic_frame: Option<String>,
+    /// 栈帧类型：C 原生 / V 虚拟机 / j 解释 / J 编译 / v 桩代码
+    pub frame_type: Option<String>,
+    /// 出错的原生库（如 `atio6axx.dll`、`liblwjgl_opengl.so`）
+    pub native_library: Option<String>,
+    /// 出错时的 Java 线程名
+    pub current_thread: Option<String>,
+    pub command_line: Option<String>,
+}
+
+/// 单次游戏启动对应的崩溃文件解析结果
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct GameCrashDetails {
+    pub crash_report: Option<MinecraftCrashReport>,
+    pub jvm_fatal: Option<JvmFatalReport>,
+}
+
+/// 行首制表符层级
+fn indent_of(line: &str) -> usize {
+    line.chars().take_while(|c| *c == '\t').count()
+}
+
+/// 解析 Minecraft 崩溃报告
+pub fn parse_crash_report(file: &str, text: &str) -> MinecraftCrashReport {
+    let lines: Vec<&str> = text.lines().collect();
+
+    let mut report = MinecraftCrashReport {
+        file: file.to_string(),
+        time: None,
+        description: None,
+        exception: None,
+        stack_trace: Vec::new(),
+        sections: Vec::new(),
+        suspected_mods: Vec::new(),
+        mod_list: Vec::new(),
+        system_details: Vec::new(),
+    };
+
+    // 头部：Time / Description / 顶部堆栈（Description 之后首个空行分隔的块）
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx].trim_end();
+        if SECTION.is_match(line) || line.starts_with("A detailed walkthrough") {
+            break;
+        }
+        if let Some(v) = line.strip_prefix("Time: ") {
+            report.time = Some(v.to_string());
+        } else if let Some(v) = line.strip_prefix("Description: ") {
+            report.description = Some(v.to_string());
+            idx += 1;
+            while idx < lines.len() && lines[idx].trim().is_empty() {
+                idx += 1;
+            }
+            while idx < lines.len() && !lines[idx].trim().is_empty() {
+                report.stack_trace.push(lines[idx].trim_end().to_string());
+                idx += 1;
+            }
+            continue;
+        }
+        idx += 1;
+    }
+    report.exception = report.stack_trace.first().cloned();
+
+    // 分节
+    for line in &lines[idx..] {
+        let line = line.trim_end();
+        if let Some(caps) = SECTION.captures(line) {
+            report.sections.push(CrashSection {
+                title: caps[1].to_string(),
+                lines: Vec::new(),
+            });
+        } else if let Some(section) = report.sections.last_mut() {
+            section.lines.push(line.to_string());
+        }
+    }
+    for section in &mut report.sections {
+        while section.lines.last().is_some_and(|l| l.is_empty()) {
+            section.lines.pop();
+        }
+    }
+
+    if let Some(section) = report.sections.iter().find(|s| s.title == "System Details") {
+        report.system_details = parse_details(&section.lines);
+    }
+    for detail in &report.system_details {
+        match detail.key.as_str() {
+            "Mod List" => report.mod_list.extend(parse_forge_mod_list(&detail.value)),
+            "Fabric Mods" => report.mod_list.extend(parse_fabric_mod_list(&detail.value)),
+            _ => {}
+        }
+    }
+    report.suspected_mods = parse_suspected_mods(&lines);
+    report
+}
+
+/// 解析 `Details:` 下的键值：一级缩进为新键，更深缩进为上一键的续行（保留相对缩进）
+fn parse_details(lines: &[String]) -> Vec<CrashDetail> {
+    let mut details: Vec<CrashDetail> = Vec::new();
+    for line in lines {
+        match indent_of(line) {
+            0 => continue,
+            1 => {
+                let body = line.trim();
+                let (key, value) = match body.split_once(':') {
+                    Some((k, v)) => (k.trim(), v.trim()),
+                    None => (body, ""),
+                };
+                details.push(CrashDetail {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                });
+            }
+            _ => {
+                let Some(last) = details.last_mut() else {
+                    continue;
+                };
+                if !last.value.is_empty() {
+                    last.value.push('\n');
+                }
+                last.value.push_str(&line[2..]);
+            }
+        }
+    }
+    details
+}
+
+/// Forge `Mod List`：`文件 |名称 |id |版本 |状态 |签名`
+fn parse_forge_mod_list(value: &str) -> Vec<CrashModEntry> {
+    value
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split('|').map(|c| c.trim()).collect();
+            if cols.len() < 4 || cols[2].is_empty() {
+                return None;
+            }
+            Some(CrashModEntry {
+                id: cols[2].to_string(),
+                name: Some(cols[1].to_string()).filter(|n| !n.is_empty()),
+                version: Some(cols[3].to_string()).filter(|v| !v.is_empty()),
+                file: Some(cols[0].to_string()).filter(|f| !f.is_empty()),
+            })
+        })
+        .collect()
+}
+
+/// Fabric `Fabric Mods`：`id: 名称 版本`，更深缩进的行（Mixin configs 等）忽略
+fn parse_fabric_mod_list(value: &str) -> Vec<CrashModEntry> {
+    value
+        .lines()
+        .filter(|line| !line.starts_with('\t'))
+        .filter_map(|line| {
+            let (id, rest) = line.split_once(": ")?;
+            let rest = rest.trim();
+            let (name, version) = match rest.rsplit_once(' ') {
+                Some((n, v)) => (n.to_string(), Some(v.to_string())),
+                None => (rest.to_string(), None),
+            };
+            Some(CrashModEntry {
+                id: id.trim().to_string(),
+                name: Some(name),
+                version,
+                file: None,
+            })
+        })
+        .collect()
+}
+
+/// `Suspected Mod(s):` 支持同行与下一级缩进两种写法，`NONE` 视为无
+fn parse_suspected_mods(lines: &[&str]) -> Vec<SuspectedMod> {
+    let mut result: Vec<SuspectedMod> = Vec::new();
+    let push = |text: &str, result: &mut Vec<SuspectedMod>| {
+        let Some(c) = SUSPECTED_MOD.captures(text.trim()) else {
+            return;
+        };
+        if !result.iter().any(|m| m.id == c[2]) {
+            result.push(SuspectedMod {
+                id: c[2].to_string(),
+                name: c[1].to_string(),
+                version: c.get(3).map(|v| v.as_str().to_string()),
+            });
+        }
+    };
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        if !trimmed.starts_with("Suspected Mod") {
+            i += 1;
+            continue;
+        }
+        let inline = trimmed.split_once(':').map(|(_, v)| v.trim()).unwrap_or("");
+        if !inline.is_empty() && !inline.eq_ignore_ascii_case("NONE") {
+            push(inline, &mut result);
+        }
+        let base = indent_of(line);
+        i += 1;
+        while i < lines.len() && !lines[i].trim().is_empty() && indent_of(lines[i]) > base {
+            if indent_of(lines[i]) == base + 1 {
+                push(lines[i], &mut result);
+            }
+            i += 1;
+        }
+    }
+    result
+}
+
+/// 解析 JVM 致命错误日志
+pub fn parse_hs_err(file: &str, text: &str) -> JvmFatalReport {
+    let mut report = JvmFatalReport {
+        file: file.to_string(),
+        signal: None,
+        signal_code: None,
+        pc: None,
+        pid: None,
+        tid: None,
+        error_message: None,
+        jre_version: None,
+        java_vm: None,
+        problematic_frame: None,
+        frame_type: None,
+        native_library: None,
+        current_thread: None,
+        command_line: None,
+    };
+
+    let mut expect_frame = false;
+    for line in text.lines() {
+        let Some(comment) = line.strip_prefix('#') else {
+            if let Some(v) = line.strip_prefix("Command Line: ") {
+                report.command_line = Some(v.trim().to_string());
+            } else if line.starts_with("Current thread") && report.current_thread.is_none() {
+                report.current_thread = JAVA_THREAD.captures(line).map(|c| c[1].to_string());
+            }
+            continue;
+        };
+        let content = comment.trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        if expect_frame {
+            expect_frame = false;
+            report.problematic_frame = Some(content.to_string());
+            report.frame_type = content.split_whitespace().next().map(|t| t.to_string());
+            report.native_library = NATIVE_LIBRARY.captures(content).map(|c| c[1].to_string());
+            continue;
+        }
+        if content == "Problematic frame:" {
+            expect_frame = true;
+        } else if let Some(v) = content.strip_prefix("JRE version: ") {
+            report.jre_version = Some(v.to_string());
+        } else if let Some(v) = content.strip_prefix("Java VM: ") {
+            report.java_vm = Some(v.to_string());
+        } else if let Some(c) = HS_ERR_HEADER.captures(content) {
+            report.signal = Some(c[1].to_string());
+            report.signal_code = Some(c[2].to_string());
+            report.pc = Some(c[3].to_string());
+            report.pid = c[4].parse().ok();
+            report.tid = c[5].parse().ok();
+            report
+                .error_message
+                .get_or_insert_with(|| content.to_string());
+        } else if report.error_message.is_none()
+            && !content.starts_with("A fatal error has been detected")
+        {
+            report.error_message = Some(content.to_string());
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORGE: &str = include_str!("fixtures/crash-forge.txt");
+    const FABRIC: &str = include_str!("fixtures/crash-fabric.txt");
+    const HS_ERR_WINDOWS: &str = include_str!("fixtures/hs_err_pid4312.log");
+    const HS_ERR_LINUX: &str = include_str!("fixtures/hs_err_pid2201.log");
+
+    #[test]
+    fn forge_report_header_and_stack() {
+        let r = parse_crash_report("crash-forge.txt", FORGE);
+        assert_eq!(r.time.as_deref(), Some("2024-03-02 21:14:07"));
+        assert_eq!(r.description.as_deref(), Some("Ticking entity"));
+        assert!(
+            r.exception
+                .as_deref()
+                .unwrap()
+                .starts_with("java.lang.NullPointerException")
+        );
+        assert_eq!(r.stack_trace.len(), 4);
+        let titles: Vec<&str> = r.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Head", "Entity being ticked", "System Details"]);
+    }
+
+    #[test]
+    fn forge_report_mods_and_details() {
+        let r = parse_crash_report("crash-forge.txt", FORGE);
+        assert_eq!(
+            r.suspected_mods,
+            vec![SuspectedMod {
+                id: "create".to_string(),
+                name: "Create".to_string(),
+                version: Some("0.5.1.f".to_string()),
+            }]
+        );
+        assert_eq!(r.mod_list.len(), 4);
+        let jei = r.mod_list.iter().find(|m| m.id == "jei").unwrap();
+        assert_eq!(jei.name.as_deref(), Some("Just Enough Items"));
+        assert_eq!(jei.version.as_deref(), Some("15.2.0.27"));
+        assert_eq!(jei.file.as_deref(), Some("jei-1.20.1-forge-15.2.0.27.jar"));
+
+        let detail = |k: &str| {
+            r.system_details
+                .iter()
+                .find(|d| d.key == k)
+                .map(|d| d.value.as_str())
+        };
+        assert_eq!(detail("Minecraft Version"), Some("1.20.1"));
+        assert_eq!(detail("Java Version"), Some("17.0.8, Microsoft"));
+        assert_eq!(detail("FML"), Some("47.2"));
+    }
+
+    #[test]
+    fn fabric_report_mods_and_caused_by() {
+        let r = parse_crash_report("crash-fabric.txt", FABRIC);
+        assert_eq!(r.description.as_deref(), Some("Initializing game"));
+        assert!(r.stack_trace.iter().any(|l| l.starts_with("Caused by:")));
+        assert!(r.suspected_mods.is_empty());
+
+        let ids: Vec<&str> = r.mod_list.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["fabric-api", "fabricloader", "sodium"]);
+        let sodium = &r.mod_list[2];
+        assert_eq!(sodium.name.as_deref(), Some("Sodium"));
+        assert_eq!(sodium.version.as_deref(), Some("0.5.8+mc1.20.4"));
+        assert!(
+            r.system_details
+                .iter()
+                .any(|d| d.key == "Launched Version" && d.value == "fabric-loader-0.15.7-1.20.4")
+        );
+    }
+
+    #[test]
+    fn inline_suspected_mods_and_none() {
+        let text = "-- Head --\nThread: main\nSuspected Mods: Sodium (sodium), Version: 0.5.8\n\n-- Other --\nSuspected Mods: NONE\n";
+        let r = parse_crash_report("x.txt", text);
+        assert_eq!(r.suspected_mods.len(), 1);
+        assert_eq!(r.suspected_mods[0].id, "sodium");
+    }
+
+    #[test]
+    fn hs_err_windows_native_frame() {
+        let r = parse_hs_err("hs_err_pid4312.log", HS_ERR_WINDOWS);
+        assert_eq!(r.signal.as_deref(), Some("EXCEPTION_ACCESS_VIOLATION"));
+        assert_eq!(r.signal_code.as_deref(), Some("0xc0000005"));
+        assert_eq!(r.pid, Some(4312));
+        assert_eq!(r.tid, Some(9876));
+        assert_eq!(r.frame_type.as_deref(), Some("C"));
+        assert_eq!(r.native_library.as_deref(), Some("atio6axx.dll"));
+        assert_eq!(r.current_thread.as_deref(), Some("Render thread"));
+        assert!(r.jre_version.as_deref().unwrap().contains("17.0.8+7"));
+        assert!(r.command_line.as_deref().unwrap().contains("-Xmx4096M"));
+    }
+
+    #[test]
+    fn hs_err_linux_signal() {
+        let r = parse_hs_err("hs_err_pid2201.log", HS_ERR_LINUX);
+        assert_eq!(r.signal.as_deref(), Some("SIGSEGV"));
+        assert_eq!(r.pc.as_deref(), Some("0x00007f3a5c1d2e3f"));
+        assert_eq!(r.native_library.as_deref(), Some("liblwjgl_opengl.so"));
+        assert!(
+            r.problematic_frame
+                .as_deref()
+                .unwrap()
+                .contains("glDrawElements")
+        );
+        assert!(r.java_vm.as_deref().unwrap().contains("linux-amd64"));
+    }
+
+    #[test]
+    fn hs_err_out_of_memory_has_message_without_signal() {
+        let text = "#\n# There is insufficient memory for the Java Runtime Environment to continue.\n# Native memory allocation (mmap) failed to map 65536 bytes\n";
+        let r = parse_hs_err("hs_err_pid1.log", text);
+        assert!(r.signal.is_none());
+        assert!(
+            r.error_message
+                .as_deref()
+                .unwrap()
+                .starts_with("There is insufficient memory")
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! 启动预览与启动脚本导出：不启动游戏，返回完整启动命令（访问令牌已隐藏），
+//! 并可生成可直接运行的 .sh / .bat / .ps1 脚本（令牌改从 WECRAFT_ACCESS_TOKEN 环境变量读取）。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 构建参数时代替真实访问令牌的占位符（预览中替换为隐藏标记，脚本中替换为环境变量引用）
+pub(super) const ACCESS_TOKEN_PLACEHOLDER: &str = "__WECRAFT_ACCESS_TOKEN__";
+
+/// 预览中显示的令牌隐藏标记
+const REDACTED: &str = "<已隐藏>";
+
+/// 脚本中读取访问令牌的环境变量
+const TOKEN_ENV: &str = "WECRAFT_ACCESS_TOKEN";
+
+/// 脚本头部说明
+const SCRIPT_NOTE: &str =
+    "WeCraft 启动脚本：正版账户请通过 WECRAFT_ACCESS_TOKEN 环境变量传入访问令牌";
+
+/// 启动脚本类型
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptKind {
+    /// POSIX shell（Linux / macOS）
+    Sh,
+    /// Windows 批处理
+    Bat,
+    /// PowerShell
+    Ps1,
+}
+
+impl ScriptKind {
+    /// 脚本文件扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            ScriptKind::Sh => "sh",
+            ScriptKind::Bat => "bat",
+            ScriptKind::Ps1 => "ps1",
+        }
+    }
+}
+
+/// 启动预览（dry-run 结果）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LaunchPreview {
+    /// 主类
+    pub main_class: String,
+    /// 实际使用的 Java（auto 已解析）
+    pub java_path: String,
+    /// 完整命令行（含包装命令与 java，访问令牌已隐藏）
+    pub argv: Vec<String>,
+    /// 工作目录（游戏目录）
+    pub working_dir: String,
+    /// 附加环境变量（在继承的环境之上设置）
+    pub env: BTreeMap<String, String>,
+    /// 原生库目录
+    pub natives_dir: String,
+    /// 已写入的启动脚本路径
+    pub script_path: Option<String>,
+}
+
+impl LaunchPreview {
+    /// 由包含令牌占位符的命令行生成预览（占位符替换为隐藏标记）
+    pub(super) fn new(
+        main_class: String,
+        java_path: String,
+        argv: &[String],
+        working_dir: String,
+        env: BTreeMap<String, String>,
+        natives_dir: String,
+    ) -> Self {
+        Self {
+            main_class,
+            java_path,
+            argv: argv
+                .iter()
+                .map(|a| a.replace(ACCESS_TOKEN_PLACEHOLDER, REDACTED))
+                .collect(),
+            working_dir,
+            env,
+            natives_dir,
+            script_path: None,
+        }
+    }
+}
+
+/// POSIX shell 单引号转义
+fn sh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// shell 参数：令牌占位符处断开单引号，插入环境变量引用（未设置时为 0）
+fn sh_arg(arg: &str) -> String {
+    arg.split(ACCESS_TOKEN_PLACEHOLDER)
+        .map(|part| {
+            if part.is_empty() {
+                String::new()
+            } else {
+                sh_quote(part)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&format!("\"${{{}:-0}}\"", TOKEN_ENV))
+}
+
+/// 批处理参数：双引号包裹（% 写成 %%，" 写成 ""），令牌替换为环境变量引用
+fn bat_arg(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('%', "%%").replace('"', "\"\""))
+        .replace(ACCESS_TOKEN_PLACEHOLDER, &format!("%{}%", TOKEN_ENV))
+}
+
+/// PowerShell 单引号转义
+fn ps1_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// PowerShell 参数：含令牌时改用可展开的双引号字符串（转义 ` " $）
+fn ps1_arg(arg: &str) -> String {
+    if !arg.contains(ACCESS_TOKEN_PLACEHOLDER) {
+        return ps1_quote(arg);
+    }
+    let escaped = arg
+        .replace('`', "``")
+        .replace('"', "`\"")
+        .replace('$', "`$")
+        .replace(ACCESS_TOKEN_PLACEHOLDER, &format!("$($env:{})", TOKEN_ENV));
+    format!("\"{}\"", escaped)
+}
+
+/// 生成启动脚本内容（argv 为包含令牌占位符的完整命令行）
+pub(super) fn render_script(
+    kind: ScriptKind,
+    argv: &[String],
+    working_dir: &str,
+    env: &BTreeMap<String, String>,
+) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    match kind {
+        ScriptKind::Sh => {
+            lines.push("#!/bin/sh".into());
+            lines.push(format!("# {}", SCRIPT_NOTE));
+            lines.push(format!("cd {} || exit 1", sh_quote(working_dir)));
+            for (key, value) in env {
+                lines.push(format!("export {}={}", key, sh_quote(value)));
+            }
+            let args: Vec<String> = argv.iter().map(|a| sh_arg(a)).collect();
+            lines.push(format!("exec {}", args.join(" ")));
+        }
+        ScriptKind::Bat => {
+            lines.push("@echo off".into());
+            lines.push(format!("rem {}", SCRIPT_NOTE));
+            lines.push("setlocal".into());
+            lines.push(format!("if not defined {0} set \"{0}=0\"", TOKEN_ENV));
+            lines.push(format!("cd /d {} || exit /b 1", bat_arg(working_dir)));
+            for (key, value) in env {
+                lines.push(format!("set \"{}={}\"", key, value.replace('%', "%%")));
+            }
+            let args: Vec<String> = argv.iter().map(|a| bat_arg(a)).collect();
+            lines.push(args.join(" "));
+            lines.push("exit /b %ERRORLEVEL%".into());
+        }
+        ScriptKind::Ps1 => {
+            lines.push(format!("# {}", SCRIPT_NOTE));
+            lines.push(format!(
+                "if (-not $env:{0}) {{ $env:{0} = '0' }}",
+                TOKEN_ENV
+            ));
+            lines.push(format!(
+                "Set-Location -LiteralPath {}",
+                ps1_quote(working_dir)
+            ));
+            for (key, value) in env {
+                lines.push(format!("$env:{} = {}", key, ps1_quote(value)));
+            }
+            let args: Vec<String> = argv.iter().map(|a| ps1_arg(a)).collect();
+            lines.push(format!("& {}", args.join(" ")));
+            lines.push("exit $LASTEXITCODE".into());
+        }
+    }
+
+    let newline = if kind == ScriptKind::Sh { "\n" } else { "\r\n" };
+    let mut script = lines.join(newline);
+    script.push_str(newline);
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv() -> Vec<String> {
+        vec![
+            "/opt/java 17/bin/java".into(),
+            "-Dlog='x'".into(),
+            "--accessToken".into(),
+            ACCESS_TOKEN_PLACEHOLDER.into(),
+            "--name=100%".into(),
+        ]
+    }
+
+    #[test]
+    fn preview_redacts_access_token() {
+        let preview = LaunchPreview::new(
+            "Main".into(),
+            "/opt/java".into(),
+            &argv(),
+            "/mc".into(),
+            BTreeMap::new(),
+            "/mc/natives".into(),
+        );
+        assert_eq!(preview.argv[3], REDACTED);
+        assert!(
+            !preview
+                .argv
+                .iter()
+                .any(|a| a.contains(ACCESS_TOKEN_PLACEHOLDER))
+        );
+    }
+
+    #[test]
+    fn sh_script_quotes_and_reads_token_from_env() {
+        let env = BTreeMap::from([("MESA_GL_VERSION_OVERRIDE".to_string(), "4.5".to_string())]);
+        let script = render_script(ScriptKind::Sh, &argv(), "/mc/it's", &env);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("cd '/mc/it'\\''s' || exit 1\n"));
+        assert!(script.contains("export MESA_GL_VERSION_OVERRIDE='4.5'\n"));
+        assert!(script.contains(
+            "exec '/opt/java 17/bin/java' '-Dlog='\\''x'\\''' '--accessToken' \"${WECRAFT_ACCESS_TOKEN:-0}\" '--name=100%'\n"
+        ));
+        assert!(!script.contains(ACCESS_TOKEN_PLACEHOLDER));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sh_script_round_trips_arguments() {
+        let args: Vec<String> = vec![
+            "printf".into(),
+            "%s\\n".into(),
+            "a b".into(),
+            "it's".into(),
+            format!("pre{}post", ACCESS_TOKEN_PLACEHOLDER),
+        ];
+        let script = render_script(ScriptKind::Sh, &args, "/", &BTreeMap::new());
+        let output = std::process::Command::new("sh")
+            .args(["-c", &script])
+            .env(TOKEN_ENV, "tok")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "a b\nit's\npretokpost\n"
+        );
+    }
+
+    #[test]
+    fn bat_and_ps1_scripts_escape_specials() {
+        let bat = render_script(ScriptKind::Bat, &argv(), "C:\\mc", &BTreeMap::new());
+        assert!(
+            bat.contains("if not defined WECRAFT_ACCESS_TOKEN set \"WECRAFT_ACCESS_TOKEN=0\"\r\n")
+        );
+        assert!(bat.contains("\"--accessToken\" \"%WECRAFT_ACCESS_TOKEN%\" \"--name=100%%\"\r\n"));
+
+        let ps1 = render_script(ScriptKind::Ps1, &argv(), "C:\\mc", &BTreeMap::new());
+        assert!(ps1.contains(
+            "& '/opt/java 17/bin/java' '-Dlog=''x''' '--accessToken' \"$($env:WECRAFT_ACCESS_TOKEN)\" '--name=100%'\r\n"
+        ));
+    }
+}
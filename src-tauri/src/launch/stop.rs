@@ -0,0 +1,164 @@
+//! 停止游戏进程：先请求正常关闭（Unix 发送 SIGTERM，Windows 关闭窗口），
+//! 宽限期内未退出再强制结束。游戏以独立进程组启动，信号 / 结束操作作用于
+//! 整个进程组（Windows 为进程树），避免模组派生的辅助进程残留。
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// 停止方式（实际采用的路径，随状态返回前端）
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopOutcome {
+    /// 宽限期内正常退出
+    Graceful,
+    /// 超过宽限期（或要求强制）后强制结束
+    Forced,
+    /// 进程启动前取消
+    Cancelled,
+}
+
+/// 让子进程成为新进程组的组长（进程组 ID = PID），非 Unix 平台为空操作
+pub(super) trait OwnProcessGroup {
+    fn own_process_group(&mut self) -> &mut Self;
+}
+
+#[cfg(unix)]
+impl OwnProcessGroup for Command {
+    fn own_process_group(&mut self) -> &mut Self {
+        use std::os::unix::process::CommandExt;
+        self.process_group(0)
+    }
+}
+
+#[cfg(not(unix))]
+impl OwnProcessGroup for Command {
+    fn own_process_group(&mut self) -> &mut Self {
+        self
+    }
+}
+
+/// 向进程组发送信号（pid 即进程组 ID）
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: i32) -> Result<(), String> {
+    let ret = unsafe { libc::kill(-(pid as libc::pid_t), signal) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "发送信号失败 (pgid {}): {}",
+            pid,
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+/// taskkill 作用于进程树（/T）；不带 /F 时向窗口发送 WM_CLOSE
+#[cfg(windows)]
+fn taskkill(pid: u32, force: bool) -> Result<(), String> {
+    use super::NoConsoleWindow;
+
+    let mut cmd = Command::new("taskkill");
+    cmd.args(["/PID", &pid.to_string(), "/T"]);
+    if force {
+        cmd.arg("/F");
+    }
+    let output = cmd
+        .no_console_window()
+        .output()
+        .map_err(|e| format!("执行 taskkill 失败: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "taskkill 失败 (pid {}): {}",
+            pid,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// 请求游戏正常关闭（保存世界后退出）
+pub(super) fn request_close(pid: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    return signal_group(pid, libc::SIGTERM);
+    #[cfg(windows)]
+    return taskkill(pid, false);
+    #[cfg(not(any(unix, windows)))]
+    return Err(format!("当前平台不支持正常关闭 (pid {})", pid));
+}
+
+/// 强制结束整个进程组 / 进程树
+pub(super) fn kill_tree(pid: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    return signal_group(pid, libc::SIGKILL);
+    #[cfg(windows)]
+    return taskkill(pid, true);
+    #[cfg(not(any(unix, windows)))]
+    return Err(format!("当前平台不支持结束进程树 (pid {})", pid));
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+
+    /// 进程仍存在且不是僵尸进程（容器内 init 可能不及时回收孤儿进程）
+    fn alive(pid: u32) -> bool {
+        let zombie = std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|s| s.rsplit(')').next().map(|r| r.trim_start().starts_with('Z')))
+            .unwrap_or(false);
+        !zombie && unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+    }
+
+    fn wait_exit(child: &mut std::process::Child, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = child.try_wait() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn term_signal_stops_process_group() {
+        let mut child = Command::new("sleep")
+            .arg("30")
+            .own_process_group()
+            .spawn()
+            .unwrap();
+        request_close(child.id()).unwrap();
+        assert!(wait_exit(&mut child, Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn kill_tree_reaps_helpers_ignoring_term() {
+        // 父进程忽略 SIGTERM，并派生一个同组的辅助进程
+        let mut child = Command::new("sh")
+            .args(["-c", "trap '' TERM; sleep 30 & echo $!; wait"])
+            .stdout(Stdio::piped())
+            .own_process_group()
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let helper: u32 = line.trim().parse().unwrap();
+
+        request_close(child.id()).unwrap();
+        assert!(!wait_exit(&mut child, Duration::from_millis(300)));
+
+        kill_tree(child.id()).unwrap();
+        assert!(wait_exit(&mut child, Duration::from_secs(5)));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while alive(helper) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!alive(helper), "辅助进程 {} 仍在运行", helper);
+    }
+}
@@ -35,15 +35,17 @@ pub use crate::account::{
 pub use crate::bootstrap::get_bootstrap_data;
 pub use crate::launch::{
     GameLogResult, LaunchConfig, LaunchGameInfo, LaunchStatus, LaunchStatusInfo,
-    front_get_game_log, front_get_launch_config, front_get_launch_games,
-    front_get_launch_status, front_get_launch_status_by_key, front_launch_game,
+    front_check_launch, front_get_crash_details, front_get_game_log, front_get_game_resources,
+    front_get_launch_config,
+    front_get_launch_games, front_get_launch_history, front_get_launch_status,
+    front_get_launch_status_by_key, front_launch_game, front_preview_launch, front_search_game_log,
     front_stop_game, front_update_launch_config, init_launch_manager,
 };
 pub use crate::window::{load_window_position, save_window_position};
 
 pub use download::{
-    cancel_download, cancel_version_download, clear_completed_tasks, download, get_download_tasks,
-    get_version_detail, get_version_download_manifest, get_version_manifest,
+    cancel_download, cancel_version_download, change_game_loader, clear_completed_tasks, download,
+    get_download_tasks, get_version_detail, get_version_download_manifest, get_version_manifest,
 };
 
 pub use crate::game::{
@@ -59,14 +61,15 @@ pub use crate::system::{
 };
 
 pub use crate::java::{
-    JavaInstallation, get_java_version, scan_java_installations, select_java_path,
+    JavaInstallation, get_java_version, install_java_runtime, scan_java_installations,
+    select_java_path,
 };
 
 pub use crate::modloader::{
     LibraryInfo, ModLoaderInfo, ModLoaderManager, ModLoaderType, ModLoaderVersionItem,
-    ModLoaderVersionList, build_fabric_launch_config, build_forge_launch_config,
-    get_fabric_version_detail, get_fabric_versions, get_forge_versions, get_installed_mod_loaders,
-    get_neoforge_versions, get_optifine_versions,
+    ModLoaderVersionList, build_fabric_launch_config, get_fabric_version_detail,
+    get_fabric_versions, get_forge_versions, get_installed_mod_loaders, get_legacy_fabric_versions,
+    get_neoforge_versions, get_optifine_versions, get_quilt_versions,
 };
 
 pub use logging::{init_logging, log_frontend};
@@ -239,6 +242,12 @@ pub fn run() {
             front_get_launch_config,
             front_update_launch_config,
             front_get_game_log,
+            front_get_game_resources,
+            front_get_crash_details,
+            front_get_launch_history,
+            front_search_game_log,
+            front_check_launch,
+            front_preview_launch,
             log_frontend,
             initialize_account_system,
             get_version_manifest,
@@ -249,14 +258,16 @@ pub fn run() {
             cancel_download,
             cancel_version_download,
             clear_completed_tasks,
+            change_game_loader,
             get_fabric_versions,
             get_fabric_version_detail,
             build_fabric_launch_config,
             get_forge_versions,
-            build_forge_launch_config,
             get_installed_mod_loaders,
             get_neoforge_versions,
             get_optifine_versions,
+            get_quilt_versions,
+            get_legacy_fabric_versions,
             get_disk_free_space,
             scan_games,
             get_game,
@@ -292,6 +303,7 @@ pub fn run() {
             background::select_background_image,
             scan_java_installations,
             get_java_version,
+            install_java_runtime,
             // 字体
             get_system_fonts,
             get_font,
@@ -17,6 +17,10 @@ pub enum ModLoaderType {
     Forge,
     /// NeoForge 加载器
     NeoForge,
+    /// Quilt 加载器（Fabric 分支，元数据接口与 Fabric 一致）
+    Quilt,
+    /// Legacy Fabric 加载器（面向 1.13 以前版本的 Fabric 移植）
+    LegacyFabric,
     /// OptiFine 优化模组
     OptiFine,
 }
@@ -29,6 +33,8 @@ impl ModLoaderType {
             ModLoaderType::Fabric => "fabric",
             ModLoaderType::Forge => "forge",
             ModLoaderType::NeoForge => "neoforge",
+            ModLoaderType::Quilt => "quilt",
+            ModLoaderType::LegacyFabric => "legacyfabric",
             ModLoaderType::OptiFine => "optifine",
         }
     }
@@ -40,10 +46,51 @@ impl ModLoaderType {
             "fabric" => Some(ModLoaderType::Fabric),
             "forge" => Some(ModLoaderType::Forge),
             "neoforge" => Some(ModLoaderType::NeoForge),
+            "quilt" => Some(ModLoaderType::Quilt),
+            "legacyfabric" => Some(ModLoaderType::LegacyFabric),
             "optifine" => Some(ModLoaderType::OptiFine),
             _ => None,
         }
     }
+
+    /// Fabric 系加载器（Fabric / Quilt / Legacy Fabric）的元数据 API 基础 URL；其余加载器返回 None
+    pub fn fabric_meta_base(&self) -> Option<&'static str> {
+        match self {
+            ModLoaderType::Fabric => Some(FABRIC_META_BASE),
+            ModLoaderType::Quilt => Some(QUILT_META_BASE),
+            ModLoaderType::LegacyFabric => Some(LEGACY_FABRIC_META_BASE),
+            _ => None,
+        }
+    }
+
+    /// 从加载器 profile 的依赖库识别加载器类型（Legacy Fabric 同样依赖 fabric-loader，需优先判定）
+    pub fn from_profile(profile: &serde_json::Value) -> Option<Self> {
+        let mut found = None;
+        for lib in profile["libraries"].as_array()? {
+            let Some(name) = lib["name"].as_str() else {
+                continue;
+            };
+            let mut parts = name.split(':');
+            let (Some(group), Some(artifact)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            let loader = match (group, artifact) {
+                ("net.legacyfabric", _) => return Some(ModLoaderType::LegacyFabric),
+                ("org.quiltmc", "quilt-loader") => ModLoaderType::Quilt,
+                ("net.fabricmc", "fabric-loader") => ModLoaderType::Fabric,
+                ("net.neoforged", "neoforge") | ("net.neoforged.fancymodloader", _) => {
+                    ModLoaderType::NeoForge
+                }
+                ("net.minecraftforge", "forge" | "fmlloader") | ("net.neoforged", "forge") => {
+                    ModLoaderType::Forge
+                }
+                ("optifine", "OptiFine") => ModLoaderType::OptiFine,
+                _ => continue,
+            };
+            found.get_or_insert(loader);
+        }
+        found
+    }
 }
 
 /// 库文件信息
@@ -93,6 +140,8 @@ pub struct FabricVersion {
 pub struct FabricLoaderVersion {
     pub version: String,
     pub builds: Option<i32>,
+    /// 是否稳定（Quilt 元数据不提供该字段）
+    pub stable: Option<bool>,
 }
 
 /// Fabric 安装器版本
@@ -191,6 +240,10 @@ pub struct ModLoaderVersionItem {
 
 /// Fabric 元数据 API 基础 URL
 const FABRIC_META_BASE: &str = "https://meta.fabricmc.net/v2";
+/// Quilt 元数据 API 基础 URL
+const QUILT_META_BASE: &str = "https://meta.quiltmc.org/v3";
+/// Legacy Fabric 元数据 API 基础 URL
+const LEGACY_FABRIC_META_BASE: &str = "https://meta.legacyfabric.net/v2";
 
 /// 模组加载器管理器
 pub struct ModLoaderManager {
@@ -214,8 +267,8 @@ impl ModLoaderManager {
 
     /// 获取已安装的模组加载器列表
     ///
-    /// 规则：游戏目录名恰好等于版本号 → 纯净版；
-    /// `{version_id}-fabric` / `{version_id}-forge` → 对应加载器。
+    /// 规则：游戏目录名恰好等于版本号 → 纯净版，目录内继承该版本的加载器 profile 按依赖库识别；
+    /// `{version_id}-{loader}`（如 `-fabric` / `-quilt` / `-legacyfabric`）→ 对应加载器。
     pub fn get_installed_mod_loaders(
         &self,
         version_id: &str,
@@ -224,18 +277,28 @@ impl ModLoaderManager {
 
         let mut loaders = Vec::new();
 
-        if versions_dir.join(version_id).exists() {
+        let game_dir = versions_dir.join(version_id);
+        if game_dir.exists() {
             loaders.push(ModLoaderType::Vanilla);
+            let profile = crate::download::loader::find_loader_profile(&game_dir, version_id)
+                .and_then(|id| std::fs::read_to_string(game_dir.join(format!("{}.json", id))).ok())
+                .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok());
+            if let Some(loader) = profile.as_ref().and_then(ModLoaderType::from_profile) {
+                loaders.push(loader);
+            }
         }
 
-        let fabric_dir = versions_dir.join(format!("{}-fabric", version_id));
-        if fabric_dir.exists() {
-            loaders.push(ModLoaderType::Fabric);
-        }
-
-        let forge_dir = versions_dir.join(format!("{}-forge", version_id));
-        if forge_dir.exists() {
-            loaders.push(ModLoaderType::Forge);
+        for loader in [
+            ModLoaderType::Fabric,
+            ModLoaderType::Quilt,
+            ModLoaderType::LegacyFabric,
+            ModLoaderType::Forge,
+            ModLoaderType::NeoForge,
+        ] {
+            let dir = versions_dir.join(format!("{}-{}", version_id, loader.as_str()));
+            if dir.exists() && !loaders.contains(&loader) {
+                loaders.push(loader);
+            }
         }
 
         Ok(loaders)
@@ -271,37 +334,77 @@ fn get_maven_url_for_fabric_library(name: &str) -> Option<(String, String)> {
 /// 获取指定 Minecraft 版本的 Fabric 加载器版本列表
 #[tauri::command]
 pub async fn get_fabric_versions(mc_version: String) -> Result<ModLoaderVersionList, String> {
-    log_info!("获取 Fabric 版本列表 for MC {}", mc_version);
+    fetch_fabric_like_versions(ModLoaderType::Fabric, mc_version).await
+}
+
+/// 获取指定 Minecraft 版本的 Quilt 加载器版本列表
+#[tauri::command]
+pub async fn get_quilt_versions(mc_version: String) -> Result<ModLoaderVersionList, String> {
+    fetch_fabric_like_versions(ModLoaderType::Quilt, mc_version).await
+}
+
+/// 获取指定 Minecraft 版本的 Legacy Fabric 加载器版本列表
+#[tauri::command]
+pub async fn get_legacy_fabric_versions(
+    mc_version: String,
+) -> Result<ModLoaderVersionList, String> {
+    fetch_fabric_like_versions(ModLoaderType::LegacyFabric, mc_version).await
+}
 
-    let url = format!("{}/v2/versions/loader/{}", FABRIC_META_BASE, mc_version);
+/// 从 Fabric 系元数据拉取加载器版本列表（`/versions/loader/{mc}`）
+async fn fetch_fabric_like_versions(
+    loader_type: ModLoaderType,
+    mc_version: String,
+) -> Result<ModLoaderVersionList, String> {
+    let base = loader_type
+        .fabric_meta_base()
+        .ok_or_else(|| format!("{} 不是 Fabric 系加载器", loader_type.as_str()))?;
+    log_info!(
+        "获取 {} 版本列表 for MC {}",
+        loader_type.as_str(),
+        mc_version
+    );
+
+    let url = format!("{}/versions/loader/{}", base, mc_version);
     let response = reqwest::get(&url)
         .await
-        .map_err(|e| format!("获取 Fabric 版本失败: {}", e))?;
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("获取 {} 版本失败: {}", loader_type.as_str(), e))?;
 
-    let versions: Vec<FabricLoaderVersion> = response
+    let versions: Vec<FabricVersion> = response
         .json()
         .await
-        .map_err(|e| format!("解析 Fabric 版本失败: {}", e))?;
-
-    let items: Vec<ModLoaderVersionItem> = versions
-        .into_iter()
-        .map(|v| ModLoaderVersionItem {
-            version: v.version,
-            stable: v.builds.map(|b| b >= 0).unwrap_or(true),
-            url: None,
-            sha1: None,
-        })
-        .collect();
+        .map_err(|e| format!("解析 {} 版本失败: {}", loader_type.as_str(), e))?;
 
-    log_info!("获取到 {} 个 Fabric 版本", items.len());
+    let items = fabric_like_version_items(versions);
+    log_info!("获取到 {} 个 {} 版本", items.len(), loader_type.as_str());
 
     Ok(ModLoaderVersionList {
-        mod_loader_type: ModLoaderType::Fabric,
+        mod_loader_type: loader_type,
         minecraft_version: mc_version,
         versions: items,
     })
 }
 
+/// 将元数据条目转换为版本项；缺少 stable 字段时以版本号是否含 beta 判定
+fn fabric_like_version_items(versions: Vec<FabricVersion>) -> Vec<ModLoaderVersionItem> {
+    versions
+        .into_iter()
+        .map(|v| {
+            let stable = v
+                .loader
+                .stable
+                .unwrap_or_else(|| !v.loader.version.contains("beta"));
+            ModLoaderVersionItem {
+                version: v.loader.version,
+                stable,
+                url: None,
+                sha1: None,
+            }
+        })
+        .collect()
+}
+
 /// 获取指定 Minecraft 版本和加载器版本的 Fabric 详细信息
 #[tauri::command]
 pub async fn get_fabric_version_detail(
@@ -315,7 +418,7 @@ pub async fn get_fabric_version_detail(
     );
 
     let url = format!(
-        "{}/v2/versions/loader/{}/{}",
+        "{}/versions/loader/{}/{}",
         FABRIC_META_BASE, mc_version, loader_version
     );
     let response = reqwest::get(&url)
@@ -330,6 +433,37 @@ pub async fn get_fabric_version_detail(
     Ok(detail)
 }
 
+/// 获取 Fabric 系加载器的启动 profile（继承原版的版本 JSON，可直接写入游戏目录）
+pub async fn fetch_fabric_profile(
+    loader_type: ModLoaderType,
+    mc_version: &str,
+    loader_version: &str,
+) -> Result<serde_json::Value, String> {
+    let base = loader_type
+        .fabric_meta_base()
+        .ok_or_else(|| format!("{} 不是 Fabric 系加载器", loader_type.as_str()))?;
+    log_info!(
+        "获取 {} profile: MC {} + Loader {}",
+        loader_type.as_str(),
+        mc_version,
+        loader_version
+    );
+
+    let url = format!(
+        "{}/versions/loader/{}/{}/profile/json",
+        base, mc_version, loader_version
+    );
+    let response = reqwest::get(&url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("获取 {} profile 失败: {}", loader_type.as_str(), e))?;
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("解析 {} profile 失败: {}", loader_type.as_str(), e))
+}
+
 /// 构建 Fabric 启动配置（包含库列表和主类信息）
 #[tauri::command]
 pub async fn build_fabric_launch_config(
@@ -428,6 +562,30 @@ pub async fn build_fabric_launch_config(
     })
 }
 
+/// Forge / NeoForge 安装器的 Maven 坐标与下载地址：(坐标, URL)
+pub fn installer_artifact(
+    loader_type: ModLoaderType,
+    mc_version: &str,
+    loader_version: &str,
+) -> Option<(String, String)> {
+    let (coord, repo) = match loader_type {
+        ModLoaderType::Forge => (
+            format!(
+                "net.minecraftforge:forge:{}-{}:installer",
+                mc_version, loader_version
+            ),
+            "https://maven.minecraftforge.net",
+        ),
+        ModLoaderType::NeoForge => (
+            format!("net.neoforged:neoforge:{}:installer", loader_version),
+            "https://maven.neoforged.net/releases",
+        ),
+        _ => return None,
+    };
+    let path = crate::launch::maven_path_from_name(&coord)?;
+    Some((coord, format!("{}/{}", repo, path)))
+}
+
 /// 获取指定 Minecraft 版本的 Forge 加载器版本列表
 #[tauri::command]
 pub async fn get_forge_versions(mc_version: String) -> Result<ModLoaderVersionList, String> {
@@ -476,62 +634,6 @@ pub async fn get_forge_versions(mc_version: String) -> Result<ModLoaderVersionLi
     })
 }
 
-/// 构建 Forge 启动配置（包含库列表和主类信息）
-#[tauri::command]
-pub async fn build_forge_launch_config(
-    mc_version: String,
-    forge_version: String,
-    _game_dir: String,
-    _assets_dir: String,
-    _username: String,
-    _uuid: String,
-    _java_path: String,
-    _memory_mb: u32,
-) -> Result<ModLoaderInfo, String> {
-    log_info!(
-        "构建 Forge 启动配置: MC {} + Forge {}",
-        mc_version,
-        forge_version
-    );
-
-    let full_version = format!("{}-{}", mc_version, forge_version);
-
-    let universal_jar_url = format!(
-        "https://maven.neoforged.net/releases/net/neoforged/forge/{}/{}/forge-{}-{}-universal.jar",
-        full_version, full_version, full_version, mc_version
-    );
-    let universal_jar_path = format!(
-        "maven/neoforged/net/neoforged/forge/{}/{}/forge-{}-{}-universal.jar",
-        full_version, full_version, full_version, mc_version
-    );
-
-    let libraries = vec![LibraryInfo {
-        name: format!("net.neoforged:forge:{}", full_version),
-        url: universal_jar_url,
-        sha1: None,
-        size: 0,
-        path: universal_jar_path,
-    }];
-
-    let main_class = if forge_version.parse::<f64>().unwrap_or(0.0) >= 1.20f64 {
-        "net.neoforged.fml.common.launcher.FMLTweaker".to_string()
-    } else {
-        "cpw.mods.modlauncher.Launcher".to_string()
-    };
-
-    log_info!("Forge 启动配置构建完成: {}", full_version);
-
-    Ok(ModLoaderInfo {
-        version_id: full_version,
-        mod_loader_type: ModLoaderType::Forge,
-        minecraft_version: mc_version,
-        loader_version: Some(forge_version),
-        main_class,
-        libraries,
-        client_jar_required: true,
-    })
-}
-
 /// 获取已安装的模组加载器类型列表
 #[tauri::command]
 pub fn get_installed_mod_loaders(
@@ -600,16 +702,10 @@ pub async fn get_optifine_versions(mc_version: String) -> Result<ModLoaderVersio
 
     #[derive(Deserialize)]
     struct OptiFineEntry {
-        #[serde(rename = "mcversion", default)]
-        mc_version: String,
         #[serde(rename = "patch", default)]
         patch: String,
         #[serde(rename = "type", default)]
         vtype: String,
-        #[serde(rename = "date", default)]
-        date: String,
-        #[serde(rename = "downloadUrl", default)]
-        download_url: String,
     }
 
     let entries: Vec<OptiFineEntry> = response
@@ -617,22 +713,16 @@ pub async fn get_optifine_versions(mc_version: String) -> Result<ModLoaderVersio
         .await
         .map_err(|e| format!("解析 OptiFine 元数据失败: {}", e))?;
 
+    // 版本号使用完整 edition（如 HD_U_I6），即安装与库坐标所用的标识
     let versions: Vec<ModLoaderVersionItem> = entries
         .into_iter()
+        .filter(|e| !e.vtype.is_empty() && !e.patch.is_empty())
         .map(|e| {
-            let version = if e.vtype.is_empty() {
-                e.patch.clone()
-            } else {
-                format!("{} ({})", e.patch, e.vtype)
-            };
+            let edition = format!("{}_{}", e.vtype, e.patch);
             ModLoaderVersionItem {
-                version,
-                stable: e.vtype == "official" || e.vtype.is_empty(),
-                url: if e.download_url.is_empty() {
-                    None
-                } else {
-                    Some(e.download_url)
-                },
+                url: crate::download::optifine::download_url(&mc_version, &edition),
+                stable: !e.patch.contains("pre"),
+                version: edition,
                 sha1: None,
             }
         })
@@ -646,3 +736,59 @@ pub async fn get_optifine_versions(mc_version: String) -> Result<ModLoaderVersio
         versions,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fabric_like_versions_fall_back_to_beta_marker() {
+        let versions: Vec<FabricVersion> = serde_json::from_value(json!([
+            { "loader": { "version": "0.15.7", "build": 1, "stable": true }, "intermediary": {} },
+            { "loader": { "version": "0.26.0-beta.1" } },
+            { "loader": { "version": "0.25.0" } }
+        ]))
+        .unwrap();
+        let stable: Vec<(String, bool)> = fabric_like_version_items(versions)
+            .into_iter()
+            .map(|v| (v.version, v.stable))
+            .collect();
+        assert_eq!(
+            stable,
+            [
+                ("0.15.7".to_string(), true),
+                ("0.26.0-beta.1".to_string(), false),
+                ("0.25.0".to_string(), true)
+            ]
+        );
+    }
+
+    #[test]
+    fn loader_type_from_profile_libraries() {
+        let profile = |names: &[&str]| json!({ "libraries": names.iter().map(|n| json!({ "name": n })).collect::<Vec<_>>() });
+        assert_eq!(
+            ModLoaderType::from_profile(&profile(&["net.fabricmc:fabric-loader:0.15.7"])),
+            Some(ModLoaderType::Fabric)
+        );
+        assert_eq!(
+            ModLoaderType::from_profile(&profile(&[
+                "net.fabricmc:fabric-loader:0.15.7",
+                "net.legacyfabric:intermediary:1.8.9"
+            ])),
+            Some(ModLoaderType::LegacyFabric)
+        );
+        assert_eq!(
+            ModLoaderType::from_profile(&profile(&["org.quiltmc:quilt-loader:0.26.0"])),
+            Some(ModLoaderType::Quilt)
+        );
+        assert_eq!(
+            ModLoaderType::from_profile(&profile(&["org.ow2.asm:asm:9.6"])),
+            None
+        );
+        assert_eq!(
+            ModLoaderType::from_str("LegacyFabric"),
+            Some(ModLoaderType::LegacyFabric)
+        );
+    }
+}
@@ -2,6 +2,8 @@ import {  InvokeOptions } from "@tauri-apps/api/core";
 import { invokeRust } from "./client";
 import { logger } from "@/helper/logger";
 import type { VersionManifest, DownloadTask, DownloadOptions, DownloadResult } from "./types/download";
+import type { Game } from "./types/game";
+import type { ModLoaderType } from "./types/modloader";
 
 /**
  * 获取 Minecraft 版本清单
@@ -78,7 +80,26 @@ export const invokeDownload = async (
     options: {
       ...options,
       loader_version: options.loader_version || null,
+      optifine_version: options.optifine_version || null,
       target_existing_game: options.target_existing_game || null
     }
   }, invokeOptions);
 };
+
+/**
+ * 更换已有游戏的加载器（升级 / 降级 / 切换，Vanilla 表示移除加载器），失败时自动回滚
+ * @param gameName 游戏名称
+ * @param loaderType 新的加载器类型
+ * @param loaderVersion 新的加载器版本（Vanilla 时为 null）
+ * @param options Tauri invoke 选项
+ * @returns 更新后的游戏信息
+ */
+export const invokeChangeGameLoader = async (
+  gameName: string,
+  loaderType: ModLoaderType,
+  loaderVersion: string | null,
+  options?: InvokeOptions
+): Promise<Game> => {
+  logger.info('更换游戏加载器', { gameName, loaderType, loaderVersion });
+  return await invokeRust("change_game_loader", { gameName, loaderType, loaderVersion }, options);
+};
@@ -22,3 +22,16 @@ export const invokeScanJavaInstallations = async (
     throw e;
   }
 };
+
+/**
+ * 下载 Mojang 托管 Java 运行时到启动器 runtime 目录，完成后登记为可用 Java
+ * @param component 运行时组件名（如 java-runtime-gamma、java-runtime-delta、jre-legacy）
+ * @param options Tauri invoke 选项
+ * @returns 安装完成的 Java 信息
+ */
+export const invokeInstallJavaRuntime = async (
+  component: string,
+  options?: InvokeOptions
+): Promise<JavaInstallation> => {
+  return invokeRust('install_java_runtime', { component }, options);
+};
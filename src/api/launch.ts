@@ -1,12 +1,25 @@
 import { InvokeOptions } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { invokeRust } from "./client";
 import { logger } from "@/helper/logger";
 import { LaunchStatus } from "./types/launch";
 import type {
+  DoctorReport,
+  GameCrashDetails,
+  GameLogLinesEvent,
   GameLogResult,
   LaunchConfig,
+  LaunchHistory,
   LaunchGameInfo,
+  LaunchPreview,
+  LaunchProgressEvent,
+  LaunchStatusEvent,
   LaunchStatusInfo,
+  LogSearchQuery,
+  LogSearchResult,
+  GameResourceEvent,
+  ResourceSeries,
+  ScriptKind,
 } from "./types/launch";
 
 /**
@@ -27,17 +40,21 @@ export const invokeLaunchGame = async (
 };
 
 /**
- * 停止 Minecraft 游戏；不传 gameId 时停止全部游戏会话
+ * 停止 Minecraft 游戏；不传 gameId 时停止全部游戏会话。
+ * 默认先请求游戏正常关闭（保存世界），宽限期后仍未退出再强制结束
  * @param gameId 游戏会话唯一 ID，可选
+ * @param force 是否跳过正常关闭直接强制结束
  * @param options Tauri invoke 选项
  */
 export const invokeStopGame = async (
   gameId?: string,
+  force?: boolean,
   options?: InvokeOptions
 ): Promise<string> => {
-  logger.info('停止Minecraft游戏', { gameId });
+  logger.info('停止Minecraft游戏', { gameId, force });
   const result = await invokeRust("front_stop_game", {
     gameId: gameId ?? null,
+    force: force ?? null,
   }, options);
   return result;
 };
@@ -86,9 +103,9 @@ export const invokeGetLaunchGames = async (
 /**
  * 增量拉取指定游戏会话的捕获日志
  * @param gameId 游戏会话唯一 ID
- * @param offset 上次拉取游标（0 表示全量）
+ * @param offset 上次拉取游标（0 表示从头；早于内存缓冲时从日志文件分页读取）
  * @param options Tauri invoke 选项
- * @returns 日志增量 + 新游标
+ * @returns 日志增量 + 新游标（新游标小于最新行时应继续拉取）
  */
 export const invokeGetGameLog = async (
   gameId: string,
@@ -100,4 +117,134 @@ export const invokeGetGameLog = async (
     throw new Error("无效的游戏日志格式");
   }
   return result as GameLogResult;
-};
\ No newline at end of file
+};
+
+/**
+ * 获取指定游戏会话的资源采样序列（约每 2 秒一次，保留最近 10 分钟）
+ * @param gameId 游戏会话唯一 ID
+ * @param options Tauri invoke 选项
+ * @returns RSS / CPU / 线程数 / 运行时长序列（未采样时 samples 为空）
+ */
+export const invokeGetGameResources = async (
+  gameId: string,
+  options?: InvokeOptions
+): Promise<ResourceSeries> => {
+  const result = await invokeRust("front_get_game_resources", { gameId }, options);
+  return result as ResourceSeries;
+};
+
+/**
+ * 解析指定游戏会话本次启动后生成的崩溃报告（crash-reports / hs_err_pid）
+ * @param gameId 游戏会话唯一 ID
+ * @param options Tauri invoke 选项
+ * @returns 崩溃报告与 JVM 致命错误日志的结构化解析结果
+ */
+export const invokeGetCrashDetails = async (
+  gameId: string,
+  options?: InvokeOptions
+): Promise<GameCrashDetails> => {
+  const result = await invokeRust("front_get_crash_details", { gameId }, options);
+  return result as GameCrashDetails;
+};
+
+/**
+ * 查询启动会话历史与总游戏时长
+ * @param gameName 游戏名称，不传时返回全部游戏
+ * @param options Tauri invoke 选项
+ * @returns 会话列表（按开始时间倒序）+ 总时长（秒）
+ */
+export const invokeGetLaunchHistory = async (
+  gameName?: string,
+  options?: InvokeOptions
+): Promise<LaunchHistory> => {
+  const result = await invokeRust("front_get_launch_history", {
+    gameName: gameName ?? null,
+  }, options);
+  return result as LaunchHistory;
+};
+
+/**
+ * 在后端检索游戏日志（等级 / 正则 / 线程 / 记录器 / 时间范围），返回命中与上下文
+ * @param query 检索条件；翻页时传入上次结果的 next_cursor
+ * @param options Tauri invoke 选项
+ * @returns 命中列表 + 下一页游标
+ */
+export const invokeSearchGameLog = async (
+  query: LogSearchQuery,
+  options?: InvokeOptions
+): Promise<LogSearchResult> => {
+  const result = await invokeRust("front_search_game_log", { query }, options);
+  return result as LogSearchResult;
+};
+
+/**
+ * 启动前环境检查（Java 版本、内存、目录可写与剩余空间、路径字符、加载器版本），不启动游戏
+ * @param config 启动配置（不传时检查默认配置）
+ * @param options Tauri invoke 选项
+ * @returns 错误与警告列表
+ */
+export const invokeCheckLaunch = async (
+  config?: LaunchConfig,
+  options?: InvokeOptions
+): Promise<DoctorReport> => {
+  const result = await invokeRust("front_check_launch", { config: config ?? null }, options);
+  return result as DoctorReport;
+};
+
+/**
+ * 启动预览（dry-run）：返回主类、完整命令行、工作目录与环境变量（访问令牌已隐藏），不启动游戏
+ * @param config 启动配置（不传时使用默认配置）
+ * @param script 同时导出的启动脚本类型（正版令牌通过 WECRAFT_ACCESS_TOKEN 环境变量传入）
+ * @param scriptPath 脚本保存路径（不传时写到游戏目录下 launch.{ext}）
+ * @param options Tauri invoke 选项
+ * @returns 启动预览
+ */
+export const invokePreviewLaunch = async (
+  config?: LaunchConfig,
+  script?: ScriptKind,
+  scriptPath?: string,
+  options?: InvokeOptions
+): Promise<LaunchPreview> => {
+  const result = await invokeRust(
+    "front_preview_launch",
+    { config: config ?? null, script: script ?? null, scriptPath: scriptPath ?? null },
+    options
+  );
+  return result as LaunchPreview;
+};
+
+/**
+ * 监听启动进度事件（后台管线阶段推进时推送）
+ * @param handler 事件回调
+ * @returns 取消监听函数
+ */
+export const listenLaunchProgress = (
+  handler: (event: LaunchProgressEvent) => void
+): Promise<UnlistenFn> => listen<LaunchProgressEvent>("launch-progress", (e) => handler(e.payload));
+
+/**
+ * 监听启动状态变化事件（启动中 / 运行中 / 崩溃 / 停止，进程退出时立即推送）
+ * @param handler 事件回调
+ * @returns 取消监听函数
+ */
+export const listenLaunchStatus = (
+  handler: (event: LaunchStatusEvent) => void
+): Promise<UnlistenFn> => listen<LaunchStatusEvent>("launch-status-changed", (e) => handler(e.payload));
+
+/**
+ * 监听游戏日志事件（新增日志按批推送）
+ * @param handler 事件回调
+ * @returns 取消监听函数
+ */
+export const listenGameLogLines = (
+  handler: (event: GameLogLinesEvent) => void
+): Promise<UnlistenFn> => listen<GameLogLinesEvent>("game-log-lines", (e) => handler(e.payload));
+
+/**
+ * 监听游戏资源采样事件（每次采样推送一条）
+ * @param handler 事件回调
+ * @returns 取消监听函数
+ */
+export const listenGameResources = (
+  handler: (event: GameResourceEvent) => void
+): Promise<UnlistenFn> => listen<GameResourceEvent>("game-resource-sample", (e) => handler(e.payload));
@@ -1,7 +1,7 @@
 import { InvokeOptions } from "@tauri-apps/api/core";
 import { invokeRust } from "./client";
 import { logger } from "@/helper/logger";
-import type { ModLoaderVersionList, ModLoaderInfo } from "./types/modloader";
+import type { ModLoaderVersionList } from "./types/modloader";
 
 /**
  * 获取指定 Minecraft 版本的 Fabric 版本列表
@@ -46,52 +46,43 @@ export const invokeGetNeoForgeVersions = async (
 };
 
 /**
- * 获取指定 Minecraft 版本的 OptiFine 版本列表
+ * 获取指定 Minecraft 版本的 Quilt 版本列表
  * @param mcVersion Minecraft 版本号
  * @param options Tauri invoke 选项
- * @returns OptiFine 版本列表
+ * @returns Quilt 版本列表
  */
-export const invokeGetOptifineVersions = async (
+export const invokeGetQuiltVersions = async (
   mcVersion: string,
   options?: InvokeOptions
 ): Promise<ModLoaderVersionList> => {
-  logger.info('获取 OptiFine 版本列表', { mcVersion });
-  return await invokeRust("get_optifine_versions", { mcVersion }, options);
+  logger.info('获取 Quilt 版本列表', { mcVersion });
+  return await invokeRust("get_quilt_versions", { mcVersion }, options);
+};
+
+/**
+ * 获取指定 Minecraft 版本的 Legacy Fabric 版本列表
+ * @param mcVersion Minecraft 版本号
+ * @param options Tauri invoke 选项
+ * @returns Legacy Fabric 版本列表
+ */
+export const invokeGetLegacyFabricVersions = async (
+  mcVersion: string,
+  options?: InvokeOptions
+): Promise<ModLoaderVersionList> => {
+  logger.info('获取 Legacy Fabric 版本列表', { mcVersion });
+  return await invokeRust("get_legacy_fabric_versions", { mcVersion }, options);
 };
 
 /**
- * 构建 Forge 启动配置
+ * 获取指定 Minecraft 版本的 OptiFine 版本列表
  * @param mcVersion Minecraft 版本号
- * @param forgeVersion Forge 版本号
- * @param gameDir 游戏目录
- * @param assetsDir 资源目录
- * @param username 玩家名称
- * @param uuid 玩家 UUID
- * @param javaPath Java 路径（可选）
- * @param memoryMb 分配内存 MB（可选）
  * @param options Tauri invoke 选项
- * @returns 模组加载器信息（含主类、库列表等）
+ * @returns OptiFine 版本列表
  */
-export const invokeBuildForgeLaunchConfig = async (
+export const invokeGetOptifineVersions = async (
   mcVersion: string,
-  forgeVersion: string,
-  gameDir: string,
-  assetsDir: string,
-  username: string,
-  uuid: string,
-  javaPath?: string,
-  memoryMb?: number,
   options?: InvokeOptions
-): Promise<ModLoaderInfo> => {
-  logger.info('构建 Forge 启动配置', { mcVersion, forgeVersion });
-  return await invokeRust("build_forge_launch_config", {
-    mcVersion,
-    forgeVersion,
-    gameDir,
-    assetsDir,
-    username,
-    uuid,
-    javaPath,
-    memoryMb,
-  }, options);
+): Promise<ModLoaderVersionList> => {
+  logger.info('获取 OptiFine 版本列表', { mcVersion });
+  return await invokeRust("get_optifine_versions", { mcVersion }, options);
 };
@@ -102,6 +102,8 @@ export interface DownloadOptions {
   loader_type: string;
   /** 模组加载器版本（可为 null 使用默认） */
   loader_version: string | null;
+  /** 与 Forge / Fabric 组合时作为模组安装的 OptiFine 版本（如 HD_U_I6） */
+  optifine_version?: string | null;
   /** 已存在的目标游戏 ID（覆盖时使用） */
   target_existing_game: string | null;
 }
@@ -1,4 +1,5 @@
 import { ModLoaderType } from "./modloader";
+import type { JvmProfile } from "./launch";
 
 /** 隔离模式（对应 Rust game/models.rs IsolationMode，仅 global/isolated 两值） */
 export enum IsolationMode {
@@ -8,6 +9,17 @@ export enum IsolationMode {
   Isolated = 'isolated',
 }
 
+/** OptiFine 安装方式（对应 Rust game/models.rs OptiFineMode） */
+export type OptiFineMode = 'standalone' | 'mod';
+
+/** 游戏已安装的 OptiFine */
+export interface OptiFineInstall {
+  /** OptiFine 版本（如 HD_U_I6） */
+  version: string;
+  /** 安装方式：独立版本 / 随 Forge、Fabric 作为模组 */
+  mode: OptiFineMode;
+}
+
 /** 游戏设置 */
 export interface GameSettings {
   /** 是否使用游戏级设置（覆盖全局） */
@@ -20,6 +32,10 @@ export interface GameSettings {
   min_memory?: number;
   /** 最大内存（MB） */
   max_memory?: number;
+  /** 自动内存（启动时按物理内存、当前占用、加载器与模组数量计算） */
+  auto_memory?: boolean;
+  /** JVM 调优配置 */
+  jvm_profile?: JvmProfile;
   /** JVM 参数 */
   jvm_args?: string[];
   /** 隔离模式 */
@@ -42,6 +58,20 @@ export interface GameSettings {
   server_address?: string;
   /** 自动连接服务器端口 */
   server_port?: number;
+  /** 启动包装命令（如 gamemoderun / mangohud / prime-run，置于 java 之前） */
+  wrapper_command?: string[];
+  /** 附加环境变量 */
+  env_vars?: Record<string, string>;
+  /** 启动前命令（在游戏目录下经 shell 执行，失败则终止启动；可读取 WECRAFT_* 环境变量） */
+  pre_launch_command?: string;
+  /** 退出后命令（输出追加到游戏日志；可读取 WECRAFT_EXIT_CODE 等环境变量） */
+  post_exit_command?: string;
+  /** 资源限制（仅 Linux cgroups v2：内存上限 / CPU 权重 / 任务数） */
+  resource_limits?: boolean;
+  /** CPU 权重（1-10000，默认 100） */
+  cpu_weight?: number;
+  /** 最大任务（线程）数 */
+  pids_max?: number;
 }
 
 /** 游戏信息 */
@@ -56,6 +86,8 @@ export interface Game {
   loader_type: ModLoaderType;
   /** 模组加载器版本 */
   loader_version: string | null;
+  /** 已安装的 OptiFine */
+  optifine: OptiFineInstall | null;
   /** 游戏路径 */
   path: string;
   /** 图标路径 */
@@ -1,3 +1,5 @@
+import type { JavaInstallation } from './java';
+
 /** 游戏启动状态枚举 */
 export enum LaunchStatus {
   /** 空闲 */
@@ -32,17 +34,45 @@ export interface LaunchGameInfo {
   progress: number;
   /** 当前阶段文案 */
   stage: string;
+  /** 实时资源占用（仅启用资源限制且运行中） */
+  resources: ResourceUsage | null;
+}
+
+/** 游戏 cgroup 的实时资源占用（Linux cgroups v2） */
+export interface ResourceUsage {
+  /** 当前内存占用（字节） */
+  memory_bytes: number;
+  /** 内存占用峰值（字节，内核 5.19+） */
+  memory_peak_bytes: number | null;
+  /** 内存上限（字节，null 为不限制） */
+  memory_max_bytes: number | null;
+  /** 累计 CPU 时间（微秒） */
+  cpu_usage_usec: number;
+  /** CPU 权重 */
+  cpu_weight: number | null;
+  /** 当前任务（线程）数 */
+  pids: number;
+  /** 任务数上限（null 为不限制） */
+  pids_max: number | null;
 }
 
-/** 游戏日志等级 */
-export type GameLogLevel = 'info' | 'warn' | 'error' | 'fatal';
+/** 游戏日志等级（debug 含 TRACE） */
+export type GameLogLevel = 'debug' | 'info' | 'warn' | 'error' | 'fatal';
 
-/** 单条游戏日志行 */
+/** 单条游戏日志（一条 log4j 事件或一行纯文本，堆栈并入 throwable） */
 export interface GameLogLine {
   /** 日志等级 */
   level: GameLogLevel;
-  /** 日志文本 */
+  /** 展示文本（log4j 事件按 `[时间] [线程/等级]: 消息` 渲染） */
   text: string;
+  /** 事件时间（Unix 毫秒，纯文本行为 null） */
+  timestamp: number | null;
+  thread: string | null;
+  logger: string | null;
+  /** 日志消息（不含时间/线程等前缀） */
+  message: string;
+  /** 异常堆栈（多行） */
+  throwable: string | null;
 }
 
 /** 日志增量拉取结果 */
@@ -53,6 +83,59 @@ export interface GameLogResult {
   lines: GameLogLine[];
 }
 
+/** 日志检索游标 */
+export interface LogSearchCursor {
+  session_id: string;
+  index: number;
+}
+
+/** 日志检索条件（除 game_name 外均可省略） */
+export interface LogSearchQuery {
+  /** 游戏名（游戏目录名） */
+  game_name: string;
+  /** 仅检索最近一次启动 */
+  latest_only?: boolean;
+  /** 等级集合（空为全部） */
+  levels?: GameLogLevel[];
+  /** 正则表达式（匹配展示文本与异常堆栈） */
+  pattern?: string | null;
+  /** 正则是否区分大小写 */
+  case_sensitive?: boolean;
+  /** 线程名（包含匹配，不区分大小写） */
+  thread?: string | null;
+  /** 记录器名（包含匹配，不区分大小写） */
+  logger?: string | null;
+  /** 起始时间（Unix 毫秒，含） */
+  since?: number | null;
+  /** 结束时间（Unix 毫秒，含） */
+  until?: number | null;
+  /** 每条命中前后附带的上下文条数（最多 20） */
+  context?: number;
+  /** 本页最多命中条数（默认 200，最多 1000） */
+  limit?: number;
+  /** 上一页返回的游标 */
+  cursor?: LogSearchCursor | null;
+}
+
+/** 单条日志检索命中 */
+export interface LogMatch {
+  session_id: string;
+  /** 记录在会话中的编号（与日志拉取游标一致） */
+  index: number;
+  line: GameLogLine;
+  before: GameLogLine[];
+  after: GameLogLine[];
+}
+
+/** 日志检索结果 */
+export interface LogSearchResult {
+  matches: LogMatch[];
+  /** 下一页游标（null 表示已检索完毕） */
+  next_cursor: LogSearchCursor | null;
+  /** 本次扫描的记录数 */
+  scanned: number;
+}
+
 /** 指定游戏会话的状态 + 进度快照 */
 export interface LaunchStatusInfo {
   /** 启动状态 */
@@ -63,16 +146,236 @@ export interface LaunchStatusInfo {
   stage: string;
   /** 最近一次错误信息 */
   last_error?: string | null;
-  /** 崩溃原因摘要（崩溃时生成） */
+  /** 崩溃原因摘要（崩溃时生成，等同 crash_report.summary） */
   crash_summary?: string | null;
+  /** 结构化崩溃诊断（崩溃时生成） */
+  crash_report?: CrashReport | null;
+  /** 启动前检查结果（检查阶段完成后生成） */
+  preflight?: DoctorReport | null;
+  /** 自动选择的 Java（仅 java_path 为 "auto" 时） */
+  java_selection?: JavaSelection | null;
+  /** 停止方式（用户停止后生成） */
+  stop_outcome?: StopOutcome | null;
+}
+
+/** 停止方式：graceful 宽限期内正常退出，forced 强制结束，cancelled 进程启动前取消 */
+export type StopOutcome = 'graceful' | 'forced' | 'cancelled';
+
+/** 自动选择 Java 的结果 */
+export interface JavaSelection {
+  java: JavaInstallation;
+  /** 版本 JSON 要求的 Java 主版本 */
+  required_major: number;
+}
+
+/** 启动前检查问题严重程度：error 阻止启动，warning 仅提示 */
+export type DoctorSeverity = 'error' | 'warning';
+
+/** 启动前检查项 */
+export type DoctorCheck = 'java' | 'memory' | 'game_dir' | 'disk_space' | 'path' | 'loader' | 'jvm_profile';
+
+/** 单条启动前检查结果 */
+export interface DoctorIssue {
+  check: DoctorCheck;
+  severity: DoctorSeverity;
+  message: string;
+  /** 修复建议 */
+  suggestion: string | null;
+}
+
+/** 启动前检查报告 */
+export interface DoctorReport {
+  issues: DoctorIssue[];
+  /** 是否存在阻止启动的错误 */
+  blocking: boolean;
+  /** 检测到的 Java（路径无效或无法运行时为空） */
+  java?: JavaInstallation | null;
+}
+
+/** 崩溃分类 */
+export type CrashCategory =
+  | 'out_of_memory'
+  | 'java_version'
+  | 'missing_class'
+  | 'missing_dependency'
+  | 'mod_conflict'
+  | 'duplicate_mod'
+  | 'mixin_failure'
+  | 'graphics_driver'
+  | 'corrupt_files'
+  | 'jvm_fatal'
+  | 'unknown';
+
+/** 崩溃证据来源 */
+export type CrashSource = 'log' | 'crash_report' | 'hs_err';
+
+/** 崩溃证据行 */
+export interface CrashEvidence {
+  /** 来源 */
+  source: CrashSource;
+  /** 来源文本中的行号（从 1 开始） */
+  line: number;
+  /** 行文本 */
+  text: string;
+}
+
+/** 结构化崩溃诊断报告 */
+export interface CrashReport {
+  /** 崩溃分类 */
+  category: CrashCategory;
+  /** 命中的规则 id */
+  rule_id: string;
+  /** 置信度（0-1） */
+  confidence: number;
+  /** 可读摘要 */
+  summary: string;
+  /** 建议修复措施 */
+  suggested_fixes: string[];
+  /** 命中证据行 */
+  evidence: CrashEvidence[];
+  /** 疑似肇事模组 id */
+  culprit_mod: string | null;
+  /** 缺失 / 冲突的关联模组 id */
+  related_mod: string | null;
+  /** 缺失的类名 */
+  missing_class: string | null;
+  /** 需要的 Java 主版本 */
+  required_java: number | null;
+  /** 参与分析的崩溃报告文件 */
+  report_files: string[];
+  /** 资源监控的附加说明（如退出前 RSS 接近 -Xmx） */
+  resource_notes: string[];
+}
+
+/** 崩溃报告中的一节（`-- 标题 --`） */
+export interface CrashSection {
+  title: string;
+  lines: string[];
+}
+
+/** 崩溃报告 System Details 中的一项 */
+export interface CrashDetail {
+  key: string;
+  value: string;
+}
+
+/** 崩溃报告列出的已加载模组 */
+export interface CrashModEntry {
+  id: string;
+  name: string | null;
+  version: string | null;
+  /** 模组文件名（仅 Forge 提供） */
+  file: string | null;
+}
+
+/** 崩溃报告中的疑似模组 */
+export interface SuspectedMod {
+  id: string;
+  name: string;
+  version: string | null;
+}
+
+/** Minecraft 崩溃报告（crash-reports/*.txt） */
+export interface MinecraftCrashReport {
+  /** 报告文件路径 */
+  file: string;
+  time: string | null;
+  description: string | null;
+  /** 异常首行 */
+  exception: string | null;
+  /** 顶部堆栈 */
+  stack_trace: string[];
+  sections: CrashSection[];
+  suspected_mods: SuspectedMod[];
+  mod_list: CrashModEntry[];
+  system_details: CrashDetail[];
+}
+
+/** JVM 致命错误日志（hs_err_pid*.log） */
+export interface JvmFatalReport {
+  /** 日志文件路径 */
+  file: string;
+  /** 信号 / 异常名（SIGSEGV、EXCEPTION_ACCESS_VIOLATION 等） */
+  signal: string | null;
+  signal_code: string | null;
+  pc: string | null;
+  pid: number | null;
+  tid: number | null;
+  /** 错误描述首行 */
+  error_message: string | null;
+  jre_version: string | null;
+  java_vm: string | null;
+  /** 出错栈帧原文 */
+  problematic_frame: string | null;
+  /** 栈帧类型：C 原生 / V 虚拟机 / j 解释 / J 编译 / v 桩代码 */
+  frame_type: string | null;
+  /** 出错的原生库 */
+  native_library: string | null;
+  /** 出错时的 Java 线程名 */
+  current_thread: string | null;
+  command_line: string | null;
+}
+
+/** 单次游戏启动对应的崩溃文件解析结果 */
+export interface GameCrashDetails {
+  crash_report: MinecraftCrashReport | null;
+  jvm_fatal: JvmFatalReport | null;
 }
 
+/** 单次启动会话记录 */
+export interface LaunchSession {
+  /** 会话 ID（即启动时生成的游戏 ID） */
+  session_id: string;
+  game_name: string;
+  version: string;
+  /** 模组加载器类型（vanilla/fabric/forge 等） */
+  loader_type: string | null;
+  loader_version: string | null;
+  java_path: string;
+  memory_mb: number;
+  username: string;
+  account_type: string | null;
+  /** 开始时间（Unix 秒） */
+  started_at: number;
+  /** 结束时间（Unix 秒，未结束为 null） */
+  ended_at: number | null;
+  /** 结束时的状态 */
+  status: LaunchStatus;
+  exit_code: number | null;
+  crash_summary: string | null;
+  /** 游戏输出日志文件路径 */
+  log_file: string | null;
+}
+
+/** 启动历史查询结果 */
+export interface LaunchHistory {
+  /** 会话列表（按开始时间倒序） */
+  sessions: LaunchSession[];
+  /** 总游戏时长（秒） */
+  total_play_seconds: number;
+}
+
+/** 快速游戏：启动后直接进入服务器 / 单人世界 / Realms（1.20 之前的版本仅支持多人服务器） */
+export type QuickPlay =
+  | { type: 'multiplayer'; host: string; port?: number }
+  | { type: 'singleplayer'; world: string }
+  | { type: 'realms'; realm_id: string };
+
+/** JVM 调优配置：默认 / G1 Aikar 参数（Java 8+）/ 分代 ZGC（Java 21+）/ Shenandoah（Java 17+，非 Oracle 构建） */
+export type JvmProfile = 'none' | 'g1_aikar' | 'zgc_generational' | 'shenandoah';
+
 /** 游戏启动配置 */
 export interface LaunchConfig {
-  /** Java 可执行文件路径 */
+  /** Java 可执行文件路径（"auto" 为按版本要求自动选择） */
   java_path: string;
-  /** 分配内存大小（MB） */
+  /** 分配内存大小（MB，-Xmx；auto_memory 时由启动器计算） */
   memory_mb: number;
+  /** 初始堆大小（MB，-Xms，不超过 memory_mb） */
+  min_memory_mb?: number;
+  /** 自动内存：按物理内存、当前占用、加载器与模组数量计算 memory_mb */
+  auto_memory?: boolean;
+  /** JVM 调优配置（自定义 JVM 参数已指定垃圾回收器时不附加） */
+  jvm_profile?: JvmProfile;
   /** Minecraft 版本号 */
   version: string;
   /** 游戏目录路径（.minecraft 根目录） */
@@ -91,7 +394,7 @@ export interface LaunchConfig {
   version_type?: string;
   /** 用户属性 JSON（--userProperties） */
   user_properties?: string;
-  /** natives 解压目录（默认 {game_dir}/versions/{version}/natives） */
+  /** natives 解压目录（不指定时按版本原生库集合使用独立目录，游戏退出后清理） */
   natives_dir?: string;
   /** 账户类型（microsoft/offline/thirdparty） */
   account_type?: string;
@@ -107,4 +410,107 @@ export interface LaunchConfig {
   fullscreen?: boolean;
   /** 启动游戏后启动器窗口是否保持可见（false 时启动后隐藏，游戏退出后恢复） */
   launcher_visible?: boolean;
+  /** 使用 log4j XML 配置启动并解析结构化日志（默认 true） */
+  xml_log_layout?: boolean;
+  /** 自动选择 Java 时优先 JDK（默认优先 JRE） */
+  prefer_jdk?: boolean;
+  /** 停止游戏时等待正常关闭的宽限期（秒，默认 15），超时后强制结束 */
+  stop_grace_secs?: number;
+  /** 快速游戏目标 */
+  quick_play?: QuickPlay;
+  /** 启动包装命令（如 gamemoderun / mangohud / taskset -c 0-3，置于 java 之前） */
+  wrapper_command?: string[];
+  /** 附加环境变量（在继承的环境之上设置） */
+  env_vars?: Record<string, string>;
+  /** 启动前命令（失败则终止启动） */
+  pre_launch_command?: string;
+  /** 退出后命令（输出追加到游戏日志） */
+  post_exit_command?: string;
+  /** 启动前 / 退出后命令的超时（秒，默认 300），超时后结束命令 */
+  hook_timeout_secs?: number;
+  /** 资源限制（仅 Linux cgroups v2，经 systemd 用户会话创建 scope，不可用时不生效） */
+  resource_limits?: boolean;
+  /** CPU 权重（1-10000，默认 100） */
+  cpu_weight?: number;
+  /** 最大任务（线程）数（默认 4096） */
+  pids_max?: number;
+}
+
+/** 启动脚本类型 */
+export type ScriptKind = 'sh' | 'bat' | 'ps1';
+
+/** 启动预览（dry-run 结果，访问令牌已隐藏） */
+export interface LaunchPreview {
+  /** 主类 */
+  main_class: string;
+  /** 实际使用的 Java（auto 已解析） */
+  java_path: string;
+  /** 完整命令行（含包装命令与 java） */
+  argv: string[];
+  /** 工作目录（游戏目录） */
+  working_dir: string;
+  /** 附加环境变量（在继承的环境之上设置） */
+  env: Record<string, string>;
+  /** 原生库目录 */
+  natives_dir: string;
+  /** 已写入的启动脚本路径 */
+  script_path?: string | null;
+}
+
+/** 启动进度事件（launch-progress） */
+export interface LaunchProgressEvent {
+  game_id: string;
+  /** 真实进度（0-100） */
+  progress: number;
+  /** 当前阶段文案 */
+  stage: string;
+}
+
+/** 启动状态变化事件（launch-status-changed），附完整状态快照 */
+export interface LaunchStatusEvent extends LaunchStatusInfo {
+  game_id: string;
+}
+
+/** 游戏日志事件（game-log-lines，约每 200ms 一批） */
+export interface GameLogLinesEvent {
+  game_id: string;
+  /** 首行的绝对编号（与上一批 offset 不连续时应用 get_game_log 补齐） */
+  from: number;
+  /** 下次拉取的游标位置 */
+  offset: number;
+  lines: GameLogLine[];
+}
+
+/** 单次资源采样 */
+export interface ResourceSample {
+  /** 采样时间（Unix 毫秒） */
+  timestamp: number;
+  /** 进程已运行时长（秒） */
+  uptime_secs: number;
+  /** 常驻内存（字节） */
+  rss_bytes: number;
+  /** CPU 占用（百分比，单核满载为 100，多核可超过 100） */
+  cpu_percent: number;
+  /** 线程数 */
+  threads: number;
+}
+
+/** 指定游戏的资源采样序列 */
+export interface ResourceSeries {
+  game_id: string;
+  /** 采样的进程 PID */
+  pid: number | null;
+  /** 最大堆内存（字节，-Xmx） */
+  heap_max_bytes: number;
+  /** 采样间隔（毫秒） */
+  interval_ms: number;
+  /** 是否仍在采样 */
+  active: boolean;
+  /** 采样序列（按时间升序） */
+  samples: ResourceSample[];
+}
+
+/** 资源采样事件（game-resource-sample） */
+export interface GameResourceEvent extends ResourceSample {
+  game_id: string;
 }
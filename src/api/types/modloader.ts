@@ -10,6 +10,8 @@ export enum ModLoaderType {
   NeoForge = "NeoForge",
   /** Quilt */
   Quilt = "Quilt",
+  /** Legacy Fabric（1.13 以前版本的 Fabric 移植） */
+  LegacyFabric = "LegacyFabric",
   /** OptiFine 优化模组 */
   OptiFine = "OptiFine",
 }
@@ -40,6 +40,8 @@ const getLoaderLabel = (type: ModLoaderType): string => {
     Fabric: 'Fabric',
     Forge: 'Forge',
     NeoForge: 'NeoForge',
+    Quilt: 'Quilt',
+    LegacyFabric: 'Legacy Fabric',
   };
   return labels[type] || type.toString();
 };
@@ -198,6 +200,7 @@ const GameListItem = ({
                   />
                   {getLoaderLabel(game.loader_type)}
                   {game.loader_version && ` ${game.loader_version}`}
+                  {game.optifine?.mode === 'mod' && ' + OptiFine'}
                 </motion.span>
               )}
             </div>
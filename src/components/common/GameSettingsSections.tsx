@@ -5,6 +5,7 @@ import { Slider } from './Slider';
 import PartitionBar, { getPartitionColor } from './PartitionBar';
 import { useGameSettingsForm, formatMemory } from '@/hooks/useGameSettingsForm';
 import type { GameSettings } from '@/helper/rustInvoke';
+import type { JvmProfile } from '@/api/types/launch';
 
 /**
  * 游戏设置共用区块：Java 配置 / 内存分配 / 窗口配置。
@@ -46,6 +47,15 @@ const GameSettingsSections = ({
     handleBrowseJava,
   } = form;
 
+  // JVM 调优配置（启动前检查会校验 Java 版本与发行版）
+  const jvmProfileOptions: { id: JvmProfile; label: string }[] = [
+    { id: 'none', label: t('settings.jvmProfile.none', '默认') },
+    { id: 'g1_aikar', label: t('settings.jvmProfile.g1Aikar', 'G1 (Aikar)') },
+    { id: 'zgc_generational', label: t('settings.jvmProfile.zgc', '分代 ZGC（Java 21+）') },
+    { id: 'shenandoah', label: t('settings.jvmProfile.shenandoah', 'Shenandoah（Java 17+）') },
+  ];
+  const currentJvmProfile = settings.jvm_profile ?? 'none';
+
   return (
     <>
       {/* Java 配置 */}
@@ -132,6 +142,32 @@ const GameSettingsSections = ({
             ]}
             className='mb-3'
           />
+
+          <SettingsPanel.Row
+            label={t('settings.jvmProfile.title', 'GC 调优')}
+            description={t('settings.jvmProfile.desc', '自定义 JVM 参数已指定垃圾回收器时不生效')}
+          >
+            <SettingsPanel.DropDown
+              label={''}
+              options={jvmProfileOptions}
+              value={jvmProfileOptions.find((o) => o.id === currentJvmProfile)}
+              onSelect={(option) => updateSetting('jvm_profile', option.id as JvmProfile)}
+            />
+          </SettingsPanel.Row>
+
+          <SettingsPanel.Row
+            label={t('settings.resourceLimits.title', '限制资源占用')}
+            description={t('settings.resourceLimits.desc', '仅 Linux（cgroups v2）：内存上限为分配内存加堆外余量，多开时互不抢占')}
+          >
+            <Toggle
+              checked={settings.resource_limits ?? false}
+              onChange={(checked) => updateSetting('resource_limits', checked)}
+              disabled={false}
+              hoverable={false}
+              bgHidden
+              variant="item"
+            />
+          </SettingsPanel.Row>
         </SettingsPanel.Sub>
       </SettingsPanel.Item>
 
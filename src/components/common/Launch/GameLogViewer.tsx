@@ -1,6 +1,6 @@
 import { useEffect, useMemo, useRef, useState } from 'react';
 import { ChevronDown, ChevronUp, Copy, Search, Loader2 } from 'lucide-react';
-import { getGameLog } from '@/helper/rustInvoke';
+import { getGameLog, listenGameLogLines } from '@/helper/rustInvoke';
 import type { GameLogLine, GameLogLevel } from '@/api';
 import DropDown, { type DropDownOption } from '../DropDown';
 import CheckSwitch from '../CheckSwitch';
@@ -9,15 +9,14 @@ import CheckSwitch from '../CheckSwitch';
 export interface GameLogViewerProps {
   /** 游戏会话唯一 ID */
   gameId: string;
-  /** 是否自动轮询增量日志 */
+  /** 是否实时接收增量日志（监听 game-log-lines 事件） */
   live?: boolean;
-  /** 轮询间隔（ms，默认 1500） */
-  pollInterval?: number;
 }
 
 /** 单行日志等级配色 */
 const LEVEL_OPTIONS: DropDownOption[] = [
   { id: 'all', label: '全部等级' },
+  { id: 'debug', label: 'DEBUG' },
   { id: 'info', label: 'INFO' },
   { id: 'warn', label: 'WARN' },
   { id: 'error', label: 'ERROR' },
@@ -25,14 +24,15 @@ const LEVEL_OPTIONS: DropDownOption[] = [
 ];
 
 const LEVEL_STYLE: Record<GameLogLevel, string> = {
+  debug: 'text-[var(--color-text-tertiary)]',
   info: 'text-[var(--color-text-secondary)]',
   warn: 'text-[var(--color-warning)]',
   error: 'text-[var(--color-error)]',
   fatal: 'text-[var(--color-error)] font-semibold',
 };
 
-/** 游戏日志查看器：增量轮询捕获日志，支持等级/关键词过滤、复制与自动滚底 */
-const GameLogViewer = ({ gameId, live = false, pollInterval = 1500 }: GameLogViewerProps) => {
+/** 游戏日志查看器：拉取已捕获日志并接收推送的增量日志，支持等级/关键词过滤、复制与自动滚底 */
+const GameLogViewer = ({ gameId, live = false }: GameLogViewerProps) => {
   const [lines, setLines] = useState<GameLogLine[]>([]);
   const [keyword, setKeyword] = useState('');
   const [levelFilter, setLevelFilter] = useState<GameLogLevel | 'all'>('all');
@@ -49,34 +49,66 @@ const GameLogViewer = ({ gameId, live = false, pollInterval = 1500 }: GameLogVie
   useEffect(() => {
     let cancelled = false;
 
-    // 拉取 log 日志
-    const fetchBatch = async (from: number) => {
+    const append = (batch: GameLogLine[], offset: number) => {
+      if (batch.length === 0 || offset <= offsetRef.current) return;
+      // 只追加游标之后的部分（拉取结果与推送批次可能重叠）
+      const fresh = batch.slice(Math.max(0, batch.length - (offset - offsetRef.current)));
+      offsetRef.current = offset;
+      setLines(prev => {
+        const merged = [...prev, ...fresh];
+        return merged.length > 5000 ? merged.slice(merged.length - 5000) : merged;
+      });
+    };
+
+    // 拉取 log 日志（游标早于缓冲时后端从磁盘分页，拉到没有新行为止）
+    const fetchFrom = async (from: number) => {
       try {
-        const result = await getGameLog(gameId, from);
-        if (cancelled) return;
-        if (result.lines.length > 0) {
-          setLines(prev => {
-            const merged = prev.some(l => result.lines[0] && l.text === result.lines[0].text && l.level === result.lines[0].level)
-              ? prev
-              : [...prev, ...result.lines];
-            return merged.length > 5000 ? merged.slice(merged.length - 5000) : merged;
-          });
-          offsetRef.current = result.offset;
+        let cursor = from;
+        for (;;) {
+          const result = await getGameLog(gameId, cursor);
+          if (cancelled || result.lines.length === 0) return;
+          append(result.lines, result.offset);
+          cursor = result.offset;
         }
       } catch {
-        // 忽略拉取错误，下轮重试
+        // 忽略拉取错误，下一批事件到达时重试
       }
     };
 
-    fetchBatch(offsetRef.current);
+    // 同一时间只有一个拉取任务；拉取期间到达的事件在结束后再补拉一轮
+    let fetching: Promise<void> | null = null;
+    let pending = false;
+    const catchUp = () => {
+      if (fetching) {
+        pending = true;
+        return;
+      }
+      fetching = fetchFrom(offsetRef.current).finally(() => {
+        fetching = null;
+        if (pending && !cancelled) {
+          pending = false;
+          catchUp();
+        }
+      });
+    };
 
+    catchUp();
     if (!live) return () => { cancelled = true; };
-    const timer = setInterval(() => fetchBatch(offsetRef.current), pollInterval);
+
+    const unlisten = listenGameLogLines(event => {
+      if (event.game_id !== gameId || cancelled) return;
+      if (fetching || event.from > offsetRef.current) {
+        // 尚在补齐或批次不连续（有行已移出推送缓冲）：改为按游标拉取
+        catchUp();
+        return;
+      }
+      append(event.lines, event.offset);
+    });
     return () => {
       cancelled = true;
-      clearInterval(timer);
+      unlisten.then(fn => fn());
     };
-  }, [gameId, live, pollInterval]);
+  }, [gameId, live]);
 
   useEffect(() => {
     if (autoScroll && boxRef.current) {
@@ -87,13 +119,16 @@ const GameLogViewer = ({ gameId, live = false, pollInterval = 1500 }: GameLogVie
   const filtered = useMemo(() => {
     return lines.filter(l => {
       if (levelFilter !== 'all' && l.level !== levelFilter) return false;
-      if (keyword && !l.text.toLowerCase().includes(keyword.toLowerCase())) return false;
+      if (keyword) {
+        const kw = keyword.toLowerCase();
+        if (!l.text.toLowerCase().includes(kw) && !l.throwable?.toLowerCase().includes(kw)) return false;
+      }
       return true;
     });
   }, [lines, keyword, levelFilter]);
 
   const handleCopy = async () => {
-    const text = filtered.map(l => l.text).join('\n');
+    const text = filtered.map(l => (l.throwable ? `${l.text}\n${l.throwable}` : l.text)).join('\n');
     if (text) {
       await navigator.clipboard.writeText(text);
     }
@@ -171,6 +206,7 @@ const GameLogViewer = ({ gameId, live = false, pollInterval = 1500 }: GameLogVie
             filtered.map((line, i) => (
               <div key={i} className={`whitespace-pre-wrap break-all ${LEVEL_STYLE[line.level]}`}>
                 {line.text}
+                {line.throwable && `\n${line.throwable}`}
               </div>
             ))
           )}
@@ -1,10 +1,10 @@
 import { useEffect, useMemo, useRef, useState } from 'react';
 import { Loader2, Gamepad2, Square, RotateCcw, Info, Lightbulb, Cpu, MemoryStick, User, FolderOpen, AlertTriangle } from 'lucide-react';
-import { getLaunchStatusByKey, stopGame, openFolder, getGameSettings, getGlobalGameSettings } from '@/helper/rustInvoke';
+import { getLaunchStatusByKey, listenLaunchProgress, listenLaunchStatus, stopGame, openFolder, getGameSettings, getGlobalGameSettings } from '@/helper/rustInvoke';
 import { AccountInfo, LaunchStatus, type Game, type GameSettings, type LaunchStatusInfo } from '@/api';
 import { useAppStore } from '@/stores/appStore';
 import { useLaunchHints } from '@/hooks/useLaunchHints';
-import { ProgressBar, GameLogViewer, Page, PageSection } from '@/components/common';
+import { ProgressBar, GameLogViewer, Page, PageSection, ResourceMonitor } from '@/components/common';
 import { Z_INDEX } from '@/utils/zIndex';
 import pkg from '../../../../package.json';
 
@@ -20,9 +20,6 @@ export interface LaunchingOverlayProps {
   onExit: () => void;
 }
 
-/** 状态轮询间隔（ms，真实进度由后端上报） */
-const POLL_INTERVAL = 500;
-
 /** 启动覆盖层：展示后端真实启动进度 / 运行状态 / 关于 / 小贴士 */
 const LaunchingOverlay = ({ gameId, game, accountInfo, onExit }: LaunchingOverlayProps) => {
   const [phase, setPhase] = useState<'launching' | 'running' | 'crashed'>('launching');
@@ -55,48 +52,59 @@ const LaunchingOverlay = ({ gameId, game, accountInfo, onExit }: LaunchingOverla
     };
   }, [game.name]);
 
-  // 轮询游戏真实状态与进度
+  // 订阅游戏状态与进度事件（先拉取一次快照，补齐订阅前的变化）
   useEffect(() => {
     if (phase === 'crashed') return;
-    const poll = async () => {
-      try {
-        const result = await getLaunchStatusByKey(gameId);
-        setInfo(result);
-        if (result.status === LaunchStatus.Running) {
-          setPhase('running');
-        }
-        else if (result.status === LaunchStatus.Crashed) {
-          setPhase('crashed');
-        }
-        // 这个分支好像没啥用，不知道是那里的问题，游戏窗口出来后立马关闭游戏之后反而没有出发这个分支
-        else if (
-          result.status === LaunchStatus.Stopped &&
-          phase === 'launching' &&
-          !stoppedRef.current
-        ) {
-          // 启动后立即退出（如进程秒退或被取消），视为异常
-          setPhase('crashed');
-        }
+    let cancelled = false;
+    const apply = (result: LaunchStatusInfo) => {
+      if (cancelled) return;
+      setInfo(result);
+      if (result.status === LaunchStatus.Running) {
+        setPhase('running');
+      }
+      else if (result.status === LaunchStatus.Crashed) {
+        setPhase('crashed');
+      }
+      else if (
+        result.status === LaunchStatus.Stopped &&
+        phase === 'launching' &&
+        !stoppedRef.current
+      ) {
+        // 启动后立即退出（如进程秒退或被取消），视为异常
+        setPhase('crashed');
+      }
 
-        else if (result.status === LaunchStatus.Stopped && phase === 'running') {
-          // 游戏进程已退出，覆盖层使命完成，自动返回主页
-          onExitRef.current();
-        }
-      } catch (e) {
-        // 忽略轮询错误，保持当前状态
-        console.warn("轮询错误：", e);
+      else if (result.status === LaunchStatus.Stopped && phase === 'running') {
+        // 游戏进程已退出，覆盖层使命完成，自动返回主页
+        onExitRef.current();
       }
     };
-    poll();
-    const timer = setInterval(poll, POLL_INTERVAL);
-    return () => clearInterval(timer);
+
+    const unlisteners = [
+      listenLaunchStatus(event => {
+        if (event.game_id === gameId) apply(event);
+      }),
+      listenLaunchProgress(event => {
+        if (event.game_id !== gameId || cancelled) return;
+        setInfo(prev => (prev ? { ...prev, progress: event.progress, stage: event.stage } : prev));
+      }),
+    ];
+    getLaunchStatusByKey(gameId)
+      .then(apply)
+      .catch(e => console.warn("获取启动状态失败：", e));
+
+    return () => {
+      cancelled = true;
+      unlisteners.forEach(p => p.then(unlisten => unlisten()));
+    };
   }, [gameId, phase]);
 
   const progress = phase === 'running' ? 100 : phase === 'crashed' ? 100 : (info?.progress ?? 0);
   const stage = info?.stage || (phase === 'launching' ? '正在准备启动环境' : '');
 
   const settings = effectiveSettings;
-  const javaPath = settings.java_path || 'java';
+  // 自动选择模式下展示后端选中的 Java
+  const javaPath = info?.java_selection?.java.path ?? (settings.java_path || '自动选择');
   const memoryMb = settings.max_memory || 2048;
 
   const handleStop = async () => {
@@ -185,6 +193,9 @@ const LaunchingOverlay = ({ gameId, game, accountInfo, onExit }: LaunchingOverla
                   </div>
                 </div>
               )}
+              {info?.crash_report?.resource_notes?.map(note => (
+                <p key={note} className="text-xs text-[var(--color-warning)]">{note}</p>
+              ))}
               <GameLogViewer gameId={gameId} live />
               <button
                 onClick={() => openFolder(game.path)}
@@ -203,6 +214,11 @@ const LaunchingOverlay = ({ gameId, game, accountInfo, onExit }: LaunchingOverla
               <span className="text-sm text-[var(--color-success)]">游戏运行中</span>
             </PageSection>
           )}
+          {phase === 'running' && (
+            <PageSection className="mb-3">
+              <ResourceMonitor gameId={gameId} />
+            </PageSection>
+          )}
 
           {/* 启动信息 */}
           <PageSection className="grid grid-cols-2 gap-3 mb-5">
@@ -0,0 +1,105 @@
+import { useEffect, useState } from 'react';
+import { getGameResources, listenGameResources } from '@/helper/rustInvoke';
+import type { ResourceSample } from '@/api';
+
+/** 资源监控 Props */
+export interface ResourceMonitorProps {
+  /** 游戏会话唯一 ID */
+  gameId: string;
+}
+
+/** 图表保留的采样数（与后端保留的序列长度一致） */
+const MAX_SAMPLES = 300;
+
+const toMb = (bytes: number) => Math.round(bytes / 1024 / 1024);
+
+const formatUptime = (secs: number) => {
+  const h = Math.floor(secs / 3600);
+  const m = Math.floor((secs % 3600) / 60);
+  const s = secs % 60;
+  return h > 0 ? `${h}:${String(m).padStart(2, '0')}:${String(s).padStart(2, '0')}` : `${m}:${String(s).padStart(2, '0')}`;
+};
+
+/** 折线图：values 按 max 归一化到 viewBox 高度 */
+const Sparkline = ({ values, max, color }: { values: number[]; max: number; color: string }) => {
+  if (values.length < 2 || max <= 0) {
+    return <div className="h-12" />;
+  }
+  const step = 100 / (values.length - 1);
+  const points = values
+    .map((v, i) => `${(i * step).toFixed(2)},${(40 - Math.min(v / max, 1) * 40).toFixed(2)}`)
+    .join(' ');
+  return (
+    <svg viewBox="0 0 100 40" preserveAspectRatio="none" className="w-full h-12">
+      <polyline points={points} fill="none" stroke={color} strokeWidth="1.5" vectorEffect="non-scaling-stroke" />
+    </svg>
+  );
+};
+
+/** 运行中游戏的资源监控：拉取已有采样并接收推送的新采样，绘制内存 / CPU 折线 */
+const ResourceMonitor = ({ gameId }: ResourceMonitorProps) => {
+  const [samples, setSamples] = useState<ResourceSample[]>([]);
+  const [heapMax, setHeapMax] = useState(0);
+
+  useEffect(() => {
+    let cancelled = false;
+    setSamples([]);
+    getGameResources(gameId)
+      .then(series => {
+        if (cancelled) return;
+        setHeapMax(series.heap_max_bytes);
+        // 拉取期间已推送的采样按时间戳去重合并
+        const last = series.samples[series.samples.length - 1]?.timestamp ?? 0;
+        setSamples(prev => [...series.samples, ...prev.filter(s => s.timestamp > last)]);
+      })
+      .catch(() => {
+        // 拉取失败时仅展示后续推送的采样
+      });
+    const unlisten = listenGameResources(({ game_id, ...sample }) => {
+      if (game_id !== gameId) return;
+      setSamples(prev => {
+        const merged = [...prev, sample];
+        return merged.length > MAX_SAMPLES ? merged.slice(merged.length - MAX_SAMPLES) : merged;
+      });
+    });
+    return () => {
+      cancelled = true;
+      unlisten.then(fn => fn());
+    };
+  }, [gameId]);
+
+  const latest = samples[samples.length - 1];
+  if (!latest) return null;
+
+  const rss = samples.map(s => s.rss_bytes);
+  const cpu = samples.map(s => s.cpu_percent);
+  const rssMax = Math.max(heapMax, ...rss);
+  const cpuMax = Math.max(100, ...cpu);
+
+  return (
+    <div className="grid grid-cols-2 gap-3">
+      <div className="rounded-(--radius-sm) bg-(--color-surface) px-4 py-3">
+        <div className="flex items-baseline justify-between text-xs text-[var(--color-text-tertiary)]">
+          <span>内存 (RSS)</span>
+          <span className="text-sm text-[var(--color-text-primary)]">
+            {toMb(latest.rss_bytes)} MB{heapMax > 0 && ` / ${toMb(heapMax)} MB`}
+          </span>
+        </div>
+        <Sparkline values={rss} max={rssMax} color="var(--color-primary)" />
+      </div>
+      <div className="rounded-(--radius-sm) bg-(--color-surface) px-4 py-3">
+        <div className="flex items-baseline justify-between text-xs text-[var(--color-text-tertiary)]">
+          <span>CPU</span>
+          <span className="text-sm text-[var(--color-text-primary)]">{latest.cpu_percent.toFixed(0)}%</span>
+        </div>
+        <Sparkline values={cpu} max={cpuMax} color="var(--color-warning)" />
+      </div>
+      <div className="col-span-2 flex justify-between text-xs text-[var(--color-text-tertiary)]">
+        <span>线程 {latest.threads}</span>
+        <span>已运行 {formatUptime(latest.uptime_secs)}</span>
+      </div>
+    </div>
+  );
+};
+
+export default ResourceMonitor;
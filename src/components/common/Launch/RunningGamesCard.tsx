@@ -1,18 +1,20 @@
-import { useCallback, useState } from 'react';
+import { useCallback, useEffect, useState } from 'react';
 import { AnimatePresence, motion } from 'framer-motion';
 import { Loader2, Square, ChevronUp, Settings } from 'lucide-react';
-import { getGame, getLaunchGames, stopGame } from '@/helper/rustInvoke';
+import { getGame, getLaunchGames, listenLaunchProgress, listenLaunchStatus, stopGame } from '@/helper/rustInvoke';
 import { LaunchStatus, type LaunchGameInfo } from '@/api';
 import { DURATION, EASING } from '@/utils/animations';
 import { Z_INDEX } from '@/utils/zIndex';
 import { useLaunchStore } from '@/stores/launchStore';
-import { usePolling } from '@/hooks/usePolling';
 import { UIMode, useUIModeStore } from '@/stores/uiModeStore';
 import useLayoutStore, { SIDEBAR_TRANSITION_DURATION } from '@/stores/layoutStore';
 import ContextMenu, { ContextMenuItemData, useContextMenu } from '../ContextMenu';
+import { formatMemory } from '@/hooks/useGameSettingsForm';
 
-/** 游戏轮询间隔（ms） */
-const POLL_INTERVAL = 2000;
+/** 展开时刷新资源占用的间隔（毫秒，仅有游戏启用资源限制时） */
+const RESOURCE_REFRESH_INTERVAL = 2000;
+
+const toMb = (bytes: number) => Math.round(bytes / 1024 / 1024);
 
 const STATUS_META: Record<LaunchStatus, { text: string; dot: string }> = {
   [LaunchStatus.Idle]: { text: '空闲', dot: 'bg-[var(--color-text-tertiary)]' },
@@ -74,11 +76,29 @@ const RunningGamesCard = () => {
         )
       );
     } catch {
-      // 忽略轮询错误，保持上次状态
+      // 忽略拉取错误，保持上次状态
     }
   };
 
-  usePolling(refresh, { interval: POLL_INTERVAL });
+  // 启动状态 / 进度事件到达时刷新列表（首次挂载先拉取一次）
+  useEffect(() => {
+    void refresh();
+    const unlisteners = [
+      listenLaunchStatus(() => void refresh()),
+      listenLaunchProgress(() => void refresh()),
+    ];
+    return () => {
+      unlisteners.forEach(p => p.then(unlisten => unlisten()));
+    };
+  }, []);
+
+  // 资源占用无事件推送：展开且有游戏启用资源限制时定时刷新
+  const hasResources = games.some((i) => i.resources);
+  useEffect(() => {
+    if (!expanded || !hasResources) return;
+    const timer = setInterval(() => void refresh(), RESOURCE_REFRESH_INTERVAL);
+    return () => clearInterval(timer);
+  }, [expanded, hasResources]);
 
   if (games.length === 0) return null;
 
@@ -88,7 +108,7 @@ const RunningGamesCard = () => {
       await stopGame(gameId);
       await refresh();
     } catch {
-      // 忽略停止失败，列表会轮询恢复
+      // 忽略停止失败，列表随状态事件恢复
     }
     setStoppingId(null);
   };
@@ -172,6 +192,11 @@ const RunningGamesCard = () => {
                       <div className="text-xs text-[var(--color-text-tertiary)] truncate">
                         {inst.username}
                         {inst.pid != null && ` · PID ${inst.pid}`}
+                        {inst.resources && ` · ${formatMemory(toMb(inst.resources.memory_bytes))}${
+                          inst.resources.memory_max_bytes != null
+                            ? ` / ${formatMemory(toMb(inst.resources.memory_max_bytes))}`
+                            : ''
+                        }`}
                       </div>
 
                       <button
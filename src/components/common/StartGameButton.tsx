@@ -37,7 +37,7 @@ const ActionButton = ({ className }: StartGameButtonProps) => {
         getGlobalGameSettings(),
       ]);
       const settings = gameSettings.use_game_settings ? gameSettings : globalSettings;
-      const javaPath = settings.java_path || 'java';
+      const javaPath = settings.java_path || 'auto';
       const maxMemory = settings.max_memory || 2048;
 
       const username = currentAccount?.name || 'Steve';
@@ -47,19 +47,31 @@ const ActionButton = ({ className }: StartGameButtonProps) => {
       const gameId = await launchGame({
         java_path: javaPath,
         memory_mb: maxMemory,
+        min_memory_mb: settings.min_memory,
+        auto_memory: settings.auto_memory,
+        jvm_profile: settings.jvm_profile,
         version: selectedGame.version_id,
         game_dir: selectedGame.path,
         assets_dir: `${selectedGame.path}/assets`,
-        natives_dir: `${selectedGame.path}/versions/${selectedGame.version_id}/natives`,
         username,
         uuid,
         account_type: accountType,
         jvm_args: settings.jvm_args || [],
+        wrapper_command: settings.wrapper_command || [],
+        env_vars: settings.env_vars || {},
+        pre_launch_command: settings.pre_launch_command,
+        post_exit_command: settings.post_exit_command,
+        resource_limits: settings.resource_limits,
+        cpu_weight: settings.cpu_weight,
+        pids_max: settings.pids_max,
         // 全屏时不传宽高（避免退出全屏后窗口恢复为异常尺寸）；非全屏时下限 640x480
         resolution_width: settings.fullscreen ? undefined : Math.max(settings.width ?? 640, 640),
         resolution_height: settings.fullscreen ? undefined : Math.max(settings.height ?? 480, 480),
         fullscreen: settings.fullscreen,
         launcher_visible: settings.launcher_visible,
+        quick_play: settings.server_address
+          ? { type: 'multiplayer', host: settings.server_address, port: settings.server_port }
+          : undefined,
       });
 
       useLaunchStore.getState().openOverlay({ gameId, game: selectedGame });
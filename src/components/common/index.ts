@@ -49,6 +49,11 @@ export { default as GameLogViewer } from './Launch/GameLogViewer';
 /** 游戏日志查看器 Props */
 export type { GameLogViewerProps } from './Launch/GameLogViewer';
 
+/** 游戏资源监控组件 */
+export { default as ResourceMonitor } from './Launch/ResourceMonitor';
+/** 游戏资源监控 Props */
+export type { ResourceMonitorProps } from './Launch/ResourceMonitor';
+
 /** 通知 Provider 及 useNotification Hook */
 export {
   NotificationProvider,
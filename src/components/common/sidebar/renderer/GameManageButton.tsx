@@ -34,6 +34,7 @@ const LOADER_ICONS: Record<ModLoaderType, React.ComponentType<{ className?: stri
   [ModLoaderType.NeoForge]: makeLoaderIcon(ModLoaderType.NeoForge),
   [ModLoaderType.Fabric]: makeLoaderIcon(ModLoaderType.Fabric),
   [ModLoaderType.Quilt]: makeLoaderIcon(ModLoaderType.Quilt),
+  [ModLoaderType.LegacyFabric]: makeLoaderIcon(ModLoaderType.LegacyFabric),
   [ModLoaderType.OptiFine]: makeLoaderIcon(ModLoaderType.OptiFine),
 };
 
@@ -22,6 +22,16 @@ export const getLaunchStatusByKey = __.invokeGetLaunchStatusByKey;
 export const getLaunchGames = __.invokeGetLaunchGames;
 /** 增量拉取指定游戏会话的捕获日志 */
 export const getGameLog = __.invokeGetGameLog;
+/** 监听启动进度事件 */
+export const listenLaunchProgress = __.listenLaunchProgress;
+/** 监听启动状态变化事件 */
+export const listenLaunchStatus = __.listenLaunchStatus;
+/** 监听游戏日志事件 */
+export const listenGameLogLines = __.listenGameLogLines;
+/** 获取指定游戏会话的资源采样序列 */
+export const getGameResources = __.invokeGetGameResources;
+/** 监听游戏资源采样事件 */
+export const listenGameResources = __.listenGameResources;
 
 /** 获取版本清单 */
 export const getVersionManifest = __.invokeGetVersionManifest;
@@ -33,6 +43,8 @@ export const cancelDownload = __.invokeCancelDownload;
 export const cancelVersionDownload = __.invokeCancelVersionDownload;
 /** 清除已完成的下载任务 */
 export const clearCompletedTasks = __.invokeClearCompletedTasks;
+/** 更换游戏加载器（失败自动回滚） */
+export const changeGameLoader = __.invokeChangeGameLoader;
 /** 下载并部署版本 */
 export const download = __.invokeDownload;
 
@@ -42,6 +54,10 @@ export const getFabricVersions = __.invokeGetFabricVersions;
 export const getForgeVersions = __.invokeGetForgeVersions;
 /** 获取 NeoForge 版本列表 */
 export const getNeoForgeVersions = __.invokeGetNeoForgeVersions;
+/** 获取 Quilt 版本列表 */
+export const getQuiltVersions = __.invokeGetQuiltVersions;
+/** 获取 Legacy Fabric 版本列表 */
+export const getLegacyFabricVersions = __.invokeGetLegacyFabricVersions;
 /** 获取 OptiFine 版本列表 */
 export const getOptifineVersions = __.invokeGetOptifineVersions;
 
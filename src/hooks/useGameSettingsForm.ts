@@ -103,6 +103,7 @@ export function useGameSettingsForm({
   // 内存自动分配开关：把推荐值真正写入设置（防抖落盘），保证启动时使用的是实际分配值
   const handleAutoMemoryChange = (checked: boolean) => {
     setAutoMemory(checked);
+    updateSetting('auto_memory', checked);
     if (checked) {
       updateSetting('min_memory', 1024);
       updateSetting('max_memory', recommendedMemory);
@@ -17,7 +17,7 @@ type StepState = 'done' | 'active' | 'pending';
 /** 每个分段的阶段归属（顺序即 UI 展示顺序） */
 const SEGMENT_PHASES: string[][] = [
   ['downloading_client'],
-  ['downloading_libraries', 'downloading_natives'],
+  ['downloading_libraries', 'downloading_natives', 'downloading_loader', 'installing_loader', 'installing_optifine'],
   ['downloading_assets', 'downloading_index', 'downloading_log_config'],
   ['validating'],
 ];
@@ -21,6 +21,8 @@ import {
   getForgeVersions,
   getNeoForgeVersions,
   getOptifineVersions,
+  getQuiltVersions,
+  getLegacyFabricVersions,
   ModLoaderType,
   type ModLoaderVersionItem,
 } from '@/helper/rustInvoke';
@@ -29,7 +31,7 @@ import { useDownloadStore } from '../../stores/downloadStore';
 import { DURATION, EASING } from '@/utils/animations';
 import { refreshAll } from '../../stores/refreshStore';
 import { useNavStore } from '../../stores/navStore';
-type LoaderKey = 'vanilla' | 'forge' | 'neoforge' | 'fabric' | 'optifine';
+type LoaderKey = 'vanilla' | 'forge' | 'neoforge' | 'fabric' | 'quilt' | 'legacyfabric' | 'optifine';
 
 interface LoaderEntry {
   key: LoaderKey;
@@ -46,9 +48,14 @@ const LOADER_LIST: LoaderEntry[] = [
   { key: 'forge', type: ModLoaderType.Forge, icon: loaderIcon(ModLoaderType.Forge) },
   { key: 'neoforge', type: ModLoaderType.NeoForge, icon: loaderIcon(ModLoaderType.NeoForge) },
   { key: 'fabric', type: ModLoaderType.Fabric, icon: loaderIcon(ModLoaderType.Fabric) },
+  { key: 'quilt', type: ModLoaderType.Quilt, icon: loaderIcon(ModLoaderType.Quilt) },
+  { key: 'legacyfabric', type: ModLoaderType.LegacyFabric, icon: loaderIcon(ModLoaderType.LegacyFabric) },
   { key: 'optifine', type: ModLoaderType.OptiFine, icon: loaderIcon(ModLoaderType.OptiFine) },
 ];
 
+/** 可与 OptiFine 组合的加载器（OptiFine 作为模组放入 mods/） */
+const OPTIFINE_HOSTS: ModLoaderType[] = [ModLoaderType.Forge, ModLoaderType.Fabric];
+
 const fetchLoaderVersions = async (
   key: LoaderKey,
   mcVersion: string
@@ -60,6 +67,10 @@ const fetchLoaderVersions = async (
       return (await getNeoForgeVersions(mcVersion)).versions;
     case 'fabric':
       return (await getFabricVersions(mcVersion)).versions;
+    case 'quilt':
+      return (await getQuiltVersions(mcVersion)).versions;
+    case 'legacyfabric':
+      return (await getLegacyFabricVersions(mcVersion)).versions;
     case 'optifine':
       return (await getOptifineVersions(mcVersion)).versions;
     default:
@@ -81,15 +92,18 @@ const VersionDetailWithInstall: React.FC = () => {
   const [gameName, setGameName] = useState('');
   const [expanded, setExpanded] = useState<LoaderKey | null>(null);
   const [selected, setSelected] = useState<{ type: ModLoaderType | null; version: string | null }>({ type: null, version: null });
+  const [optifineAddon, setOptifineAddon] = useState<string | null>(null);
   const [loaderState, setLoaderState] = useState<Record<LoaderKey, { loading: boolean; error: string | null; versions: ModLoaderVersionItem[] }>>({
     vanilla: { loading: false, error: null, versions: [] },
     forge: { loading: false, error: null, versions: [] },
     neoforge: { loading: false, error: null, versions: [] },
     fabric: { loading: false, error: null, versions: [] },
+    quilt: { loading: false, error: null, versions: [] },
+    legacyfabric: { loading: false, error: null, versions: [] },
     optifine: { loading: false, error: null, versions: [] },
   });
   const [starting, setStarting] = useState(false);
-  const loadedRef = useRef<Record<LoaderKey, boolean>>({ vanilla: true, forge: false, neoforge: false, fabric: false, optifine: false });
+  const loadedRef = useRef<Record<LoaderKey, boolean>>({ vanilla: true, forge: false, neoforge: false, fabric: false, quilt: false, legacyfabric: false, optifine: false });
 
   useEffect(() => {
     if (versionId) {
@@ -100,6 +114,7 @@ const VersionDetailWithInstall: React.FC = () => {
   const toggleLoader = useCallback((key: LoaderKey) => {
     if (key === 'vanilla') {
       setSelected({ type: null, version: null });
+      setOptifineAddon(null);
       return;
     }
     setExpanded(prev => (prev === key ? null : key));
@@ -128,9 +143,15 @@ const VersionDetailWithInstall: React.FC = () => {
   const handleSelectVersion = useCallback((key: LoaderKey, version: string) => {
     const entry = LOADER_LIST.find(l => l.key === key);
     if (!entry) return;
-    setSelected({ type: entry.type, version });
+    // 已选 Forge / Fabric 时 OptiFine 作为模组附加，否则作为独立版本安装
+    if (key === 'optifine' && selected.type !== null && OPTIFINE_HOSTS.includes(selected.type)) {
+      setOptifineAddon(version);
+    } else {
+      setSelected({ type: entry.type, version });
+      if (entry.type === null || !OPTIFINE_HOSTS.includes(entry.type)) setOptifineAddon(null);
+    }
     setExpanded(null);
-  }, []);
+  }, [selected.type]);
 
   const handleStart = useCallback(async () => {
     if (starting) return;
@@ -156,6 +177,7 @@ const VersionDetailWithInstall: React.FC = () => {
         version_id: versionId,
         loader_type: selected.type ?? ModLoaderType.Vanilla,
         loader_version: selected.version,
+        optifine_version: optifineAddon,
         target_existing_game: null,
       });
       await refreshAll();
@@ -165,7 +187,7 @@ const VersionDetailWithInstall: React.FC = () => {
       setStarting(false);
       notifyError(t('download.install.downloadFailed'), msg);
     }
-  }, [versionId, gameName, selected, starting, startDownloadProgress, errorDownloadProgress, info, notifyError, navigate, setCurrentPath, t]);
+  }, [versionId, gameName, selected, optifineAddon, starting, startDownloadProgress, errorDownloadProgress, info, notifyError, navigate, setCurrentPath, t]);
 
   return (
     <Page className="h-full flex flex-col overflow-hidden relative">
@@ -205,7 +227,9 @@ const VersionDetailWithInstall: React.FC = () => {
               {LOADER_LIST.map(entry => {
                 const isExpanded = expanded === entry.key;
                 const state = loaderState[entry.key];
-                const isSelected = entry.type === selected.type && (entry.type === null ? selected.version === null : selected.version !== null);
+                const isAddon = entry.key === 'optifine' && optifineAddon !== null;
+                const isSelected = isAddon || (entry.type === selected.type && (entry.type === null ? selected.version === null : selected.version !== null));
+                const selectedVersion = isAddon ? optifineAddon : selected.version;
                 const Icon = entry.icon;
 
                 return (
@@ -224,8 +248,8 @@ const VersionDetailWithInstall: React.FC = () => {
                         <span className="block text-xs text-[var(--color-text-tertiary)]">
                           {entry.key === 'vanilla'
                             ? t('download.install.loaderVanillaDesc')
-                            : isSelected && selected.version
-                              ? `${t('download.install.loaderSelected')}: ${selected.version}`
+                            : isSelected && selectedVersion
+                              ? `${t('download.install.loaderSelected')}: ${selectedVersion}`
                               : state.versions.length > 0
                                 ? t('download.install.loaderVersionCount', { count: state.versions.length })
                                 : ''}
@@ -278,7 +302,7 @@ const VersionDetailWithInstall: React.FC = () => {
                               ) : (
                                 <div className="max-h-56 overflow-y-auto pr-1 space-y-1">
                                   {state.versions.map(item => {
-                                    const active = isSelected && selected.version === item.version;
+                                    const active = isSelected && selectedVersion === item.version;
                                     return (
                                       <button
                                         key={item.version}
@@ -33,8 +33,8 @@ const GameGameSettings = () => {
   // 自动分配内存开关（显式状态，避免写入推荐值后开关自动弹回）
   const [autoMemory, setAutoMemory] = useState(
     initialData.loaded.use_game_settings
-      ? !initialData.loaded.min_memory && !initialData.loaded.max_memory
-      : !initialData.global.min_memory && !initialData.global.max_memory
+      ? initialData.loaded.auto_memory ?? (!initialData.loaded.min_memory && !initialData.loaded.max_memory)
+      : initialData.global.auto_memory ?? (!initialData.global.min_memory && !initialData.global.max_memory)
   );
 
   const isInitialLoad = useRef(true);
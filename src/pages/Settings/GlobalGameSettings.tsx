@@ -19,7 +19,7 @@ const GlobalGameSettings = () => {
   const [settings, setSettings] = useState<GameSettings>(routeData?.loaded ?? {});
   // 自动分配内存开关（显式状态，避免写入推荐值后开关自动弹回）
   const [autoMemory, setAutoMemory] = useState(
-    !routeData?.loaded?.min_memory && !routeData?.loaded?.max_memory
+    routeData?.loaded?.auto_memory ?? (!routeData?.loaded?.min_memory && !routeData?.loaded?.max_memory)
   );
 
   const isInitialLoad = useRef(true);
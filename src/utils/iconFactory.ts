@@ -37,6 +37,7 @@ const LOADER_BLOCK_ICONS: Record<ModLoaderType, string> = {
   [ModLoaderType.Forge]: BLOCK_ICONS.furnace,
   [ModLoaderType.NeoForge]: BLOCK_ICONS.blastFurnace,
   [ModLoaderType.Quilt]: BLOCK_ICONS.chorusFlower,
+  [ModLoaderType.LegacyFabric]: BLOCK_ICONS.loom,
   [ModLoaderType.OptiFine]: BLOCK_ICONS.glowstone,
 };
 
@@ -67,11 +67,11 @@ export const getJavaRequirement = (mcVersion: string): number => {
 export const LOADER_COMPATIBILITY: Record<string, { compatible: string[]; incompatible: string[] }> = {
   Forge: {
     compatible: ['OptiFine'],
-    incompatible: ['NeoForge', 'Fabric', 'Quilt'],
+    incompatible: ['NeoForge', 'Fabric', 'Quilt', 'LegacyFabric'],
   },
   NeoForge: {
     compatible: ['OptiFine'],
-    incompatible: ['Forge', 'Fabric', 'Quilt'],
+    incompatible: ['Forge', 'Fabric', 'Quilt', 'LegacyFabric'],
   },
   Fabric: {
     compatible: ['OptiFine', 'Quilt'],
@@ -81,6 +81,10 @@ export const LOADER_COMPATIBILITY: Record<string, { compatible: string[]; incomp
     compatible: ['Fabric', 'OptiFine'],
     incompatible: ['Forge', 'NeoForge'],
   },
+  LegacyFabric: {
+    compatible: ['OptiFine'],
+    incompatible: ['Forge', 'NeoForge', 'Fabric', 'Quilt'],
+  },
   OptiFine: {
     compatible: ['Forge', 'Fabric', 'NeoForge'],
     incompatible: [],